@@ -125,6 +125,67 @@ fn benchmark_game_logic(c: &mut Criterion) {
     group.finish();
 }
 
+fn benchmark_move_generation(c: &mut Criterion) {
+    use rustshogi::attack::{attacks_from, attacks_from_by_pattern};
+    use rustshogi::board::Board;
+    use rustshogi::color::ColorType;
+    use rustshogi::piece::PieceType;
+
+    let mut group = c.benchmark_group("Move Generation");
+
+    let mut board = Board::new();
+    board.startpos();
+    let board = black_box(board);
+    let pieces: Vec<u8> = board.has_piece.get_trues();
+
+    group.bench_function("able_move_squares_table", |b| {
+        b.iter(|| {
+            for &index in &pieces {
+                let _ = board.get_able_move_squares(index);
+            }
+        });
+    });
+
+    group.bench_function("able_move_squares_pattern", |b| {
+        b.iter(|| {
+            for &index in &pieces {
+                let _ = board.get_able_move_squares_by_pattern(index);
+            }
+        });
+    });
+
+    let sliders = [
+        PieceType::Rook,
+        PieceType::Bichop,
+        PieceType::Lance,
+        PieceType::Dragon,
+        PieceType::Horse,
+    ];
+    let occupied = board.has_piece;
+
+    group.bench_function("sliding_attacks_table", |b| {
+        b.iter(|| {
+            for piece_type in sliders {
+                for index in 12..109 {
+                    let _ = attacks_from(piece_type, ColorType::Black, index, occupied);
+                }
+            }
+        });
+    });
+
+    group.bench_function("sliding_attacks_pattern", |b| {
+        b.iter(|| {
+            for piece_type in sliders {
+                for index in 12..109 {
+                    let _ = attacks_from_by_pattern(piece_type, ColorType::Black, index, occupied);
+                }
+            }
+        });
+    });
+
+    group.finish();
+}
+
 fn benchmark_direction(c: &mut Criterion) {
     use rustshogi::direction::Direction;
     let mut group = c.benchmark_group("direction");
@@ -213,6 +274,7 @@ criterion_group!(
     benches,
     benchmark_bitboard_operations,
     benchmark_game_logic,
+    benchmark_move_generation,
     benchmark_direction,
     benchmark_piece,
     benchmark_random
@@ -0,0 +1,77 @@
+use rustshogi::evaluator::UniformEvaluator;
+use rustshogi::selfplay::SelfPlayConfig;
+use rustshogi::variant::Variant;
+
+use std::process::exit;
+
+const USAGE: &str = "usage: selfplay --output <path> [options]
+
+options:
+  --games <n>                number of games (default 1)
+  --threads <n>              worker threads (default: number of CPUs)
+  --simulations <n>          PUCT simulations per move (default 800)
+  --batch-size <n>           leaves per evaluation batch (default 16)
+  --c-puct <x>               exploration constant (default 1.5)
+  --dirichlet-alpha <x>      root noise concentration (default 0.15)
+  --dirichlet-epsilon <x>    root noise fraction (default 0.25)
+  --temperature <x>          sampling temperature (default 1.0)
+  --temperature-moves <n>    plies sampled with temperature (default 30)
+  --resign-threshold <x>     resign below this value (default: never)
+  --max-moves <n>            plies before a draw (default 512)
+  --variant <name>           standard, minishogi, judkins or gorogoro";
+
+fn parse<T: std::str::FromStr>(key: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid value for {}: {}", key, value))
+}
+
+fn run(args: &[String]) -> Result<(), String> {
+    let mut config = SelfPlayConfig::new();
+    let mut games = 1;
+    let mut threads = num_cpus::get();
+    let mut output: Option<String> = None;
+
+    let mut iter = args.iter();
+    while let Some(key) = iter.next() {
+        if key == "--help" || key == "-h" {
+            println!("{}", USAGE);
+            return Ok(());
+        }
+        let value = iter
+            .next()
+            .ok_or_else(|| format!("missing value for {}", key))?;
+        match key.as_str() {
+            "--output" => output = Some(value.clone()),
+            "--games" => games = parse(key, value)?,
+            "--threads" => threads = parse(key, value)?,
+            "--simulations" => config.search.simulations = parse(key, value)?,
+            "--batch-size" => config.search.batch_size = parse(key, value)?,
+            "--c-puct" => config.search.c_puct = parse(key, value)?,
+            "--dirichlet-alpha" => config.search.dirichlet_alpha = parse(key, value)?,
+            "--dirichlet-epsilon" => config.search.dirichlet_epsilon = parse(key, value)?,
+            "--temperature" => config.temperature = parse(key, value)?,
+            "--temperature-moves" => config.temperature_moves = parse(key, value)?,
+            "--resign-threshold" => config.resign_threshold = Some(parse(key, value)?),
+            "--max-moves" => config.max_moves = parse(key, value)?,
+            "--variant" => {
+                config.variant = Variant::from_name(value)
+                    .ok_or_else(|| format!("unknown variant: {}", value))?
+            }
+            _ => return Err(format!("unknown option: {}", key)),
+        }
+    }
+
+    let output = output.ok_or_else(|| "--output is required".to_string())?;
+    let summary = config.run_to_file(&UniformEvaluator, &output, games, threads)?;
+    println!("{}", summary.__repr__());
+    Ok(())
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Err(e) = run(&args) {
+        eprintln!("error: {}\n\n{}", e, USAGE);
+        exit(2);
+    }
+}
@@ -1,71 +1,153 @@
 #[path = "rustshogi/address.rs"]
 pub mod address;
+#[path = "rustshogi/attack.rs"]
+pub mod attack;
 #[path = "rustshogi/bitboard.rs"]
 pub mod bitboard;
+#[path = "rustshogi/bitboard81.rs"]
+pub mod bitboard81;
 #[path = "rustshogi/board.rs"]
 pub mod board;
+#[path = "rustshogi/budget.rs"]
+pub mod budget;
 #[path = "rustshogi/color.rs"]
 pub mod color;
 #[path = "rustshogi/common.rs"]
 pub mod common;
+#[path = "rustshogi/dfpn.rs"]
+pub mod dfpn;
 #[path = "rustshogi/direction.rs"]
 pub mod direction;
+#[path = "rustshogi/evaluator.rs"]
+pub mod evaluator;
+#[path = "rustshogi/feature.rs"]
+pub mod feature;
 #[path = "rustshogi/game.rs"]
 pub mod game;
 #[path = "rustshogi/hand.rs"]
 pub mod hand;
+#[path = "rustshogi/handicap.rs"]
+pub mod handicap;
 #[path = "rustshogi/mctsresult.rs"]
 pub mod mctsresult;
+#[path = "rustshogi/move_label.rs"]
+pub mod move_label;
+#[path = "rustshogi/move_list.rs"]
+pub mod move_list;
 #[path = "rustshogi/move_pattern.rs"]
 pub mod move_pattern;
+#[path = "rustshogi/move_picker.rs"]
+pub mod move_picker;
 #[path = "rustshogi/moves.rs"]
 pub mod moves;
+#[path = "rustshogi/packed_sfen.rs"]
+pub mod packed_sfen;
 #[path = "rustshogi/pca.rs"]
 pub mod pca;
 #[path = "rustshogi/piece.rs"]
 pub mod piece;
+#[path = "rustshogi/planes.rs"]
+pub mod planes;
+#[path = "rustshogi/puct.rs"]
+pub mod puct;
 #[path = "rustshogi/random.rs"]
 pub mod random;
+#[path = "rustshogi/rollout.rs"]
+pub mod rollout;
+#[path = "rustshogi/selfplay.rs"]
+pub mod selfplay;
+#[path = "rustshogi/variant.rs"]
+pub mod variant;
+#[path = "rustshogi/zobrist.rs"]
+pub mod zobrist;
 
 #[cfg(test)]
 #[path = "rustshogi/test_address.rs"]
 pub mod test_address;
 #[cfg(test)]
+#[path = "rustshogi/test_attack.rs"]
+pub mod test_attack;
+#[cfg(test)]
 #[path = "rustshogi/test_bitboard.rs"]
 pub mod test_bitboard;
 #[cfg(test)]
+#[path = "rustshogi/test_bitboard81.rs"]
+pub mod test_bitboard81;
+#[cfg(test)]
 #[path = "rustshogi/test_board.rs"]
 pub mod test_board;
 #[cfg(test)]
 #[path = "rustshogi/test_board_pca.rs"]
 pub mod test_board_pca;
 #[cfg(test)]
+#[path = "rustshogi/test_budget.rs"]
+pub mod test_budget;
+#[cfg(test)]
 #[path = "rustshogi/test_color.rs"]
 pub mod test_color;
 #[cfg(test)]
 #[path = "rustshogi/test_common.rs"]
 pub mod test_common;
 #[cfg(test)]
+#[path = "rustshogi/test_dfpn.rs"]
+pub mod test_dfpn;
+#[cfg(test)]
 #[path = "rustshogi/test_direction.rs"]
 pub mod test_direction;
 #[cfg(test)]
+#[path = "rustshogi/test_feature.rs"]
+pub mod test_feature;
+#[cfg(test)]
 #[path = "rustshogi/test_game.rs"]
 pub mod test_game;
 #[cfg(test)]
 #[path = "rustshogi/test_hand.rs"]
 pub mod test_hand;
 #[cfg(test)]
+#[path = "rustshogi/test_handicap.rs"]
+pub mod test_handicap;
+#[cfg(test)]
+#[path = "rustshogi/test_mctsresult.rs"]
+pub mod test_mctsresult;
+#[cfg(test)]
+#[path = "rustshogi/test_move_label.rs"]
+pub mod test_move_label;
+#[cfg(test)]
+#[path = "rustshogi/test_move_list.rs"]
+pub mod test_move_list;
+#[cfg(test)]
+#[path = "rustshogi/test_move_picker.rs"]
+pub mod test_move_picker;
+#[cfg(test)]
 #[path = "rustshogi/test_moves.rs"]
 pub mod test_moves;
 #[cfg(test)]
+#[path = "rustshogi/test_packed_sfen.rs"]
+pub mod test_packed_sfen;
+#[cfg(test)]
 #[path = "rustshogi/test_pca.rs"]
 pub mod test_pca;
 #[cfg(test)]
 #[path = "rustshogi/test_piece.rs"]
 pub mod test_piece;
 #[cfg(test)]
+#[path = "rustshogi/test_planes.rs"]
+pub mod test_planes;
+#[cfg(test)]
+#[path = "rustshogi/test_puct.rs"]
+pub mod test_puct;
+#[cfg(test)]
 #[path = "rustshogi/test_random.rs"]
 pub mod test_random;
+#[cfg(test)]
+#[path = "rustshogi/test_rollout.rs"]
+pub mod test_rollout;
+#[cfg(test)]
+#[path = "rustshogi/test_selfplay.rs"]
+pub mod test_selfplay;
+#[cfg(test)]
+#[path = "rustshogi/test_variant.rs"]
+pub mod test_variant;
 
 use pyo3::prelude::*;
 
@@ -76,9 +158,31 @@ fn rustshogi(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<piece::PieceType>()?;
     m.add_class::<piece::Piece>()?;
     m.add_class::<moves::Move>()?;
+    m.add_class::<moves::ExtendedMove>()?;
     m.add_class::<hand::Hand>()?;
     m.add_class::<board::Board>()?;
     m.add_class::<game::Game>()?;
+    m.add_class::<handicap::Handicap>()?;
+    m.add_class::<variant::Variant>()?;
     m.add_class::<mctsresult::MctsResult>()?;
+    m.add_class::<rollout::RolloutPolicyType>()?;
+    m.add_class::<rollout::RolloutConfig>()?;
+    m.add_class::<budget::SearchBudget>()?;
+    m.add_class::<budget::StopFlag>()?;
+    m.add_class::<dfpn::MateStatus>()?;
+    m.add_class::<dfpn::MateResult>()?;
+    m.add_class::<dfpn::DfPnSolver>()?;
+    m.add_class::<puct::PuctConfig>()?;
+    m.add_class::<puct::PuctResult>()?;
+    m.add_class::<selfplay::SelfPlayConfig>()?;
+    m.add_class::<selfplay::SelfPlaySummary>()?;
+    m.add_class::<packed_sfen::PackedSfenValue>()?;
+    m.add_class::<packed_sfen::Hcpe>()?;
+    m.add_class::<pca::PCASolver>()?;
+    m.add_class::<pca::PCATransform>()?;
+    m.add_class::<pca::IncrementalPCA>()?;
+    m.add_class::<feature::FeaturePlane>()?;
+    m.add_class::<feature::FeatureNormalization>()?;
+    m.add_class::<feature::FeatureExtractor>()?;
     Ok(())
 }
@@ -4,10 +4,32 @@ from ._core import (
     PieceType,
     Piece,
     Move,
+    ExtendedMove,
     Hand,
     Board,
     Game,
+    Handicap,
+    Variant,
     MctsResult,
+    RolloutPolicyType,
+    RolloutConfig,
+    SearchBudget,
+    StopFlag,
+    MateStatus,
+    MateResult,
+    DfPnSolver,
+    PuctConfig,
+    PuctResult,
+    SelfPlayConfig,
+    SelfPlaySummary,
+    PackedSfenValue,
+    Hcpe,
+    PCASolver,
+    PCATransform,
+    IncrementalPCA,
+    FeaturePlane,
+    FeatureNormalization,
+    FeatureExtractor,
 )
 
 __all__ = [
@@ -16,8 +38,30 @@ __all__ = [
     "PieceType",
     "Piece",
     "Move",
+    "ExtendedMove",
     "Hand",
     "Board",
     "Game",
+    "Handicap",
+    "Variant",
     "MctsResult",
+    "RolloutPolicyType",
+    "RolloutConfig",
+    "SearchBudget",
+    "StopFlag",
+    "MateStatus",
+    "MateResult",
+    "DfPnSolver",
+    "PuctConfig",
+    "PuctResult",
+    "SelfPlayConfig",
+    "SelfPlaySummary",
+    "PackedSfenValue",
+    "Hcpe",
+    "PCASolver",
+    "PCATransform",
+    "IncrementalPCA",
+    "FeaturePlane",
+    "FeatureNormalization",
+    "FeatureExtractor",
 ]
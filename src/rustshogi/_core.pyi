@@ -1,4 +1,8 @@
 from enum import Enum
+from typing import Callable, Sequence
+
+import numpy as np
+import numpy.typing as npt
 
 class Address:
     column: int
@@ -63,12 +67,30 @@ class Move:
     def __str__(self) -> str: ...
     def __eq__(self, value: object) -> bool: ...
     def __ne__(self, value: object) -> bool: ...
+    @staticmethod
+    def pass_move() -> Move: ...
+    @staticmethod
+    def from_label(label: int, board: Board, turn: ColorType) -> Move: ...
+    def to_label(self, turn: ColorType) -> int: ...
+    def mirrored(self, variant: Variant = Variant.Standard) -> Move: ...
+    def flipped(self, variant: Variant = Variant.Standard) -> Move: ...
     def is_drop(self) -> bool: ...
+    def is_pass(self) -> bool: ...
     def is_promote(self) -> bool: ...
     def get_from(self) -> Address: ...
     def get_to(self) -> Address: ...
     def get_piece(self) -> Piece: ...
 
+class ExtendedMove:
+    def __init__(self, mv: Move, board: Board) -> None: ...
+    def __repr__(self) -> str: ...
+    def __eq__(self, value: object) -> bool: ...
+    def to_move(self) -> Move: ...
+    def get_moved_piece(self) -> Piece: ...
+    def get_captured_piece(self) -> Piece: ...
+    def is_capture(self) -> bool: ...
+    def is_check(self) -> bool: ...
+
 class Hand:
     pieces: list[Piece]
     counts: list[int]
@@ -85,8 +107,27 @@ class Hand:
     def decrease_piece(self, color_type: ColorType, piece_type: PieceType) -> None: ...
     def get_player_pieces(self, color_type: ColorType) -> list[Piece]: ...
 
+class Variant(Enum):
+    Standard = 0
+    Minishogi = 1
+    Judkins = 2
+    GoroGoro = 3
+
+    @staticmethod
+    def from_name(name: str) -> Variant: ...
+    def __repr__(self) -> str: ...
+    @property
+    def name(self) -> str: ...
+    @property
+    def width(self) -> int: ...
+    @property
+    def height(self) -> int: ...
+    def sfen(self) -> str: ...
+
 class Board:
-    def __init__(self, sfen: str = "startpos") -> None: ...
+    def __init__(
+        self, sfen: str = "startpos", variant: Variant = Variant.Standard
+    ) -> None: ...
     def __repr__(self) -> str: ...
     def __str__(self) -> str: ...
     def __eq__(self, value: object) -> bool: ...
@@ -94,12 +135,77 @@ class Board:
     def deploy(
         self, address: Address, piece_type: PieceType, color: ColorType
     ) -> None: ...
+    @staticmethod
+    def from_handicap(name: str) -> Board: ...
+    @property
+    def variant(self) -> Variant: ...
     def startpos(self) -> None: ...
     def get_piece(self, address: Address) -> Piece: ...
     def search_moves(self, color: ColorType) -> list[Move]: ...
+    def search_moves_ordered(self, color: ColorType) -> list[Move]: ...
+    def gives_check(self, mv: Move) -> bool: ...
+    def generate_checks(self, color: ColorType) -> list[Move]: ...
+    def generate_evasions(self, color: ColorType) -> list[Move]: ...
+    def is_legal(self, mv: Move) -> bool: ...
+    def perft(self, color: ColorType, depth: int) -> int: ...
+    def mirrored(self) -> Board: ...
+    def flipped(self) -> Board: ...
+    def to_packed_sfen(self, turn: ColorType) -> bytes: ...
+    @staticmethod
+    def from_packed_sfen(data: bytes) -> tuple[Board, ColorType]: ...
+    def to_hcp(self, turn: ColorType) -> bytes: ...
+    @staticmethod
+    def from_hcp(data: bytes) -> tuple[Board, ColorType]: ...
+    def legal_move_mask(self, turn: ColorType) -> npt.NDArray[np.bool_]: ...
+    def make_move(self, mv: Move) -> ExtendedMove: ...
+    def undo_move(self, mv: ExtendedMove) -> None: ...
     def execute_move(self, moves: Move) -> None: ...
     def is_finished(self) -> tuple[bool, ColorType]: ...
-    def to_vector(self, target_dimensions: int | None = None) -> list[float]: ...
+    def see(self, mv: Move) -> int: ...
+    def get_pieces(self, color: ColorType) -> list[tuple[Address, Piece]]: ...
+    def to_vector(
+        self,
+        target_dimensions: int | None = None,
+        extractor: FeatureExtractor | None = None,
+    ) -> list[float]: ...
+    @staticmethod
+    def to_vector_batch(
+        boards: list[Board | str],
+        target_dimensions: int | None = None,
+        threads: int | None = None,
+        extractor: FeatureExtractor | None = None,
+    ) -> npt.NDArray[np.float32]: ...
+    def to_planes(
+        self,
+        turn: ColorType,
+        orient: bool = True,
+        history: list[Board] = [],
+        history_length: int = 1,
+        repetition: int = 0,
+    ) -> npt.NDArray[np.float32]: ...
+
+class Handicap(Enum):
+    Even = 0
+    Lance = 1
+    Bishop = 2
+    Rook = 3
+    RookLance = 4
+    TwoPiece = 5
+    FourPiece = 6
+    SixPiece = 7
+    EightPiece = 8
+    TenPiece = 9
+
+    @staticmethod
+    def from_name(name: str) -> Handicap: ...
+    @staticmethod
+    def from_kif_header(kif: str) -> Handicap: ...
+    def __repr__(self) -> str: ...
+    @property
+    def name(self) -> str: ...
+    @property
+    def kif(self) -> str: ...
+    def sfen(self) -> str: ...
 
 class Game:
     def __init__(
@@ -109,14 +215,48 @@ class Game:
         turn: ColorType = ColorType.Black,
         winner: ColorType = ColorType.Black,
     ) -> None: ...
+    @staticmethod
+    def from_sfen(sfen: str) -> Game: ...
+    @staticmethod
+    def from_variant(variant: Variant) -> Game: ...
+    @staticmethod
+    def from_handicap(name: str) -> Game: ...
+    @staticmethod
+    def from_kif_header(kif: str) -> Game: ...
     def input_board(self, sfen: str) -> None: ...
     def input_hand(self, sfen: str) -> None: ...
     def input_move_number(self, sfen: str) -> None: ...
     def input_turn(self, sfen: str) -> None: ...
+    def to_sfen(self) -> str: ...
     def is_finished(self) -> tuple[bool, ColorType]: ...
     def execute_move(self, moves: Move) -> None: ...
+    def hash_key(self) -> int: ...
+    def make_move(self, mv: Move) -> ExtendedMove: ...
+    def undo_move(self, mv: ExtendedMove) -> None: ...
+    def make_null_move(self) -> ExtendedMove: ...
+    def undo_null_move(self) -> None: ...
     def random_play(self) -> Game: ...
-    def random_move(self, num: int, threads: int | None = None) -> list[MctsResult]: ...
+    def random_move(
+        self,
+        num: int,
+        threads: int | None = None,
+        rollout: RolloutConfig | None = None,
+    ) -> list[MctsResult]: ...
+    def random_move_with_budget(
+        self,
+        budget: SearchBudget,
+        threads: int | None = None,
+        rollout: RolloutConfig | None = None,
+    ) -> list[MctsResult]: ...
+    def puct_search(
+        self,
+        evaluator: Callable[
+            [list[Board], list[ColorType]],
+            tuple[Sequence[float], npt.ArrayLike],
+        ]
+        | None = None,
+        config: PuctConfig | None = None,
+    ) -> PuctResult: ...
     def generate_random_board(self) -> Board: ...
 
 class MctsResult:
@@ -124,6 +264,342 @@ class MctsResult:
     mv: Move
     white_wins: int
     black_wins: int
+    draws: int
     total_games: int
+    total_moves: int
+    value_sum: float
+    value_square_sum: float
 
     def merge(self, other: MctsResult) -> None: ...
+    def calc_result(self, turn: ColorType) -> float: ...
+    def average_game_length(self) -> float: ...
+    def mean_value(self, turn: ColorType) -> float: ...
+    def value_variance(self) -> float: ...
+    def value_interval(
+        self, turn: ColorType, z: float = 1.96
+    ) -> tuple[float, float]: ...
+    def wilson_interval(
+        self, turn: ColorType, z: float = 1.96
+    ) -> tuple[float, float]: ...
+    def ucb(self, turn: ColorType, parent_games: int, c: float = 1.4142135623730951) -> float: ...
+    def __repr__(self) -> str: ...
+
+class RolloutPolicyType(Enum):
+    Uniform = 0
+    CaptureFirst = 1
+    Softmax = 2
+    EpsilonGreedy = 3
+
+class RolloutConfig:
+    policy: RolloutPolicyType
+    temperature: float
+    epsilon: float
+    mate_in_one: bool
+    cutoff: int | None
+    cutoff_margin: int
+
+    def __init__(
+        self,
+        policy: RolloutPolicyType = RolloutPolicyType.Uniform,
+        temperature: float = 100.0,
+        epsilon: float = 0.1,
+        mate_in_one: bool = False,
+        cutoff: int | None = None,
+        cutoff_margin: int = 0,
+    ) -> None: ...
+    def __repr__(self) -> str: ...
+
+class StopFlag:
+    def __init__(self) -> None: ...
+    def stop(self) -> None: ...
+    def reset(self) -> None: ...
+    def is_stopped(self) -> bool: ...
+    def __repr__(self) -> str: ...
+
+class SearchBudget:
+    max_simulations: int | None
+    max_time_ms: int | None
+    remaining_ms: int | None
+    increment_ms: int
+    byoyomi_ms: int
+    moves_to_go: int
+    margin_ms: int
+    early_stop: bool
+    batch_size: int
+    stop_flag: StopFlag | None
+
+    def __init__(
+        self,
+        max_simulations: int | None = None,
+        max_time_ms: int | None = None,
+        remaining_ms: int | None = None,
+        increment_ms: int = 0,
+        byoyomi_ms: int = 0,
+        moves_to_go: int = 30,
+        margin_ms: int = 50,
+        early_stop: bool = False,
+        batch_size: int = 256,
+        stop_flag: StopFlag | None = None,
+    ) -> None: ...
+    def allocate_time_ms(self) -> int | None: ...
+    def __repr__(self) -> str: ...
+
+class MateStatus(Enum):
+    Proven = 0
+    Disproven = 1
+    Unknown = 2
+
+class MateResult:
+    status: MateStatus
+    moves: list[Move]
+    nodes: int
+
+    def is_mate(self) -> bool: ...
+    def __repr__(self) -> str: ...
+
+class PuctConfig:
+    simulations: int
+    batch_size: int
+    c_puct: float
+    dirichlet_alpha: float
+    dirichlet_epsilon: float
+
+    def __init__(
+        self,
+        simulations: int = 800,
+        batch_size: int = 16,
+        c_puct: float = 1.5,
+        dirichlet_alpha: float = 0.15,
+        dirichlet_epsilon: float = 0.0,
+    ) -> None: ...
+    def __repr__(self) -> str: ...
+
+class PuctResult:
+    moves: list[Move]
+    visits: list[int]
+    priors: list[float]
+    value: float
+
+    def best_move(self) -> Move | None: ...
+    def visit_distribution(self) -> list[float]: ...
+    def __repr__(self) -> str: ...
+
+class PackedSfenValue:
+    sfen: bytes
+    score: int
+    move16: int
+    game_ply: int
+    game_result: int
+
+    def __init__(
+        self,
+        board: Board,
+        turn: ColorType,
+        score: int = 0,
+        mv: Move | None = None,
+        game_ply: int = 1,
+        game_result: int = 0,
+    ) -> None: ...
+    def board(self) -> tuple[Board, ColorType]: ...
+    def move(self) -> Move | None: ...
+    def to_bytes(self) -> bytes: ...
+    @staticmethod
+    def from_bytes(data: bytes) -> PackedSfenValue: ...
+    @staticmethod
+    def read_file(
+        path: str, offset: int = 0, count: int | None = None
+    ) -> list[PackedSfenValue]: ...
+    @staticmethod
+    def write_file(
+        path: str, records: list[PackedSfenValue], append: bool = False
+    ) -> None: ...
+    def __eq__(self, value: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+class Hcpe:
+    hcp: bytes
+    eval: int
+    best_move16: int
+    game_result: int
+
+    def __init__(
+        self,
+        board: Board,
+        turn: ColorType,
+        eval: int = 0,
+        best_move: Move | None = None,
+        winner: ColorType | None = None,
+    ) -> None: ...
+    def board(self) -> tuple[Board, ColorType]: ...
+    def best_move(self) -> Move | None: ...
+    def winner(self) -> ColorType | None: ...
+    def to_bytes(self) -> bytes: ...
+    @staticmethod
+    def from_bytes(data: bytes) -> Hcpe: ...
+    @staticmethod
+    def read_file(
+        path: str, offset: int = 0, count: int | None = None
+    ) -> list[Hcpe]: ...
+    @staticmethod
+    def write_file(path: str, records: list[Hcpe], append: bool = False) -> None: ...
+    def __eq__(self, value: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+class PCASolver(Enum):
+    Full = 0
+    Randomized = 1
+    Incremental = 2
+
+class PCATransform:
+    n_components: int
+
+    def __init__(self, n_components: int) -> None: ...
+    def fit(
+        self,
+        boards: list[Board | str],
+        threads: int | None = None,
+        solver: PCASolver = PCASolver.Full,
+        batch_size: int = 1024,
+    ) -> None: ...
+    def transform(
+        self, boards: list[Board | str], threads: int | None = None
+    ) -> npt.NDArray[np.float32]: ...
+    def transform_vector(self, features: list[float]) -> list[float]: ...
+    def save(self, path: str) -> None: ...
+    @staticmethod
+    def load(path: str) -> PCATransform: ...
+    def set_global(self) -> None: ...
+    @property
+    def is_fitted(self) -> bool: ...
+    @property
+    def n_features(self) -> int: ...
+    @property
+    def components(self) -> npt.NDArray[np.float32]: ...
+    @property
+    def mean(self) -> npt.NDArray[np.float32]: ...
+    @property
+    def explained_variance(self) -> npt.NDArray[np.float32]: ...
+    @property
+    def explained_variance_ratio(self) -> npt.NDArray[np.float32]: ...
+    def __eq__(self, value: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+class IncrementalPCA:
+    n_components: int
+    n_features: int
+    n_samples_seen: int
+
+    def __init__(self, n_components: int) -> None: ...
+    def partial_fit(
+        self, boards: list[Board | str], threads: int | None = None
+    ) -> None: ...
+    def to_transform(self) -> PCATransform: ...
+    def __repr__(self) -> str: ...
+
+class FeaturePlane(Enum):
+    Occupied = 0
+    White = 1
+    Black = 2
+    Empty = 3
+    King = 4
+    Gold = 5
+    Rook = 6
+    Bishop = 7
+    Silver = 8
+    Knight = 9
+    Lance = 10
+    Pawn = 11
+    Dragon = 12
+    Horse = 13
+    ProSilver = 14
+    ProKnight = 15
+    ProLance = 16
+    ProPawn = 17
+
+class FeatureNormalization(Enum):
+    Raw = 0
+    HandRatio = 1
+    L2 = 2
+    Whiten = 3
+
+class FeatureExtractor:
+    def __init__(
+        self,
+        planes: list[FeaturePlane] | None = None,
+        hand: bool = True,
+        normalization: FeatureNormalization = FeatureNormalization.Raw,
+        transform: PCATransform | None = None,
+    ) -> None: ...
+    @property
+    def planes(self) -> list[FeaturePlane]: ...
+    @property
+    def hand(self) -> bool: ...
+    @property
+    def normalization(self) -> FeatureNormalization: ...
+    @property
+    def transform(self) -> PCATransform | None: ...
+    @property
+    def raw_dimensions(self) -> int: ...
+    @property
+    def dimensions(self) -> int: ...
+    def fit(
+        self,
+        boards: list[Board | str],
+        n_components: int,
+        threads: int | None = None,
+        solver: PCASolver = PCASolver.Full,
+        batch_size: int = 1024,
+    ) -> None: ...
+    def extract(self, board: Board) -> list[float]: ...
+    def extract_batch(
+        self, boards: list[Board | str], threads: int | None = None
+    ) -> npt.NDArray[np.float32]: ...
+    def __repr__(self) -> str: ...
+
+class SelfPlaySummary:
+    games: int
+    black_wins: int
+    white_wins: int
+    draws: int
+    positions: int
+
+    def __repr__(self) -> str: ...
+
+class SelfPlayConfig:
+    search: PuctConfig
+    temperature: float
+    temperature_moves: int
+    resign_threshold: float | None
+    max_moves: int
+    variant: Variant
+
+    def __init__(
+        self,
+        search: PuctConfig | None = None,
+        temperature: float = 1.0,
+        temperature_moves: int = 30,
+        resign_threshold: float | None = None,
+        max_moves: int = 512,
+        variant: Variant = Variant.Standard,
+    ) -> None: ...
+    def run(
+        self,
+        output: str,
+        games: int,
+        threads: int | None = None,
+        evaluator: Callable[
+            [list[Board], list[ColorType]],
+            tuple[Sequence[float], npt.ArrayLike],
+        ]
+        | None = None,
+    ) -> SelfPlaySummary: ...
+    def __repr__(self) -> str: ...
+
+class DfPnSolver:
+    attacker: ColorType
+    max_nodes: int
+
+    def __init__(
+        self, attacker: ColorType = ColorType.Black, max_nodes: int = 100000
+    ) -> None: ...
+    def solve(self, board: Board) -> MateResult: ...
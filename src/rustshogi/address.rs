@@ -35,6 +35,26 @@ impl Address {
         self.value
     }
 
+    /// 0〜80の通し番号から作る
+    pub fn from_square(square: u8) -> Self {
+        Self::from_number(square_to_index(square))
+    }
+
+    /// 0〜80の通し番号を返す（枠のマスは `None`）
+    pub fn to_square(&self) -> Option<u8> {
+        index_to_square(self.value)
+    }
+
+    /// 筋が `width` 本の盤で左右を反転したマス
+    pub fn mirrored(&self, width: u8) -> Self {
+        Self::from_numbers(width + 1 - self.get_column(), self.get_row())
+    }
+
+    /// `width` 筋 `height` 段の盤を180度回したマス
+    pub fn rotated(&self, width: u8, height: u8) -> Self {
+        Self::from_numbers(width + 1 - self.get_column(), height + 1 - self.get_row())
+    }
+
     pub fn to_string(&self) -> String {
         let mut string = String::new();
         let column = common::integer_to_char(self.value % 11);
@@ -114,3 +134,19 @@ pub fn index_to_row(index: u8) -> u8 {
 pub fn index_to_column(index: u8) -> u8 {
     index % 11
 }
+
+/// 盤上のマスのインデックスを0〜80の通し番号に変換する（枠のマスは `None`）
+pub fn index_to_square(index: u8) -> Option<u8> {
+    let row = index_to_row(index);
+    let column = index_to_column(index);
+    if (1..=9).contains(&row) && (1..=9).contains(&column) {
+        Some((row - 1) * 9 + column - 1)
+    } else {
+        None
+    }
+}
+
+/// 0〜80の通し番号を盤上のマスのインデックスに変換する
+pub fn square_to_index(square: u8) -> u8 {
+    (square / 9 + 1) * 11 + square % 9 + 1
+}
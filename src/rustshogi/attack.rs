@@ -0,0 +1,327 @@
+use super::bitboard::{BitBoard, BIT_OF_FRAME, LENGTH_OF_EDGE, LENGTH_OF_FRAME};
+use super::color::ColorType;
+use super::direction::{Direction, DirectionName};
+use super::move_pattern;
+use super::piece::{MoveType, PieceType, PIECE_TYPE_NUMBER};
+
+use once_cell::sync::Lazy;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use strum::IntoEnumIterator;
+
+/// 盤外（枠とインデックス121以降）のマス
+const OUTSIDE: u128 = BIT_OF_FRAME | 0x7F;
+
+/// 利きを表引きする直線（縦・横・斜め2方向）のビットのずらし幅
+const LINE_STEPS: [i8; 4] = [
+    LENGTH_OF_FRAME as i8,
+    1,
+    LENGTH_OF_FRAME as i8 + 1,
+    LENGTH_OF_FRAME as i8 - 1,
+];
+
+const FILE: usize = 0;
+const RANK: usize = 1;
+const DIAGONAL: usize = 2;
+const ANTI_DIAGONAL: usize = 3;
+
+fn shift(bit_board: BitBoard, shift_number: i8) -> BitBoard {
+    if shift_number > 0 {
+        bit_board << shift_number as usize
+    } else {
+        bit_board >> shift_number.unsigned_abs() as usize
+    }
+}
+
+fn shift_u128(bits: u128, shift_number: i8) -> u128 {
+    if shift_number > 0 {
+        bits << shift_number as u32
+    } else {
+        bits >> shift_number.unsigned_abs() as u32
+    }
+}
+
+fn is_outside(index: u8) -> bool {
+    (OUTSIDE >> (127 - index)) & 1 != 0
+}
+
+/// `step` 方向に盤端か `occupied` の駒に当たるまで進んだマス（当たったマスを含む）
+fn ray(index: u8, step: i8, occupied: u128) -> u128 {
+    let mut attacks = 0;
+    let mut bit = 1u128 << (127 - index);
+    loop {
+        bit = shift_u128(bit, step);
+        if bit == 0 || bit & OUTSIDE != 0 {
+            return attacks;
+        }
+        attacks |= bit;
+        if bit & occupied != 0 {
+            return attacks;
+        }
+    }
+}
+
+/// 直線1本分の利きの表引きに使うマジックナンバー
+struct Magic {
+    mask: u128,
+    magic: u128,
+    shift: u32,
+    offset: usize,
+}
+
+impl Magic {
+    fn index(&self, occupied: u128) -> usize {
+        let hash = (occupied & self.mask).wrapping_mul(self.magic);
+        // マスクが空のときは128ビットずらすことになるので0番目を使う
+        self.offset + hash.checked_shr(self.shift).unwrap_or(0) as usize
+    }
+}
+
+/// 起動時に一度だけ作る利きの表
+struct AttackTables {
+    /// 近接の利き（飛び駒は隣接マスのみ）[手番][駒種][マス]
+    step: Vec<u128>,
+    /// 直線ごとのマジックナンバー [直線][マス]
+    magics: Vec<Magic>,
+    /// マジックナンバーで引く直線上の利き
+    line_attacks: Vec<u128>,
+    /// 香車の前方のマス [手番][マス]
+    forward: Vec<u128>,
+}
+
+impl AttackTables {
+    fn new() -> Self {
+        let mut step = vec![0; ColorType::ColorNumber as usize * PIECE_TYPE_NUMBER as usize * 128];
+        let mut forward = vec![0; ColorType::ColorNumber as usize * 128];
+        for color in [ColorType::Black, ColorType::White] {
+            for index in (0..128).filter(|index| !is_outside(*index)) {
+                for piece_type in PieceType::iter().skip(1) {
+                    // すべてのマスが埋まっているとみなすと、飛び駒は隣接マスだけになる
+                    step[step_index(color, piece_type, index)] = attacks_from_by_pattern(
+                        piece_type,
+                        color,
+                        index,
+                        BitBoard::from_u128(u128::MAX),
+                    )
+                    .to_u128();
+                }
+                forward[color as usize * 128 + index as usize] =
+                    attacks_from_by_pattern(PieceType::Lance, color, index, BitBoard::new())
+                        .to_u128();
+            }
+        }
+
+        // 実行ごとに同じ表になるよう固定のシードを使う
+        let mut rng = StdRng::seed_from_u64(0x4d_4147_4943);
+        let mut magics = Vec::with_capacity(LINE_STEPS.len() * 128);
+        let mut line_attacks = Vec::new();
+        for line_step in LINE_STEPS {
+            for index in 0..128u8 {
+                if is_outside(index) {
+                    magics.push(Magic {
+                        mask: 0,
+                        magic: 0,
+                        shift: 128,
+                        offset: line_attacks.len(),
+                    });
+                    line_attacks.push(0);
+                    continue;
+                }
+                let (magic, attacks) = find_magic(index, line_step, line_attacks.len(), &mut rng);
+                magics.push(magic);
+                line_attacks.extend(attacks);
+            }
+        }
+
+        Self {
+            step,
+            magics,
+            line_attacks,
+            forward,
+        }
+    }
+
+    fn line(&self, line: usize, index: u8, occupied: u128) -> u128 {
+        let magic = &self.magics[line * 128 + index as usize];
+        self.line_attacks[magic.index(occupied)]
+    }
+}
+
+fn step_index(color: ColorType, piece_type: PieceType, index: u8) -> usize {
+    (color as usize * PIECE_TYPE_NUMBER as usize + piece_type as usize) * 128 + index as usize
+}
+
+/// 直線1本分のマスクとマジックナンバーを探し、表の中身と一緒に返す
+fn find_magic(index: u8, line_step: i8, offset: usize, rng: &mut StdRng) -> (Magic, Vec<u128>) {
+    // 盤端のマスは駒の有無で利きが変わらないのでマスクから除く
+    let mut mask = 0;
+    for step in [line_step, -line_step] {
+        let full = ray(index, step, 0);
+        mask |= full & !farthest_square(full, step);
+    }
+    let bits = mask.count_ones();
+    let shift = 128 - bits;
+
+    let mut occupancies = Vec::new();
+    let mut subset: u128 = 0;
+    loop {
+        let attacks = ray(index, line_step, subset) | ray(index, -line_step, subset);
+        occupancies.push((subset, attacks));
+        subset = subset.wrapping_sub(mask) & mask;
+        if subset == 0 {
+            break;
+        }
+    }
+
+    let size = 1usize << bits;
+    loop {
+        let magic = if bits == 0 {
+            0
+        } else {
+            rng.gen::<u128>() & rng.gen::<u128>() & rng.gen::<u128>()
+        };
+        let candidate = Magic {
+            mask,
+            magic,
+            shift,
+            offset,
+        };
+        let mut table: Vec<Option<u128>> = vec![None; size];
+        let is_valid = occupancies.iter().all(|(occupied, attacks)| {
+            let slot = &mut table[candidate.index(*occupied) - offset];
+            match slot {
+                Some(existing) => existing == attacks,
+                None => {
+                    *slot = Some(*attacks);
+                    true
+                }
+            }
+        });
+        if is_valid {
+            let attacks = table
+                .into_iter()
+                .map(|attack| attack.unwrap_or(0))
+                .collect();
+            return (candidate, attacks);
+        }
+    }
+}
+
+/// 盤端まで進んだ利き `full` のうち、最も遠いマスを返す
+fn farthest_square(full: u128, step: i8) -> u128 {
+    if full == 0 {
+        0
+    } else if step > 0 {
+        // 左シフトで進むので最上位のビットが最も遠い
+        1u128 << (127 - full.leading_zeros())
+    } else {
+        full & full.wrapping_neg()
+    }
+}
+
+static ATTACK_TABLES: Lazy<AttackTables> = Lazy::new(AttackTables::new);
+
+/// 飛車の縦横の利き
+pub fn rook_attacks(index: u8, occupied: BitBoard) -> BitBoard {
+    let occupied = occupied.to_u128();
+    BitBoard::from_u128(
+        ATTACK_TABLES.line(FILE, index, occupied) | ATTACK_TABLES.line(RANK, index, occupied),
+    )
+}
+
+/// 角の斜めの利き
+pub fn bishop_attacks(index: u8, occupied: BitBoard) -> BitBoard {
+    let occupied = occupied.to_u128();
+    BitBoard::from_u128(
+        ATTACK_TABLES.line(DIAGONAL, index, occupied)
+            | ATTACK_TABLES.line(ANTI_DIAGONAL, index, occupied),
+    )
+}
+
+/// 香車の前方の利き
+pub fn lance_attacks(color: ColorType, index: u8, occupied: BitBoard) -> BitBoard {
+    BitBoard::from_u128(
+        ATTACK_TABLES.line(FILE, index, occupied.to_u128())
+            & ATTACK_TABLES.forward[color as usize * 128 + index as usize],
+    )
+}
+
+/// 指定したマスにある駒の利きを返す
+///
+/// `occupied` に含まれるマスで飛び駒の利きが止まる。味方の駒があるマスも利きに含む。
+pub fn attacks_from(
+    piece_type: PieceType,
+    color: ColorType,
+    index: u8,
+    occupied: BitBoard,
+) -> BitBoard {
+    let step = BitBoard::from_u128(ATTACK_TABLES.step[step_index(color, piece_type, index)]);
+    match piece_type {
+        PieceType::Rook | PieceType::Dragon => step | rook_attacks(index, occupied),
+        PieceType::Bichop | PieceType::Horse => step | bishop_attacks(index, occupied),
+        PieceType::Lance => lance_attacks(color, index, occupied),
+        _ => step,
+    }
+}
+
+/// `MovePattern` をたどって利きを求める（表を作るときと検算に使う）
+pub fn attacks_from_by_pattern(
+    piece_type: PieceType,
+    color: ColorType,
+    index: u8,
+    occupied: BitBoard,
+) -> BitBoard {
+    let frame = BitBoard::from_u128(BIT_OF_FRAME);
+    let bit_board = BitBoard::from_u128(1u128 << (127 - index));
+    let mut attacks = BitBoard::new();
+
+    for pattern in move_pattern::get_move_patterns(piece_type) {
+        let mut direction = Direction::new(pattern.direction);
+        if color == ColorType::White {
+            direction.reverse();
+        }
+        let step = LENGTH_OF_FRAME as i8 * direction.vertical_vector + direction.horizon_vector;
+
+        match pattern.move_type {
+            MoveType::Short => {
+                let target_board = shift(bit_board, step);
+                if (frame & target_board) == BitBoard::new() {
+                    attacks |= target_board;
+                }
+            }
+            MoveType::Long => {
+                for i in 1..LENGTH_OF_EDGE {
+                    let target_board = shift(bit_board, step * i as i8);
+                    if (frame & target_board) != BitBoard::new() {
+                        break;
+                    }
+                    attacks |= target_board;
+                    if (occupied & target_board) != BitBoard::new() {
+                        break;
+                    }
+                }
+            }
+            MoveType::Hop => {
+                let up_direction = if color == ColorType::White {
+                    Direction::new(DirectionName::Down)
+                } else {
+                    Direction::new(DirectionName::Up)
+                };
+                let v = direction.vertical_vector + up_direction.vertical_vector;
+                let target_board = shift(
+                    bit_board,
+                    LENGTH_OF_FRAME as i8 * v + direction.horizon_vector,
+                );
+                if (frame & target_board) == BitBoard::new() {
+                    attacks |= target_board;
+                }
+            }
+            MoveType::None => {}
+        }
+    }
+
+    // 盤外（インデックス121以降）へはみ出した利きを除く
+    let mut playable = frame;
+    playable.flip();
+    attacks & playable
+}
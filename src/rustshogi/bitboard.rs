@@ -136,7 +136,7 @@ impl BitBoard {
         bitboard
     }
 
-    pub fn from_u128(integer: u128) -> Self {
+    pub const fn from_u128(integer: u128) -> Self {
         BitBoard {
             data: [(integer >> 64) as u64, integer as u64],
         }
@@ -305,6 +305,27 @@ static BOARD_MASK: BitBoard = BitBoard {
     data: [(BOARD_MASK_U128 >> 64) as u64, BOARD_MASK_U128 as u64],
 };
 
+/// 盤の枠のマス
+pub const FRAME: BitBoard = BitBoard::from_u128(BIT_OF_FRAME);
+
+/// 手番ごとの成れるマス（敵陣3段）
+pub const PRO_ZONE: [BitBoard; 2] = [
+    BitBoard::from_u128(BIT_OF_PRO_ZONE_BLACK),
+    BitBoard::from_u128(BIT_OF_PRO_ZONE_WHITE),
+];
+
+/// 手番ごとの最も奥の1段
+pub const LAST1_ZONE: [BitBoard; 2] = [
+    BitBoard::from_u128(BIT_OF_LAST1_ZONE_BLACK),
+    BitBoard::from_u128(BIT_OF_LAST1_ZONE_WHITE),
+];
+
+/// 手番ごとの奥から2段
+pub const LAST2_ZONE: [BitBoard; 2] = [
+    BitBoard::from_u128(BIT_OF_LAST2_ZONE_BLACK),
+    BitBoard::from_u128(BIT_OF_LAST2_ZONE_WHITE),
+];
+
 #[cfg(target_arch = "x86_64")]
 /// Performs a bitwise XOR operation using SSE2 intrinsics.
 ///
@@ -0,0 +1,205 @@
+use super::address::{index_to_square, square_to_index, Address};
+use super::bitboard::{BitBoard, LENGTH_OF_EDGE, LENGTH_OF_FRAME};
+
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
+
+/// 盤上のマスの数
+pub const SQUARE_NUMBER: u8 = 81;
+
+/// `data[0]` に入るマスの数
+const LOWER_SQUARES: u32 = 63;
+
+const LOWER_MASK: u64 = (1 << LOWER_SQUARES) - 1;
+
+const UPPER_MASK: u64 = (1 << (SQUARE_NUMBER as u32 - LOWER_SQUARES)) - 1;
+
+/// 1段分（9マス）のビット
+const RANK_MASK: u128 = (1 << LENGTH_OF_EDGE) - 1;
+
+/// 枠を持たない81マスのビットボード
+///
+/// マスの番号は `(行 - 1) * 9 + (列 - 1)`（`Address` の行と列）で、
+/// 0〜62番を `data[0]`、63〜80番を `data[1]` の下位ビットから順に持つ。
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Hash)]
+pub struct BitBoard81 {
+    data: [u64; 2],
+}
+
+impl BitBoard81 {
+    pub const fn new() -> Self {
+        Self { data: [0, 0] }
+    }
+
+    /// 81ビットの整数（マス番号のビットが立ったもの）から作る
+    pub const fn from_u128(bits: u128) -> Self {
+        Self {
+            data: [
+                bits as u64 & LOWER_MASK,
+                (bits >> LOWER_SQUARES) as u64 & UPPER_MASK,
+            ],
+        }
+    }
+
+    pub const fn to_u128(&self) -> u128 {
+        self.data[0] as u128 | ((self.data[1] as u128) << LOWER_SQUARES)
+    }
+
+    pub fn from_square(square: u8) -> Self {
+        Self::from_u128(1 << square)
+    }
+
+    /// 枠付きのインデックスから作る（枠のマスは空になる）
+    pub fn from_index(index: u8) -> Self {
+        match index_to_square(index) {
+            Some(square) => Self::from_square(square),
+            None => Self::new(),
+        }
+    }
+
+    pub fn from_address(address: &Address) -> Self {
+        Self::from_index(address.to_index())
+    }
+
+    /// 枠付きの `BitBoard` から盤上のマスだけを取り出す
+    pub fn from_bitboard(bitboard: BitBoard) -> Self {
+        let framed = bitboard.to_u128();
+        let mut bits = 0u128;
+        for row in 1..=LENGTH_OF_EDGE as u32 {
+            // 1列目が上位ビットに並んでいるので、9ビットを反転して下位ビットを1列目にする
+            let last = row * LENGTH_OF_FRAME as u32 + LENGTH_OF_EDGE as u32;
+            let rank = ((framed >> (127 - last)) & RANK_MASK) as u32;
+            let rank = rank.reverse_bits() >> (32 - LENGTH_OF_EDGE as u32);
+            bits |= (rank as u128) << ((row - 1) * LENGTH_OF_EDGE as u32);
+        }
+        Self::from_u128(bits)
+    }
+
+    /// 枠付きの `BitBoard` に戻す
+    pub fn to_bitboard(&self) -> BitBoard {
+        let bits = self.to_u128();
+        let mut framed = 0u128;
+        for row in 1..=LENGTH_OF_EDGE as u32 {
+            let rank = ((bits >> ((row - 1) * LENGTH_OF_EDGE as u32)) & RANK_MASK) as u32;
+            let rank = rank.reverse_bits() >> (32 - LENGTH_OF_EDGE as u32);
+            let last = row * LENGTH_OF_FRAME as u32 + LENGTH_OF_EDGE as u32;
+            framed |= (rank as u128) << (127 - last);
+        }
+        BitBoard::from_u128(framed)
+    }
+
+    pub fn contains(&self, square: u8) -> bool {
+        (self.to_u128() >> square) & 1 != 0
+    }
+
+    pub fn set(&mut self, square: u8) {
+        *self |= Self::from_square(square);
+    }
+
+    pub fn clear(&mut self, square: u8) {
+        *self &= !Self::from_square(square);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data == [0, 0]
+    }
+
+    pub fn count_ones(&self) -> u32 {
+        self.data[0].count_ones() + self.data[1].count_ones()
+    }
+
+    /// 最も番号の小さいマスを取り除いて返す
+    pub fn pop_lsb(&mut self) -> Option<u8> {
+        if self.data[0] != 0 {
+            let square = self.data[0].trailing_zeros() as u8;
+            self.data[0] &= self.data[0] - 1;
+            Some(square)
+        } else if self.data[1] != 0 {
+            let square = self.data[1].trailing_zeros() as u8 + LOWER_SQUARES as u8;
+            self.data[1] &= self.data[1] - 1;
+            Some(square)
+        } else {
+            None
+        }
+    }
+
+    /// 立っているマスの番号を小さい順に返す
+    pub fn squares(&self) -> impl Iterator<Item = u8> {
+        let mut bitboard = *self;
+        std::iter::from_fn(move || bitboard.pop_lsb())
+    }
+
+    /// 立っているマスを枠付きのインデックスで返す
+    pub fn indices(&self) -> impl Iterator<Item = u8> {
+        self.squares().map(square_to_index)
+    }
+}
+
+impl From<BitBoard> for BitBoard81 {
+    fn from(bitboard: BitBoard) -> Self {
+        Self::from_bitboard(bitboard)
+    }
+}
+
+impl From<BitBoard81> for BitBoard {
+    fn from(bitboard: BitBoard81) -> Self {
+        bitboard.to_bitboard()
+    }
+}
+
+impl BitAnd for BitBoard81 {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self {
+        Self {
+            data: [self.data[0] & rhs.data[0], self.data[1] & rhs.data[1]],
+        }
+    }
+}
+
+impl BitAndAssign for BitBoard81 {
+    fn bitand_assign(&mut self, rhs: Self) {
+        *self = *self & rhs;
+    }
+}
+
+impl BitOr for BitBoard81 {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self {
+            data: [self.data[0] | rhs.data[0], self.data[1] | rhs.data[1]],
+        }
+    }
+}
+
+impl BitOrAssign for BitBoard81 {
+    fn bitor_assign(&mut self, rhs: Self) {
+        *self = *self | rhs;
+    }
+}
+
+impl BitXor for BitBoard81 {
+    type Output = Self;
+
+    fn bitxor(self, rhs: Self) -> Self {
+        Self {
+            data: [self.data[0] ^ rhs.data[0], self.data[1] ^ rhs.data[1]],
+        }
+    }
+}
+
+impl BitXorAssign for BitBoard81 {
+    fn bitxor_assign(&mut self, rhs: Self) {
+        *self = *self ^ rhs;
+    }
+}
+
+impl Not for BitBoard81 {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        Self {
+            data: [!self.data[0] & LOWER_MASK, !self.data[1] & UPPER_MASK],
+        }
+    }
+}
@@ -1,42 +1,110 @@
-use super::address::Address;
-use super::bitboard::{
-    generate_column, BitBoard, BIT_OF_FRAME, BIT_OF_LAST1_ZONE_BLACK, BIT_OF_LAST1_ZONE_WHITE,
-    BIT_OF_LAST2_ZONE_BLACK, BIT_OF_LAST2_ZONE_WHITE, BIT_OF_PRO_ZONE_BLACK, BIT_OF_PRO_ZONE_WHITE,
-    LENGTH_OF_EDGE, LENGTH_OF_FRAME,
-};
+use super::address::{index_to_square, square_to_index, Address};
+use super::attack;
+use super::bitboard::{generate_column, BitBoard, FRAME, LENGTH_OF_EDGE, LENGTH_OF_FRAME};
+use super::bitboard81::BitBoard81;
 use super::color::{get_reverse_color, ColorType};
 use super::direction::{Direction, DirectionName};
+use super::feature::FeatureExtractor;
 use super::hand::Hand;
+use super::handicap::Handicap;
+use super::move_label;
+use super::move_list::MoveList;
 use super::move_pattern;
-use super::moves::Move;
-use super::pca::apply_pca_compression;
+use super::move_picker;
+use super::moves::{ExtendedMove, Move};
+use super::packed_sfen::{self, PackedFormat};
 use super::piece::{MoveType, Piece, PieceType, PIECE_TYPE_NUMBER, PROMOTE};
+use super::planes::{self, PlaneOptions};
+use super::variant::Variant;
+use super::zobrist::ZOBRIST;
 
 use lru::LruCache;
+use ndarray::Array3;
+use numpy::{IntoPyArray, PyArray1, PyArray2, PyArray3};
 use once_cell::sync::Lazy;
 use pyo3::prelude::*;
+use pyo3::types::PyBytes;
 use std::num::NonZeroUsize;
 use std::sync::Mutex;
 use strum::IntoEnumIterator;
 
-use std::hash::Hash;
+use std::hash::{Hash, Hasher};
 
 const CACHE_SIZE: usize = 70000;
 
 static MOVE_CACHE: Lazy<Mutex<LruCache<(Board, ColorType), Vec<Move>>>> =
     Lazy::new(|| Mutex::new(LruCache::new(NonZeroUsize::new(CACHE_SIZE).unwrap())));
 
+/// `to_vector` の圧縮前の特徴量の次元数（128マス × 18枚のビットボード + 持ち駒16）
+pub const VECTOR_DIMENSIONS: usize = 2320;
+
+/// 片方の手番が盤上に置ける駒の最大数
+///
+/// 実戦では40枚を超えないが、SFENや `deploy` では任意に駒を置けるので盤のマス数にしておく。
+pub const MAX_PIECES: usize = 81;
+
+/// 縦・横・斜めに並んだ2マスの間にあるマス（並んでいなければ空）
+fn squares_between(from_index: u8, to_index: u8) -> BitBoard {
+    let from = Address::from_number(from_index);
+    let to = Address::from_number(to_index);
+    let row_diff = to.get_row() as i8 - from.get_row() as i8;
+    let column_diff = to.get_column() as i8 - from.get_column() as i8;
+    if row_diff != 0 && column_diff != 0 && row_diff.abs() != column_diff.abs() {
+        return BitBoard::new();
+    }
+
+    let step = row_diff.signum() * LENGTH_OF_FRAME as i8 + column_diff.signum();
+    let mut between = 0u128;
+    let mut index = from_index as i8 + step;
+    while index != to_index as i8 {
+        between |= 1u128 << (127 - index);
+        index += step;
+    }
+    BitBoard::from_u128(between)
+}
+
+/// 将棋の盤面
+///
+/// 成れる段などの局面によらないマスクは `bitboard::PRO_ZONE` などの定数に移してあり、ビットボードは18枚だけ持つ。
+/// ただしmailboxと駒リスト（245バイト）も持つので、全体ではマスクを持っていた頃（672バイト）より大きい808バイトになる。
+/// ロールアウトでは盤面を何度も複製するので、大きさを変えたら `test_board_size` も見直す。
 #[pyclass]
-#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+#[derive(Clone, Debug)]
 pub struct Board {
     pub has_piece: BitBoard,
     pub player_prossesion: [BitBoard; ColorType::ColorNumber as usize],
-    pub is_frame: BitBoard,
-    pub able_pro: [BitBoard; ColorType::ColorNumber as usize],
-    pub last_one: [BitBoard; ColorType::ColorNumber as usize],
-    pub last_two: [BitBoard; ColorType::ColorNumber as usize],
     pub has_specific_piece: [BitBoard; PIECE_TYPE_NUMBER as usize],
     pub hand: Hand,
+    /// 81マスそれぞれの駒（`Piece::to_u8` の値、空きマスは0）
+    pub mailbox: [u8; 81],
+    /// 手番ごとの盤上の駒があるマスのインデックス（先頭 `piece_counts` 個が有効）
+    pub piece_lists: [[u8; MAX_PIECES]; ColorType::ColorNumber as usize],
+    pub piece_counts: [u8; ColorType::ColorNumber as usize],
+    /// 盤の大きさと成れる段を決める将棋の種類
+    pub variant: Variant,
+}
+
+// 駒リストの並び順は局面の同一性に関係しないので比較とハッシュから除く
+impl PartialEq for Board {
+    fn eq(&self, other: &Self) -> bool {
+        self.has_piece == other.has_piece
+            && self.player_prossesion == other.player_prossesion
+            && self.has_specific_piece == other.has_specific_piece
+            && self.hand == other.hand
+            && self.variant == other.variant
+    }
+}
+
+impl Eq for Board {}
+
+impl Hash for Board {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.has_piece.hash(state);
+        self.player_prossesion.hash(state);
+        self.has_specific_piece.hash(state);
+        self.hand.hash(state);
+        self.variant.hash(state);
+    }
 }
 
 impl Default for Board {
@@ -50,7 +118,41 @@ impl Board {
         (self.has_specific_piece[piece_type as usize].to_u128() >> (127 - index)) & 1 != 0
     }
 
+    fn add_to_piece_list(&mut self, color: ColorType, index: u8) {
+        let count = self.piece_counts[color as usize] as usize;
+        debug_assert!(
+            count < MAX_PIECES,
+            "piece list of {:?} is full ({} pieces)",
+            color,
+            count
+        );
+        self.piece_lists[color as usize][count] = index;
+        self.piece_counts[color as usize] += 1;
+    }
+
+    fn remove_from_piece_list(&mut self, color: ColorType, index: u8) {
+        let count = self.piece_counts[color as usize] as usize;
+        let list = &mut self.piece_lists[color as usize];
+        if let Some(position) = list[..count].iter().position(|square| *square == index) {
+            list[position] = list[count - 1];
+            self.piece_counts[color as usize] -= 1;
+        }
+    }
+
+    /// マスにある駒を駒リストとmailboxから取り除く
+    fn clear_square(&mut self, index: u8) {
+        if let Some(square) = index_to_square(index) {
+            let code = self.mailbox[square as usize];
+            if code != 0 {
+                self.remove_from_piece_list(Piece::from_u8(code).owner, index);
+                self.mailbox[square as usize] = 0;
+            }
+        }
+    }
+
     fn drop(&mut self, index: u8) {
+        self.clear_square(index);
+
         let bit = 1u128 << (127 - index);
         let bit_mask = BitBoard::from_u128(bit);
         let not_bit_mask = BitBoard::from_u128(!bit);
@@ -63,20 +165,21 @@ impl Board {
         for has_specific_piece in self.has_specific_piece.iter_mut().skip(1) {
             *has_specific_piece &= not_bit_mask;
         }
+        debug_assert!(self.is_square_consistent(index));
     }
 
-    fn move_standard(&mut self, from_index: u8, to_index: u8) {
-        let piece_type = self.get_piece_type_from_index(from_index);
+    fn move_standard(&mut self, from_index: u8, to_index: u8, is_promote: bool) {
+        let mut piece_type = self.get_piece_type_from_index(from_index);
+        if is_promote {
+            piece_type = piece_type.promote();
+        }
         let color_type = self.get_color_type_from_index(from_index);
         self.drop(from_index);
         self.deploy(to_index, piece_type, color_type);
     }
 
     fn move_to_hand(&mut self, index: u8, is_color_reverse: bool) {
-        let mut piece_type = self.get_piece_type_from_index(index);
-        if piece_type as u8 > PROMOTE {
-            piece_type = PieceType::from_usize(piece_type as usize - PROMOTE as usize);
-        }
+        let piece_type = self.get_piece_type_from_index(index).demote();
 
         let mut color_type = self.get_color_type_from_index(index);
         if is_color_reverse {
@@ -93,25 +196,15 @@ impl Board {
     }
 
     pub fn new() -> Self {
-        let is_frame = BitBoard::from_u128(BIT_OF_FRAME);
-        let mut has_specific_piece = BitBoard::from_u128(BIT_OF_FRAME);
-        has_specific_piece.flip();
+        Self::with_variant(Variant::Standard)
+    }
+
+    /// 駒のない `variant` の盤を作る（盤の外のマスは空きマスにも含めない）
+    pub fn with_variant(variant: Variant) -> Self {
+        let has_specific_piece = variant.playable();
         Self {
             has_piece: BitBoard::new(),
             player_prossesion: [BitBoard::new(), BitBoard::new()],
-            is_frame,
-            able_pro: [
-                BitBoard::from_u128(BIT_OF_PRO_ZONE_BLACK),
-                BitBoard::from_u128(BIT_OF_PRO_ZONE_WHITE),
-            ],
-            last_one: [
-                BitBoard::from_u128(BIT_OF_LAST1_ZONE_BLACK),
-                BitBoard::from_u128(BIT_OF_LAST1_ZONE_WHITE),
-            ],
-            last_two: [
-                BitBoard::from_u128(BIT_OF_LAST2_ZONE_BLACK),
-                BitBoard::from_u128(BIT_OF_LAST2_ZONE_WHITE),
-            ],
             has_specific_piece: [
                 has_specific_piece,
                 BitBoard::new(),
@@ -130,10 +223,24 @@ impl Board {
                 BitBoard::new(),
             ],
             hand: Hand::new(),
+            mailbox: [0; 81],
+            piece_lists: [[0; MAX_PIECES]; ColorType::ColorNumber as usize],
+            piece_counts: [0; ColorType::ColorNumber as usize],
+            variant,
         }
     }
 
     pub fn deploy(&mut self, index: u8, piece_type: PieceType, color: ColorType) {
+        self.clear_square(index);
+        if let Some(square) = index_to_square(index) {
+            if piece_type != PieceType::None
+                && (color == ColorType::Black || color == ColorType::White)
+            {
+                self.mailbox[square as usize] = Piece::from(color, piece_type).to_u8();
+                self.add_to_piece_list(color, index);
+            }
+        }
+
         let mask = 1u128 << (127 - index);
         let bit_mask = BitBoard::from_u128(mask);
         let not_bit_mask = BitBoard::from_u128(!mask);
@@ -153,9 +260,69 @@ impl Board {
                 *has_specific_piece &= not_bit_mask;
             }
         }
+        debug_assert!(self.is_square_consistent(index));
+    }
+
+    /// 指定したマスでmailbox・駒リストとビットボードが一致しているかを返す
+    pub fn is_square_consistent(&self, index: u8) -> bool {
+        let square = match index_to_square(index) {
+            Some(square) => square,
+            None => return true,
+        };
+        let mut bit_piece_type = PieceType::None;
+        for piece_type in PieceType::iter() {
+            if self.is_a_has_specific_piece(index, piece_type) {
+                bit_piece_type = piece_type;
+                break;
+            }
+        }
+
+        let code = self.mailbox[square as usize];
+        if code == 0 {
+            return bit_piece_type == PieceType::None;
+        }
+        let piece = Piece::from_u8(code);
+        let count = self.piece_counts[piece.owner as usize] as usize;
+        piece.piece_type == bit_piece_type
+            && piece.owner == self.get_color_type_from_index(index)
+            && self.piece_lists[piece.owner as usize][..count].contains(&index)
+    }
+
+    /// 盤全体でmailbox・駒リストとビットボードが一致しているかを返す
+    pub fn is_consistent(&self) -> bool {
+        let all_squares = (0..81).all(|square| self.is_square_consistent(square_to_index(square)));
+        let counts = [ColorType::Black, ColorType::White].iter().all(|color| {
+            self.piece_counts[*color as usize] as u32 == self.pieces81(*color).count_ones()
+        });
+        all_squares && counts
+    }
+
+    /// `color` 側の盤上の駒があるマスのインデックス
+    pub fn get_piece_list(&self, color: ColorType) -> &[u8] {
+        &self.piece_lists[color as usize][..self.piece_counts[color as usize] as usize]
+    }
+
+    /// 盤上の駒があるマスを枠のない81マスのビットボードで返す
+    pub fn occupied81(&self) -> BitBoard81 {
+        BitBoard81::from(self.has_piece)
+    }
+
+    /// `color` 側の駒があるマスを枠のない81マスのビットボードで返す
+    pub fn pieces81(&self, color: ColorType) -> BitBoard81 {
+        BitBoard81::from(self.has_piece & self.player_prossesion[color as usize])
+    }
+
+    /// `piece_type` の駒（先手・後手とも）があるマスを枠のない81マスのビットボードで返す
+    pub fn specific_pieces81(&self, piece_type: PieceType) -> BitBoard81 {
+        BitBoard81::from(self.has_specific_piece[piece_type as usize])
     }
 
     pub fn startpos(&mut self) {
+        if self.variant != Variant::Standard {
+            self.input_board(self.variant.board_sfen());
+            self.hand = Hand::new();
+            return;
+        }
         self.deploy(
             Address::from_numbers(1, 1).to_index(),
             PieceType::Lance,
@@ -371,19 +538,30 @@ impl Board {
         let parts: Vec<&str> = sfen.split('/').collect();
         for (row, part) in parts.iter().enumerate().rev() {
             let mut column = 0;
+            let mut is_promoted = false;
             let chars = part.chars();
             for ch in chars {
                 if ch.is_ascii_digit() {
                     let empty_spaces = ch.to_digit(10).unwrap() as usize;
                     column += empty_spaces;
+                } else if ch == '+' {
+                    is_promoted = true;
                 } else {
                     let piece = Piece::from_char(ch);
-                    let piece_type = piece.piece_type;
+                    let piece_type = if is_promoted {
+                        piece.piece_type.promote()
+                    } else {
+                        piece.piece_type
+                    };
                     let owner = piece.owner;
-                    let index =
-                        Address::from_numbers((1 + column) as u8, (9 - row) as u8).to_index();
+                    let index = Address::from_numbers(
+                        (1 + column) as u8,
+                        self.variant.height() - row as u8,
+                    )
+                    .to_index();
                     self.deploy(index, piece_type, owner);
                     column += 1;
+                    is_promoted = false;
                 }
             }
         }
@@ -407,17 +585,41 @@ impl Board {
         }
     }
 
+    /// SFENから盤面を作る
+    ///
+    /// `盤面 持ち駒` の形式と、手番と手数を含む `盤面 手番 持ち駒 手数` の形式を受け付ける。
     pub fn from_sfen(sfen: String) -> Self {
-        let mut board = Self::new();
-        let parts: Vec<&str> = sfen.split(" ").collect();
+        Self::from_sfen_with_variant(sfen, Variant::Standard)
+    }
+
+    /// `variant` の盤としてSFENを読む（"startpos" はその種類の初期局面）
+    pub fn from_sfen_with_variant(sfen: String, variant: Variant) -> Self {
+        let mut board = Self::with_variant(variant);
+        let parts: Vec<&str> = sfen.split_whitespace().collect();
+        if parts.is_empty() {
+            return board;
+        }
         board.input_board(parts[0]);
-        if parts.len() > 1 {
+        if parts.len() > 2 {
+            board.input_hand(parts[2]);
+        } else if parts.len() > 1 {
             board.input_hand(parts[1]);
         }
         board
     }
 
+    /// 手合割の名前（"2-piece" や "二枚落ち" など）から初期局面を作る
+    pub fn from_handicap(name: &str) -> Result<Self, String> {
+        let handicap =
+            Handicap::from_name(name).ok_or_else(|| format!("unknown handicap: {}", name))?;
+        Ok(Self::from_sfen(handicap.sfen()))
+    }
+
     pub fn get_piece_type_from_index(&self, index: u8) -> PieceType {
+        if let Some(square) = index_to_square(index) {
+            return PieceType::from_usize((self.mailbox[square as usize] & 0x3F) as usize);
+        }
+        // 枠のマスはmailboxの対象外なのでビットボードを調べる
         for piece_type in PieceType::iter() {
             if self.is_a_has_specific_piece(index, piece_type) {
                 return piece_type;
@@ -448,12 +650,13 @@ impl Board {
 
     pub fn to_string(&self) -> String {
         let mut result = String::new();
-        for row in (1..=LENGTH_OF_EDGE).rev() {
-            if row < 9 {
+        let height = self.variant.height();
+        for row in (1..=height).rev() {
+            if row < height {
                 result.push('/');
             }
             let mut empty_count = 0;
-            for col in 1..=LENGTH_OF_EDGE {
+            for col in 1..=self.variant.width() {
                 let index = Address::from_numbers(col, row).to_index();
                 let piece = self.get_piece(index);
                 if piece.piece_type != PieceType::None {
@@ -474,29 +677,29 @@ impl Board {
         result
     }
 
+    /// 指定したマスの駒が動けるマス（利きの表を引いて求める）
     pub fn get_able_move_squares(&self, index: u8) -> BitBoard {
+        let piece_type = self.get_piece_type_from_index(index);
+        if piece_type == PieceType::None {
+            return BitBoard::new();
+        }
+        let color_type = self.get_color_type_from_index(index);
+        // 盤は長方形なので、盤の外へ出た飛び利きが盤に戻ることはない
+        let attacks = attack::attacks_from(piece_type, color_type, index, self.has_piece)
+            & self.variant.playable();
+        BitBoard::from_u128(
+            attacks.to_u128() & !self.player_prossesion[color_type as usize].to_u128(),
+        )
+    }
+
+    /// `MovePattern` を1マスずつたどって動けるマスを求める（検算とベンチマーク用）
+    pub fn get_able_move_squares_by_pattern(&self, index: u8) -> BitBoard {
         let piece_type = self.get_piece_type_from_index(index);
         let color_type = self.get_color_type_from_index(index);
         let bit_board = BitBoard::from_u128(1u128 << (127 - index));
         let mut bit_movable = BitBoard::new();
 
-        let move_patterns = match piece_type {
-            PieceType::King => move_pattern::KING_MOVE_PATTERNS,
-            PieceType::Gold => move_pattern::GOLD_MOVE_PATTERNS,
-            PieceType::Rook => move_pattern::ROOK_MOVE_PATTERNS,
-            PieceType::Bichop => move_pattern::BICHOP_MOVE_PATTERNS,
-            PieceType::Silver => move_pattern::SILVER_MOVE_PATTERNS,
-            PieceType::Knight => move_pattern::KNIGHT_MOVE_PATTERNS,
-            PieceType::Lance => move_pattern::LANCE_MOVE_PATTERNS,
-            PieceType::Pawn => move_pattern::PAWN_MOVE_PATTERNS,
-            PieceType::Dragon => move_pattern::DRAGON_MOVE_PATTERNS,
-            PieceType::Horse => move_pattern::HORSE_MOVE_PATTERNS,
-            PieceType::ProSilver => move_pattern::PRO_SILVER_MOVE_PATTERNS,
-            PieceType::ProKnight => move_pattern::PRO_KNIGHT_MOVE_PATTERNS,
-            PieceType::ProLance => move_pattern::PRO_LANCE_MOVE_PATTERNS,
-            PieceType::ProPawn => move_pattern::PRO_PAWN_MOVE_PATTERNS,
-            _ => &[],
-        };
+        let move_patterns = move_pattern::get_move_patterns(piece_type);
 
         for pattern in move_patterns {
             let mut direction = Direction::new(pattern.direction);
@@ -514,7 +717,7 @@ impl Board {
                         bit_board >> shift_number.unsigned_abs() as usize
                     };
 
-                    if (self.is_frame & target_board) == BitBoard::new()
+                    if (FRAME & target_board) == BitBoard::new()
                         && (self.player_prossesion[color_type as usize] & target_board)
                             == BitBoard::new()
                     {
@@ -532,7 +735,7 @@ impl Board {
                             bit_board >> shift_number.unsigned_abs() as usize
                         };
 
-                        if (self.is_frame & target_board) != BitBoard::new() {
+                        if (FRAME & target_board) != BitBoard::new() {
                             break;
                         }
                         if (self.player_prossesion[color_type as usize] & target_board)
@@ -566,7 +769,7 @@ impl Board {
                         bit_board >> shift_number.unsigned_abs() as usize
                     };
 
-                    if (self.is_frame & target_board) == BitBoard::new()
+                    if (FRAME & target_board) == BitBoard::new()
                         && (self.player_prossesion[color_type as usize] & target_board)
                             == BitBoard::new()
                     {
@@ -590,7 +793,7 @@ impl Board {
             return result;
         }
 
-        let pro_area = self.able_pro[color_type as usize];
+        let pro_area = self.variant.pro_zone(color_type);
 
         if (bit_board & pro_area) != BitBoard::new() {
             return bit_movable;
@@ -601,8 +804,8 @@ impl Board {
 
     pub fn get_able_drop_squares(&self, color: ColorType, piece_type: PieceType) -> BitBoard {
         let none = self.has_specific_piece[PieceType::None as usize];
-        let mut last_not_two = self.last_two[color as usize];
-        let mut last_not_one = self.last_one[color as usize];
+        let mut last_not_two = self.variant.last2_zone(color);
+        let mut last_not_one = self.variant.last1_zone(color);
         last_not_two.flip();
         last_not_one.flip();
 
@@ -638,7 +841,17 @@ impl Board {
             return moves.clone();
         }
 
-        let mut vector_move: Vec<Move> = Vec::with_capacity(128);
+        let mut move_list = MoveList::new();
+        self.search_moves_into(color, &mut move_list);
+        let vector_move = move_list.to_vec();
+
+        cache.put((self.clone(), color), vector_move.clone());
+        vector_move
+    }
+
+    /// `search_moves` と同じ手を同じ順に `moves` へ書き込む（キャッシュを使わず、確保もしない）
+    pub fn search_moves_into(&self, color: ColorType, moves: &mut MoveList) {
+        moves.clear();
 
         let player_board = if color.to_bool() {
             &self.player_prossesion[ColorType::White as usize]
@@ -648,7 +861,7 @@ impl Board {
 
         for player_board_index in player_board.get_trues_iter() {
             let move_board = self.get_able_move_squares(player_board_index);
-            vector_move.extend(move_board.get_trues_iter().map(|move_index| {
+            moves.extend(move_board.get_trues_iter().map(|move_index| {
                 Move::from_standart(
                     Address::from_number(player_board_index),
                     Address::from_number(move_index),
@@ -657,7 +870,7 @@ impl Board {
             }));
 
             let pro_board = self.get_able_pro_move_squares(player_board_index, move_board);
-            vector_move.extend(pro_board.get_trues_iter().map(|move_index| {
+            moves.extend(pro_board.get_trues_iter().map(|move_index| {
                 Move::from_standart(
                     Address::from_number(player_board_index),
                     Address::from_number(move_index),
@@ -666,20 +879,19 @@ impl Board {
             }));
         }
 
-        let player_hand_pieces = self.hand.get_player_pieces(color);
-        for player_hand_piece in player_hand_pieces {
+        for player_hand_piece in self.hand.player_pieces(color) {
             let move_board =
                 self.get_able_drop_squares(player_hand_piece.owner, player_hand_piece.piece_type);
-            vector_move.extend(move_board.get_trues_iter().map(|move_index| {
+            moves.extend(move_board.get_trues_iter().map(|move_index| {
                 Move::from_drop(player_hand_piece, Address::from_number(move_index))
             }));
         }
-
-        cache.put((self.clone(), color), vector_move.clone());
-        vector_move
     }
 
     pub fn execute_move(&mut self, moves: &Move) {
+        if moves.get_is_pass() {
+            return;
+        }
         let is_drop = moves.get_is_drop();
         let to_index = moves.get_to().to_index();
         let mut piece = Piece::new();
@@ -698,10 +910,480 @@ impl Board {
         if is_drop {
             self.move_from_hand(to_index, piece.piece_type, piece.owner);
         } else {
-            self.move_standard(from_index, to_index);
+            self.move_standard(from_index, to_index, moves.get_is_promote());
         }
     }
 
+    /// 手を指し、戻すのに必要な情報を持つ `ExtendedMove` を返す
+    pub fn make_move(&mut self, mv: &Move) -> ExtendedMove {
+        let extended = ExtendedMove::from_move(mv, self);
+        self.execute_move(mv);
+        extended
+    }
+
+    /// `make_move` で指した手を戻す
+    pub fn undo_move(&mut self, mv: &ExtendedMove) {
+        if mv.to_move().get_is_pass() {
+            return;
+        }
+        let to_index = mv.get_to().to_index();
+        let moved = mv.get_moved_piece();
+        self.drop(to_index);
+
+        if mv.get_is_drop() {
+            self.hand.add_piece(moved.owner, moved.piece_type);
+            return;
+        }
+
+        self.deploy(mv.get_from().to_index(), moved.piece_type, moved.owner);
+        let captured = mv.get_captured_piece();
+        if captured.piece_type != PieceType::None {
+            self.deploy(to_index, captured.piece_type, captured.owner);
+            self.hand
+                .decrease_piece(moved.owner, captured.piece_type.demote());
+        }
+    }
+
+    /// `occupied` を盤上の駒配置とみなしたときに、指定マスへ利いている `color` 側の駒を返す
+    pub fn attackers_to(&self, index: u8, color: ColorType, occupied: BitBoard) -> BitBoard {
+        let mut attackers = BitBoard::new();
+        let own = self.player_prossesion[color as usize] & occupied;
+        let reverse = get_reverse_color(color);
+
+        for piece_type in PieceType::iter().skip(1) {
+            let pieces = self.has_specific_piece[piece_type as usize] & own;
+            if pieces == BitBoard::new() {
+                continue;
+            }
+            // 逆向きの利きと重なる駒が、そのマスに利いている
+            attackers |= attack::attacks_from(piece_type, reverse, index, occupied) & pieces;
+        }
+        attackers
+    }
+
+    /// 盤上の駒と持ち駒から計算した局面のハッシュ値（手番は含まない）
+    pub fn hash_key(&self) -> u64 {
+        let mut key = 0;
+        for color in [ColorType::Black, ColorType::White] {
+            let own = self.player_prossesion[color as usize];
+            for piece_type in PieceType::iter().skip(1) {
+                let pieces = self.has_specific_piece[piece_type as usize] & own;
+                for index in pieces.get_trues_iter() {
+                    key ^= ZOBRIST.board_key(color, piece_type as usize, index);
+                }
+                if piece_type as u8 <= PROMOTE {
+                    key ^= ZOBRIST.hand_key(
+                        color,
+                        piece_type as usize,
+                        self.hand.get_count(color, piece_type),
+                    );
+                }
+            }
+        }
+        key
+    }
+
+    pub fn get_king_index(&self, color: ColorType) -> Option<u8> {
+        let king = self.has_specific_piece[PieceType::King as usize]
+            & self.player_prossesion[color as usize];
+        let bits = king.to_u128();
+        if bits == 0 {
+            None
+        } else {
+            Some(bits.leading_zeros() as u8)
+        }
+    }
+
+    /// `color` 側の玉に相手の駒が利いているかを返す
+    pub fn is_in_check(&self, color: ColorType) -> bool {
+        match self.get_king_index(color) {
+            Some(king_index) => {
+                self.attackers_to(king_index, get_reverse_color(color), self.has_piece)
+                    != BitBoard::new()
+            }
+            None => false,
+        }
+    }
+
+    /// 手を指すと相手玉に王手がかかるかを、盤面を複製せずに返す
+    ///
+    /// 動かした駒による直接の王手と、駒が退いたことによる開き王手の両方を調べる。
+    pub fn gives_check(&self, mv: &Move) -> bool {
+        if mv.get_is_pass() {
+            return false;
+        }
+        let to_index = mv.get_to().to_index();
+        let to_bit = BitBoard::from_u128(1u128 << (127 - to_index));
+        let (color, piece_type, occupied) = if mv.get_is_drop() {
+            let piece = mv.get_piece();
+            (piece.owner, piece.piece_type, self.has_piece | to_bit)
+        } else {
+            let from_index = mv.get_from().to_index();
+            let not_from_bit = BitBoard::from_u128(!(1u128 << (127 - from_index)));
+            let mut piece_type = self.get_piece_type_from_index(from_index);
+            if mv.get_is_promote() {
+                piece_type = piece_type.promote();
+            }
+            (
+                self.get_color_type_from_index(from_index),
+                piece_type,
+                (self.has_piece & not_from_bit) | to_bit,
+            )
+        };
+        // 移動元に駒がない手は王手にならない
+        if color == ColorType::None {
+            return false;
+        }
+        let king_index = match self.get_king_index(get_reverse_color(color)) {
+            Some(king_index) => king_index,
+            None => return false,
+        };
+        let king_bit = BitBoard::from_u128(1u128 << (127 - king_index));
+
+        if (attack::attacks_from(piece_type, color, to_index, occupied) & king_bit)
+            != BitBoard::new()
+        {
+            return true;
+        }
+        // 動かした駒は `occupied` から外れているので、残りの駒の利きが開き王手になる
+        !mv.get_is_drop() && self.attackers_to(king_index, color, occupied) != BitBoard::new()
+    }
+
+    /// 手を指した後に自玉へ相手の駒が利いていないかを返す（盤面を複製しない）
+    ///
+    /// パスは手番の情報を持たないので常に `true` を返す。
+    pub fn is_legal(&self, mv: &Move) -> bool {
+        if mv.get_is_pass() {
+            return true;
+        }
+        let to_index = mv.get_to().to_index();
+        let to_bit = BitBoard::from_u128(1u128 << (127 - to_index));
+        let (color, king_index, occupied) = if mv.get_is_drop() {
+            let color = mv.get_piece().owner;
+            (color, self.get_king_index(color), self.has_piece | to_bit)
+        } else {
+            let from_index = mv.get_from().to_index();
+            let color = self.get_color_type_from_index(from_index);
+            let king_index = if self.get_piece_type_from_index(from_index) == PieceType::King {
+                Some(to_index)
+            } else {
+                self.get_king_index(color)
+            };
+            let not_from_bit = BitBoard::from_u128(!(1u128 << (127 - from_index)));
+            (color, king_index, (self.has_piece & not_from_bit) | to_bit)
+        };
+        let king_index = match king_index {
+            Some(king_index) => king_index,
+            None => return true,
+        };
+        // 移動先で取られた駒は利きを持たない
+        let not_to_bit = BitBoard::from_u128(!to_bit.to_u128());
+        (self.attackers_to(king_index, get_reverse_color(color), occupied) & not_to_bit)
+            == BitBoard::new()
+    }
+
+    /// `color` から指して `depth` 手先までの、自玉に王手が残らない手順の数（手の生成の検算用）
+    pub fn perft(&self, color: ColorType, depth: u32) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+        let mut moves = MoveList::new();
+        self.search_moves_into(color, &mut moves);
+        let mut board = self.clone();
+        let mut nodes = 0;
+        for mv in moves.iter().filter(|mv| self.is_legal(mv)) {
+            if depth == 1 {
+                nodes += 1;
+                continue;
+            }
+            let extended = board.make_move(mv);
+            nodes += board.perft(get_reverse_color(color), depth - 1);
+            board.undo_move(&extended);
+        }
+        nodes
+    }
+
+    /// やねうら王のPackedSfen（32バイト）に符号化する
+    pub fn to_packed_sfen(&self, turn: ColorType) -> Result<[u8; 32], String> {
+        packed_sfen::pack_position(self, turn, PackedFormat::PackedSfen)
+    }
+
+    /// PackedSfenから盤面と手番を作る
+    pub fn from_packed_sfen(data: &[u8]) -> Result<(Self, ColorType), String> {
+        packed_sfen::unpack_position(data, PackedFormat::PackedSfen)
+    }
+
+    /// Apery・dlshogiのHuffmanCodedPos（32バイト）に符号化する
+    pub fn to_hcp(&self, turn: ColorType) -> Result<[u8; 32], String> {
+        packed_sfen::pack_position(self, turn, PackedFormat::HuffmanCodedPos)
+    }
+
+    /// HuffmanCodedPosから盤面と手番を作る
+    pub fn from_hcp(data: &[u8]) -> Result<(Self, ColorType), String> {
+        packed_sfen::unpack_position(data, PackedFormat::HuffmanCodedPos)
+    }
+
+    /// 駒の位置を `transform` で移し、`swap` なら先後を入れ替えた盤
+    fn transformed(&self, transform: impl Fn(Address) -> Address, swap: bool) -> Self {
+        let mut board = Self::with_variant(self.variant);
+        for color in [ColorType::Black, ColorType::White] {
+            let owner = if swap {
+                get_reverse_color(color)
+            } else {
+                color
+            };
+            for &index in self.get_piece_list(color) {
+                let to = transform(Address::from_number(index)).to_index();
+                board.deploy(to, self.get_piece_type_from_index(index), owner);
+            }
+            for piece in self.hand.player_pieces(color) {
+                let count = self.hand.get_count(color, piece.piece_type);
+                board.hand.add_pieces(owner, piece.piece_type, count);
+            }
+        }
+        board
+    }
+
+    /// 左右を反転した盤（データ拡張用）
+    pub fn mirrored(&self) -> Self {
+        let width = self.variant.width();
+        self.transformed(|address| address.mirrored(width), false)
+    }
+
+    /// 180度回して先後と持ち駒を入れ替えた盤（データ拡張用）
+    ///
+    /// 元の盤の先手番の局面は、この盤の後手番の局面と同じ形になる。
+    pub fn flipped(&self) -> Self {
+        let (width, height) = (self.variant.width(), self.variant.height());
+        self.transformed(|address| address.rotated(width, height), true)
+    }
+
+    /// `color` 側の王手になる手（相手玉を取る手は含まない）
+    ///
+    /// 盤上の駒を動かす手は `gives_check` で調べ、駒打ちは玉から逆向きに引いた利きと
+    /// 打てるマスの重なりから求める。自玉が取られる手も含む（`search_moves` と同じ疑似合法手）。
+    pub fn generate_checks(&self, color: ColorType) -> Vec<Move> {
+        let enemy = get_reverse_color(color);
+        let king_index = match self.get_king_index(enemy) {
+            Some(king_index) => king_index,
+            None => return Vec::new(),
+        };
+        let not_king_bit = BitBoard::from_u128(!(1u128 << (127 - king_index)));
+
+        let mut moves = Vec::new();
+        for &from_index in self.get_piece_list(color) {
+            let movable = self.get_able_move_squares(from_index) & not_king_bit;
+            let promotable = self.get_able_pro_move_squares(from_index, movable);
+            for (squares, promote) in [(movable, false), (promotable, true)] {
+                moves.extend(
+                    squares
+                        .get_trues_iter()
+                        .map(|to_index| {
+                            Move::from_standart(
+                                Address::from_number(from_index),
+                                Address::from_number(to_index),
+                                promote,
+                            )
+                        })
+                        .filter(|mv| self.gives_check(mv)),
+                );
+            }
+        }
+
+        for piece in self.hand.get_player_pieces(color) {
+            let squares = self.get_able_drop_squares(color, piece.piece_type)
+                & attack::attacks_from(piece.piece_type, enemy, king_index, self.has_piece);
+            moves.extend(
+                squares
+                    .get_trues_iter()
+                    .map(|to_index| Move::from_drop(piece, Address::from_number(to_index))),
+            );
+        }
+        moves
+    }
+
+    /// 王手をかけられている `color` 側の合法な応手（玉の移動、王手した駒を取る手、合駒）
+    ///
+    /// 王手をかけられていなければ空を返す。
+    pub fn generate_evasions(&self, color: ColorType) -> Vec<Move> {
+        let king_index = match self.get_king_index(color) {
+            Some(king_index) => king_index,
+            None => return Vec::new(),
+        };
+        let checkers = self
+            .attackers_to(king_index, get_reverse_color(color), self.has_piece)
+            .get_trues();
+        if checkers.is_empty() {
+            return Vec::new();
+        }
+
+        let mut moves: Vec<Move> = self
+            .get_able_move_squares(king_index)
+            .get_trues_iter()
+            .map(|to_index| {
+                Move::from_standart(
+                    Address::from_number(king_index),
+                    Address::from_number(to_index),
+                    false,
+                )
+            })
+            .filter(|mv| self.is_legal(mv))
+            .collect();
+        // 両王手は玉を動かすしかない
+        if checkers.len() > 1 {
+            return moves;
+        }
+
+        let checker_index = checkers[0];
+        let between = squares_between(checker_index, king_index);
+        let target = between | BitBoard::from_u128(1u128 << (127 - checker_index));
+        for &from_index in self.get_piece_list(color) {
+            if from_index == king_index {
+                continue;
+            }
+            let movable = self.get_able_move_squares(from_index) & target;
+            let promotable = self.get_able_pro_move_squares(from_index, movable);
+            for (squares, promote) in [(movable, false), (promotable, true)] {
+                moves.extend(
+                    squares
+                        .get_trues_iter()
+                        .map(|to_index| {
+                            Move::from_standart(
+                                Address::from_number(from_index),
+                                Address::from_number(to_index),
+                                promote,
+                            )
+                        })
+                        .filter(|mv| self.is_legal(mv)),
+                );
+            }
+        }
+
+        // 合駒は利きを遮るだけなので、王手が1つならそのまま合法になる
+        for piece in self.hand.get_player_pieces(color) {
+            let squares = self.get_able_drop_squares(color, piece.piece_type) & between;
+            moves.extend(
+                squares
+                    .get_trues_iter()
+                    .map(|to_index| Move::from_drop(piece, Address::from_number(to_index))),
+            );
+        }
+        moves
+    }
+
+    /// 盤上の駒と持ち駒の価値の合計の差（`color` 側から見た駒得）
+    pub fn evaluate(&self, color: ColorType) -> i32 {
+        let mut score = 0;
+        for piece_type in PieceType::iter().skip(2) {
+            let pieces = self.has_specific_piece[piece_type as usize];
+            let value = Piece::get_value(piece_type);
+            score += value
+                * ((pieces & self.player_prossesion[ColorType::Black as usize]).count_ones()
+                    as i32
+                    - (pieces & self.player_prossesion[ColorType::White as usize]).count_ones()
+                        as i32);
+            if piece_type as u8 <= PROMOTE {
+                score += value
+                    * (self.hand.get_count(ColorType::Black, piece_type) as i32
+                        - self.hand.get_count(ColorType::White, piece_type) as i32);
+            }
+        }
+        if color == ColorType::White {
+            -score
+        } else {
+            score
+        }
+    }
+
+    fn get_least_valuable_attacker(&self, attackers: BitBoard) -> Option<(u8, PieceType)> {
+        let mut least: Option<(u8, PieceType)> = None;
+        for piece_type in PieceType::iter().skip(1) {
+            let pieces = self.has_specific_piece[piece_type as usize] & attackers;
+            if pieces == BitBoard::new() {
+                continue;
+            }
+            let is_less = match least {
+                Some((_, current)) => Piece::get_value(piece_type) < Piece::get_value(current),
+                None => true,
+            };
+            if is_less {
+                least = Some((pieces.get_trues()[0], piece_type));
+            }
+        }
+        least
+    }
+
+    /// 静的交換評価（SEE）
+    ///
+    /// 移動先のマスで取り合いを最後まで進めたときの、手を指す側から見た駒得を返す。
+    /// 飛び駒の背後からの利き（x-ray）と、取り返しの際の成りによる駒得も考慮する。
+    pub fn see(&self, mv: &Move) -> i32 {
+        let to_index = mv.get_to().to_index();
+        let to_bit = BitBoard::from_u128(1u128 << (127 - to_index));
+        let mut gains: Vec<i32> = Vec::with_capacity(32);
+
+        let (mut color, mut piece_on_to, mut occupied) = if mv.get_is_drop() {
+            let piece = mv.get_piece();
+            gains.push(0);
+            (piece.owner, piece.piece_type, self.has_piece | to_bit)
+        } else {
+            let from_index = mv.get_from().to_index();
+            let not_from_bit = BitBoard::from_u128(!(1u128 << (127 - from_index)));
+            let mut piece_type = self.get_piece_type_from_index(from_index);
+            let mut gain = Piece::get_value(self.get_piece_type_from_index(to_index));
+            if mv.get_is_promote() {
+                gain += Piece::get_value(piece_type.promote()) - Piece::get_value(piece_type);
+                piece_type = piece_type.promote();
+            }
+            gains.push(gain);
+            (
+                self.get_color_type_from_index(from_index),
+                piece_type,
+                self.has_piece & not_from_bit,
+            )
+        };
+
+        loop {
+            color = get_reverse_color(color);
+            let attackers = self.attackers_to(to_index, color, occupied);
+            let (attacker_index, attacker_type) = match self.get_least_valuable_attacker(attackers)
+            {
+                Some(attacker) => attacker,
+                None => break,
+            };
+            let attacker_bit = BitBoard::from_u128(1u128 << (127 - attacker_index));
+            let not_attacker_bit = BitBoard::from_u128(!(1u128 << (127 - attacker_index)));
+
+            // 玉は相手の利きが残っているマスでは取り返せない
+            if attacker_type == PieceType::King
+                && self.attackers_to(to_index, get_reverse_color(color), occupied)
+                    != BitBoard::new()
+            {
+                break;
+            }
+
+            let mut gain = Piece::get_value(piece_on_to) - gains[gains.len() - 1];
+            let mut next_piece = attacker_type;
+            let pro_area = self.variant.pro_zone(color);
+            if Piece::able_pro(attacker_type)
+                && ((to_bit & pro_area) != BitBoard::new()
+                    || (attacker_bit & pro_area) != BitBoard::new())
+            {
+                gain += Piece::get_value(attacker_type.promote()) - Piece::get_value(attacker_type);
+                next_piece = attacker_type.promote();
+            }
+            gains.push(gain);
+            piece_on_to = next_piece;
+            occupied &= not_attacker_bit;
+        }
+
+        // 各手番は取り合いを続けるか途中で止めるかを選べる
+        for i in (1..gains.len()).rev() {
+            gains[i - 1] = -std::cmp::max(-gains[i - 1], gains[i]);
+        }
+        gains[0]
+    }
+
     pub fn is_finished(&self) -> (bool, ColorType) {
         let winner;
         let is_finish = self.has_specific_piece[PieceType::King as usize]
@@ -723,75 +1405,61 @@ impl Board {
         (is_finish, winner)
     }
 
-    pub fn to_vector(&self, target_dimensions: Option<usize>) -> Vec<f32> {
-        // 全特徴量を取得
-        let mut features = Vec::with_capacity(2320);
-
-        // 基本盤面情報 (2304次元) - 各マスについて全てのBitBoardの値を連続配置
-        let mut board_vector = [0f32; 2304];
-
-        let bitboards = [
-            self.has_piece,
-            self.player_prossesion[ColorType::White as usize],
-            self.player_prossesion[ColorType::Black as usize],
-            self.has_specific_piece[PieceType::None as usize],
-            self.has_specific_piece[PieceType::King as usize],
-            self.has_specific_piece[PieceType::Gold as usize],
-            self.has_specific_piece[PieceType::Rook as usize],
-            self.has_specific_piece[PieceType::Bichop as usize],
-            self.has_specific_piece[PieceType::Silver as usize],
-            self.has_specific_piece[PieceType::Knight as usize],
-            self.has_specific_piece[PieceType::Lance as usize],
-            self.has_specific_piece[PieceType::Pawn as usize],
-            self.has_specific_piece[PieceType::Dragon as usize],
-            self.has_specific_piece[PieceType::Horse as usize],
-            self.has_specific_piece[PieceType::ProSilver as usize],
-            self.has_specific_piece[PieceType::ProKnight as usize],
-            self.has_specific_piece[PieceType::ProLance as usize],
-            self.has_specific_piece[PieceType::ProPawn as usize],
-        ];
-
-        // 各マス（index）について、そのマスに関連する全てのBitBoardの値を連続配置
-        for index in 0..128 {
-            let mut bitboard_offset = 0;
-            for bitboard in &bitboards {
-                let u128_value = bitboard.to_u128();
-                board_vector[index * 18 + bitboard_offset] =
-                    if (u128_value >> (127 - index)) & 1 != 0 {
-                        1.0
-                    } else {
-                        0.0
-                    };
-                bitboard_offset += 1;
-            }
-        }
+    /// 特徴量を返す。`target_dimensions` がそれより小さければグローバルなPCA変換で圧縮する
+    ///
+    /// その次元数の主成分を持つグローバルなPCA変換がなければエラーにする。
+    /// 設定を変えるときは `FeatureExtractor::extract` を使う。
+    pub fn to_vector(&self, target_dimensions: Option<usize>) -> Result<Vec<f32>, String> {
+        FeatureExtractor::from_global(target_dimensions)?.extract(self)
+    }
 
-        features.extend_from_slice(&board_vector);
+    /// `to_vector(target_dimensions)` が返す特徴量の次元数
+    pub fn vector_dimensions(target_dimensions: Option<usize>) -> usize {
+        target_dimensions
+            .filter(|target_dims| *target_dims < VECTOR_DIMENSIONS)
+            .unwrap_or(VECTOR_DIMENSIONS)
+    }
 
-        // 持ち駒情報 (16次元: 2色 × 8駒種)
-        let hand_features = self.hand.to_vector();
-        features.extend_from_slice(&hand_features);
+    /// 複数の盤面の `to_vector` を、1行1局面の連続した配列に並べて返す
+    ///
+    /// 行ごとに `num_threads` 個のスレッドで並列に作る。SFENはそのスレッドの中で読む。
+    pub fn to_vector_batch(
+        sources: &[BoardSource],
+        target_dimensions: Option<usize>,
+        num_threads: usize,
+    ) -> Result<Vec<f32>, String> {
+        FeatureExtractor::from_global(target_dimensions)?.extract_batch(sources, num_threads)
+    }
+}
 
-        // 合計: 2304 + 16 = 2320次元
+/// 特徴量をまとめて作るときの入力（盤面かSFEN）
+///
+/// Pythonのリストから取り出すときに盤面をそのまま複製できるよう、`Board` は箱に入れない。
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug, FromPyObject)]
+pub enum BoardSource {
+    Board(Board),
+    Sfen(String),
+}
 
-        // 次元圧縮が必要な場合
-        if let Some(target_dims) = target_dimensions {
-            if target_dims < features.len() {
-                // PCAによる次元圧縮
-                return apply_pca_compression(&features, target_dims);
-            }
-        }
+impl From<Board> for BoardSource {
+    fn from(board: Board) -> Self {
+        BoardSource::Board(board)
+    }
+}
 
-        features
+impl From<String> for BoardSource {
+    fn from(sfen: String) -> Self {
+        BoardSource::Sfen(sfen)
     }
 }
 
 #[pymethods]
 impl Board {
     #[new]
-    #[pyo3(signature = (sfen = "startpos".to_string()))]
-    pub fn new_for_python(sfen: String) -> Self {
-        Self::from_sfen(sfen)
+    #[pyo3(signature = (sfen = "startpos".to_string(), variant = Variant::Standard))]
+    pub fn new_for_python(sfen: String, variant: Variant) -> Self {
+        Self::from_sfen_with_variant(sfen, variant)
     }
 
     pub fn __repr__(&self) -> String {
@@ -803,25 +1471,23 @@ impl Board {
     }
 
     pub fn __eq__(&self, other: &Self) -> bool {
-        self.has_piece == other.has_piece
-            && self.player_prossesion == other.player_prossesion
-            && self.is_frame == other.is_frame
-            && self.able_pro == other.able_pro
-            && self.last_one == other.last_one
-            && self.last_two == other.last_two
-            && self.has_specific_piece == other.has_specific_piece
-            && self.hand == other.hand
+        self == other
     }
 
     pub fn __ne__(&self, other: &Self) -> bool {
-        self.has_piece != other.has_piece
-            || self.player_prossesion != other.player_prossesion
-            || self.is_frame != other.is_frame
-            || self.able_pro != other.able_pro
-            || self.last_one != other.last_one
-            || self.last_two != other.last_two
-            || self.has_specific_piece != other.has_specific_piece
-            || self.hand != other.hand
+        self != other
+    }
+
+    #[getter]
+    #[pyo3(name = "variant")]
+    pub fn python_variant(&self) -> Variant {
+        self.variant
+    }
+
+    #[staticmethod]
+    #[pyo3(name = "from_handicap")]
+    pub fn python_from_handicap(name: &str) -> PyResult<Self> {
+        Self::from_handicap(name).map_err(pyo3::exceptions::PyValueError::new_err)
     }
 
     #[pyo3(name = "deploy")]
@@ -829,6 +1495,14 @@ impl Board {
         self.deploy(address.to_index(), piece_type, color);
     }
 
+    #[pyo3(name = "get_pieces")]
+    pub fn python_get_pieces(&self, color: ColorType) -> Vec<(Address, Piece)> {
+        self.get_piece_list(color)
+            .iter()
+            .map(|index| (Address::from_number(*index), self.get_piece(*index)))
+            .collect()
+    }
+
     #[pyo3(name = "startpos")]
     pub fn python_startpos(&mut self) {
         self.startpos();
@@ -844,6 +1518,92 @@ impl Board {
         self.search_moves(color)
     }
 
+    #[pyo3(name = "search_moves_ordered")]
+    pub fn python_search_moves_ordered(&self, color: ColorType) -> Vec<Move> {
+        move_picker::ordered_moves(self, color)
+    }
+
+    #[pyo3(name = "gives_check")]
+    pub fn python_gives_check(&self, mv: &Move) -> bool {
+        self.gives_check(mv)
+    }
+
+    #[pyo3(name = "generate_checks")]
+    pub fn python_generate_checks(&self, color: ColorType) -> Vec<Move> {
+        self.generate_checks(color)
+    }
+
+    #[pyo3(name = "generate_evasions")]
+    pub fn python_generate_evasions(&self, color: ColorType) -> Vec<Move> {
+        self.generate_evasions(color)
+    }
+
+    #[pyo3(name = "is_legal")]
+    pub fn python_is_legal(&self, mv: &Move) -> bool {
+        self.is_legal(mv)
+    }
+
+    #[pyo3(name = "perft")]
+    pub fn python_perft(&self, color: ColorType, depth: u32) -> u64 {
+        self.perft(color, depth)
+    }
+
+    #[pyo3(name = "mirrored")]
+    pub fn python_mirrored(&self) -> Self {
+        self.mirrored()
+    }
+
+    #[pyo3(name = "flipped")]
+    pub fn python_flipped(&self) -> Self {
+        self.flipped()
+    }
+
+    #[pyo3(name = "to_packed_sfen")]
+    pub fn python_to_packed_sfen<'py>(
+        &self,
+        py: Python<'py>,
+        turn: ColorType,
+    ) -> PyResult<Bound<'py, PyBytes>> {
+        let data = self
+            .to_packed_sfen(turn)
+            .map_err(pyo3::exceptions::PyValueError::new_err)?;
+        Ok(PyBytes::new(py, &data))
+    }
+
+    #[staticmethod]
+    #[pyo3(name = "from_packed_sfen")]
+    pub fn python_from_packed_sfen(data: &[u8]) -> PyResult<(Self, ColorType)> {
+        Self::from_packed_sfen(data).map_err(pyo3::exceptions::PyValueError::new_err)
+    }
+
+    #[pyo3(name = "to_hcp")]
+    pub fn python_to_hcp<'py>(
+        &self,
+        py: Python<'py>,
+        turn: ColorType,
+    ) -> PyResult<Bound<'py, PyBytes>> {
+        let data = self
+            .to_hcp(turn)
+            .map_err(pyo3::exceptions::PyValueError::new_err)?;
+        Ok(PyBytes::new(py, &data))
+    }
+
+    #[staticmethod]
+    #[pyo3(name = "from_hcp")]
+    pub fn python_from_hcp(data: &[u8]) -> PyResult<(Self, ColorType)> {
+        Self::from_hcp(data).map_err(pyo3::exceptions::PyValueError::new_err)
+    }
+
+    #[pyo3(name = "make_move")]
+    pub fn python_make_move(&mut self, mv: &Move) -> ExtendedMove {
+        self.make_move(mv)
+    }
+
+    #[pyo3(name = "undo_move")]
+    pub fn python_undo_move(&mut self, mv: &ExtendedMove) {
+        self.undo_move(mv);
+    }
+
     #[pyo3(name = "execute_move")]
     pub fn python_execute_move(&mut self, moves: &Move) {
         self.execute_move(moves);
@@ -854,10 +1614,85 @@ impl Board {
         self.is_finished()
     }
 
+    #[pyo3(name = "see")]
+    pub fn python_see(&self, mv: &Move) -> i32 {
+        self.see(mv)
+    }
+
+    /// `extractor` を渡すとその設定で作り、グローバルなPCA変換は使わない
     #[pyo3(name = "to_vector")]
-    #[pyo3(signature = (target_dimensions = None))]
-    pub fn python_to_vector(&self, target_dimensions: Option<usize>) -> Vec<f32> {
-        self.to_vector(target_dimensions)
+    #[pyo3(signature = (target_dimensions = None, extractor = None))]
+    pub fn python_to_vector(
+        &self,
+        target_dimensions: Option<usize>,
+        extractor: Option<FeatureExtractor>,
+    ) -> PyResult<Vec<f32>> {
+        python_feature_extractor(target_dimensions, extractor)?
+            .extract(self)
+            .map_err(pyo3::exceptions::PyValueError::new_err)
+    }
+
+    /// 盤面かSFENのリストから `(局面数, 次元)` の特徴量の配列を作る（作る間はGILを解放する）
+    #[staticmethod]
+    #[pyo3(name = "to_vector_batch")]
+    #[pyo3(signature = (boards, target_dimensions = None, threads = None, extractor = None))]
+    pub fn python_to_vector_batch<'py>(
+        py: Python<'py>,
+        boards: Vec<BoardSource>,
+        target_dimensions: Option<usize>,
+        threads: Option<usize>,
+        extractor: Option<FeatureExtractor>,
+    ) -> PyResult<Bound<'py, PyArray2<f32>>> {
+        let extractor = python_feature_extractor(target_dimensions, extractor)?;
+        extractor.python_extract_batch(py, boards, threads)
+    }
+
+    /// `turn` 側の合法手のラベル（`Move.to_label`）に `True` を立てたNumPy配列
+    #[pyo3(name = "legal_move_mask")]
+    pub fn python_legal_move_mask<'py>(
+        &self,
+        py: Python<'py>,
+        turn: ColorType,
+    ) -> Bound<'py, PyArray1<bool>> {
+        move_label::legal_move_mask(self, turn).into_pyarray(py)
+    }
+
+    /// AlphaZero形式の入力平面を `(平面, 段, 筋)` のNumPy配列で返す
+    #[pyo3(name = "to_planes")]
+    #[pyo3(signature = (turn, orient = true, history = Vec::new(), history_length = 1, repetition = 0))]
+    pub fn python_to_planes<'py>(
+        &self,
+        py: Python<'py>,
+        turn: ColorType,
+        orient: bool,
+        history: Vec<Board>,
+        history_length: usize,
+        repetition: u32,
+    ) -> PyResult<Bound<'py, PyArray3<f32>>> {
+        let options = PlaneOptions {
+            history_length,
+            orient,
+        };
+        let data = planes::to_planes(self, &history, turn, repetition, &options)
+            .map_err(pyo3::exceptions::PyValueError::new_err)?;
+        let array = Array3::from_shape_vec(options.shape(self), data)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        Ok(array.into_pyarray(py))
+    }
+}
+
+/// Pythonの `to_vector` に渡された引数から抽出器を決める
+fn python_feature_extractor(
+    target_dimensions: Option<usize>,
+    extractor: Option<FeatureExtractor>,
+) -> PyResult<FeatureExtractor> {
+    match (target_dimensions, extractor) {
+        (Some(_), Some(_)) => Err(pyo3::exceptions::PyValueError::new_err(
+            "pass either target_dimensions or extractor, not both",
+        )),
+        (_, Some(extractor)) => Ok(extractor),
+        (target_dimensions, None) => FeatureExtractor::from_global(target_dimensions)
+            .map_err(pyo3::exceptions::PyValueError::new_err),
     }
 }
 
@@ -0,0 +1,248 @@
+use super::color::ColorType;
+use super::mctsresult::MctsResult;
+
+use pyo3::prelude::*;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// 別スレッドから探索を止めるためのフラグ（クローンは同じフラグを共有する）
+#[pyclass]
+#[derive(Clone, Debug, Default)]
+pub struct StopFlag {
+    flag: Arc<AtomicBool>,
+}
+
+impl StopFlag {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn stop(&self) {
+        self.flag.store(true, Ordering::SeqCst);
+    }
+
+    pub fn reset(&self) {
+        self.flag.store(false, Ordering::SeqCst);
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        self.flag.load(Ordering::SeqCst)
+    }
+}
+
+#[pymethods]
+impl StopFlag {
+    #[new]
+    pub fn new_for_python() -> Self {
+        Self::new()
+    }
+
+    #[pyo3(name = "stop")]
+    pub fn python_stop(&self) {
+        self.stop();
+    }
+
+    #[pyo3(name = "reset")]
+    pub fn python_reset(&self) {
+        self.reset();
+    }
+
+    #[pyo3(name = "is_stopped")]
+    pub fn python_is_stopped(&self) -> bool {
+        self.is_stopped()
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!("StopFlag(stopped={})", self.is_stopped())
+    }
+}
+
+/// 探索を打ち切る条件（時間・シミュレーション回数・持ち時間・停止フラグ）
+#[pyclass]
+#[derive(Clone, Debug)]
+pub struct SearchBudget {
+    #[pyo3(get, set)]
+    pub max_simulations: Option<usize>,
+    #[pyo3(get, set)]
+    pub max_time_ms: Option<u64>,
+    #[pyo3(get, set)]
+    pub remaining_ms: Option<u64>,
+    #[pyo3(get, set)]
+    pub increment_ms: u64,
+    #[pyo3(get, set)]
+    pub byoyomi_ms: u64,
+    #[pyo3(get, set)]
+    pub moves_to_go: u16,
+    #[pyo3(get, set)]
+    pub margin_ms: u64,
+    #[pyo3(get, set)]
+    pub early_stop: bool,
+    #[pyo3(get, set)]
+    pub batch_size: usize,
+    #[pyo3(get, set)]
+    pub stop_flag: Option<StopFlag>,
+}
+
+impl Default for SearchBudget {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SearchBudget {
+    pub fn new() -> Self {
+        Self {
+            max_simulations: None,
+            max_time_ms: None,
+            remaining_ms: None,
+            increment_ms: 0,
+            byoyomi_ms: 0,
+            moves_to_go: 30,
+            margin_ms: 50,
+            early_stop: false,
+            batch_size: 256,
+            stop_flag: None,
+        }
+    }
+
+    pub fn from_simulations(max_simulations: usize) -> Self {
+        Self {
+            max_simulations: Some(max_simulations),
+            ..Self::new()
+        }
+    }
+
+    pub fn from_time(max_time_ms: u64) -> Self {
+        Self {
+            max_time_ms: Some(max_time_ms),
+            ..Self::new()
+        }
+    }
+
+    /// 停止フラグ以外に終了条件がない場合は無制限に探索してしまうため、それを検出する
+    pub fn is_bounded(&self) -> bool {
+        self.max_simulations.is_some()
+            || self.max_time_ms.is_some()
+            || self.remaining_ms.is_some()
+            || self.stop_flag.is_some()
+    }
+
+    /// この1手に使う時間（ミリ秒）を決める
+    ///
+    /// 持ち時間がある場合は残り時間を `moves_to_go` 手で割り、加算時間と秒読みを足す。
+    /// 時間切れにならないよう `margin_ms` を残す。`max_time_ms` があれば小さい方を使う。
+    pub fn allocate_time_ms(&self) -> Option<u64> {
+        let clock_time = self.remaining_ms.map(|remaining| {
+            let base = remaining / self.moves_to_go.max(1) as u64 + self.increment_ms;
+            let limit = (remaining + self.byoyomi_ms).saturating_sub(self.margin_ms);
+            (base + self.byoyomi_ms).min(limit)
+        });
+        match (self.max_time_ms, clock_time) {
+            (Some(max_time), Some(clock_time)) => Some(max_time.min(clock_time)),
+            (Some(max_time), None) => Some(max_time),
+            (None, clock_time) => clock_time,
+        }
+    }
+
+    pub fn deadline(&self, start: Instant) -> Option<Instant> {
+        self.allocate_time_ms()
+            .map(|time_ms| start + Duration::from_millis(time_ms))
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        self.stop_flag
+            .as_ref()
+            .map(|flag| flag.is_stopped())
+            .unwrap_or(false)
+    }
+}
+
+#[pymethods]
+impl SearchBudget {
+    #[new]
+    #[pyo3(signature = (max_simulations = None, max_time_ms = None, remaining_ms = None, increment_ms = 0, byoyomi_ms = 0, moves_to_go = 30, margin_ms = 50, early_stop = false, batch_size = 256, stop_flag = None))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_for_python(
+        max_simulations: Option<usize>,
+        max_time_ms: Option<u64>,
+        remaining_ms: Option<u64>,
+        increment_ms: u64,
+        byoyomi_ms: u64,
+        moves_to_go: u16,
+        margin_ms: u64,
+        early_stop: bool,
+        batch_size: usize,
+        stop_flag: Option<StopFlag>,
+    ) -> Self {
+        Self {
+            max_simulations,
+            max_time_ms,
+            remaining_ms,
+            increment_ms,
+            byoyomi_ms,
+            moves_to_go,
+            margin_ms,
+            early_stop,
+            batch_size,
+            stop_flag,
+        }
+    }
+
+    #[pyo3(name = "allocate_time_ms")]
+    pub fn python_allocate_time_ms(&self) -> Option<u64> {
+        self.allocate_time_ms()
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!(
+            "SearchBudget(max_simulations={:?}, max_time_ms={:?}, remaining_ms={:?}, increment_ms={}, byoyomi_ms={}, early_stop={})",
+            self.max_simulations,
+            self.max_time_ms,
+            self.remaining_ms,
+            self.increment_ms,
+            self.byoyomi_ms,
+            self.early_stop
+        )
+    }
+}
+
+/// 残りのシミュレーションがどの手に割り振られても、勝率1位の手が入れ替わらないかを判定する
+///
+/// 手ごとのシミュレーション回数は揃わないので、手番側の勝ち数（引き分けは0.5勝）ではなく勝率で比べる。
+/// 1位の手は残りをすべて負けた場合、ほかの手は残りをすべて勝った場合の勝率を使う。
+pub fn is_leader_decided(results: &[MctsResult], turn: ColorType, remaining: u64) -> bool {
+    if results.len() < 2 {
+        return true;
+    }
+    // (勝ち数, シミュレーション回数)
+    let scores: Vec<(f64, f64)> = results
+        .iter()
+        .map(|result| {
+            let wins = if turn == ColorType::White {
+                result.white_wins
+            } else {
+                result.black_wins
+            };
+            (
+                wins as f64 + 0.5 * result.draws as f64,
+                result.total_games as f64,
+            )
+        })
+        .collect();
+    let win_rate = |wins: f64, games: f64| if games > 0.0 { wins / games } else { 0.0 };
+
+    let leader = (0..scores.len())
+        .max_by(|&a, &b| {
+            win_rate(scores[a].0, scores[a].1).total_cmp(&win_rate(scores[b].0, scores[b].1))
+        })
+        .unwrap();
+    let remaining = remaining as f64;
+    let (leader_wins, leader_games) = scores[leader];
+    let leader_worst = win_rate(leader_wins, leader_games + remaining);
+    scores
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| *index != leader)
+        .all(|(_, &(wins, games))| leader_worst > win_rate(wins + remaining, games + remaining))
+}
@@ -0,0 +1,402 @@
+use super::board::Board;
+use super::color::{get_reverse_color, ColorType};
+use super::moves::Move;
+use super::piece::PieceType;
+use super::zobrist::ZOBRIST;
+
+use pyo3::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+/// 証明数・反証数の無限大
+const INFINITY: u32 = u32::MAX / 2;
+
+#[pyclass(eq, eq_int)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum MateStatus {
+    /// 詰みを証明した
+    Proven = 0,
+    /// 不詰を証明した
+    Disproven,
+    /// ノード数の上限に達して判定できなかった
+    Unknown,
+}
+
+/// 詰将棋を解いた結果
+#[pyclass]
+#[derive(Clone, Debug)]
+pub struct MateResult {
+    #[pyo3(get)]
+    pub status: MateStatus,
+    #[pyo3(get)]
+    pub moves: Vec<Move>,
+    #[pyo3(get)]
+    pub nodes: u64,
+}
+
+#[pymethods]
+impl MateResult {
+    pub fn is_mate(&self) -> bool {
+        self.status == MateStatus::Proven
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!(
+            "MateResult(status={:?}, moves={}, nodes={})",
+            self.status,
+            self.moves.len(),
+            self.nodes
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Entry {
+    pn: u32,
+    dn: u32,
+    /// 詰みを証明した局面での詰みまでの手数
+    distance: u32,
+    /// 探索経路上の局面（千日手）に頼った不詰で、別の経路からは使い回せない
+    is_repetition: bool,
+}
+
+impl Entry {
+    fn unknown() -> Self {
+        Self {
+            pn: 1,
+            dn: 1,
+            distance: 0,
+            is_repetition: false,
+        }
+    }
+
+    fn proven(distance: u32) -> Self {
+        Self {
+            pn: 0,
+            dn: INFINITY,
+            distance,
+            is_repetition: false,
+        }
+    }
+
+    fn disproven() -> Self {
+        Self {
+            pn: INFINITY,
+            dn: 0,
+            distance: 0,
+            is_repetition: false,
+        }
+    }
+
+    fn repetition() -> Self {
+        Self {
+            is_repetition: true,
+            ..Self::disproven()
+        }
+    }
+}
+
+/// df-pn（深さ優先証明数探索）による詰将棋ソルバー
+///
+/// 攻め方は王手だけを指し、受け方は王手を外す手だけを指す。打ち歩詰めは攻め方の手から除き、
+/// 探索経路上に同じ局面が現れた場合は連続王手の千日手として不詰とみなす。
+/// この不詰は経路に依存するので置換表には保存せず、親の局面へ戻り値で伝える。
+#[pyclass]
+pub struct DfPnSolver {
+    #[pyo3(get, set)]
+    pub attacker: ColorType,
+    #[pyo3(get, set)]
+    pub max_nodes: u64,
+    nodes: u64,
+    table: HashMap<u64, Entry>,
+    path: Vec<u64>,
+}
+
+impl DfPnSolver {
+    pub fn new(attacker: ColorType, max_nodes: u64) -> Self {
+        Self {
+            attacker,
+            max_nodes,
+            nodes: 0,
+            table: HashMap::new(),
+            path: Vec::new(),
+        }
+    }
+
+    fn key(board: &Board, is_attacker_turn: bool) -> u64 {
+        if is_attacker_turn {
+            board.hash_key()
+        } else {
+            board.hash_key() ^ ZOBRIST.turn
+        }
+    }
+
+    fn lookup(&self, key: u64) -> Entry {
+        if self.path.contains(&key) {
+            return Entry::repetition();
+        }
+        self.table.get(&key).copied().unwrap_or_else(Entry::unknown)
+    }
+
+    /// 攻め方の合法な王手（打ち歩詰めを除く）
+    pub fn attack_moves(&self, board: &Board) -> Vec<Move> {
+        board
+            .generate_checks(self.attacker)
+            .into_iter()
+            .filter(|mv| {
+                if !board.is_legal(mv) {
+                    return false;
+                }
+                if !(mv.get_is_drop() && mv.get_piece().piece_type == PieceType::Pawn) {
+                    return true;
+                }
+                let mut next = board.clone();
+                next.execute_move(mv);
+                !self.evasion_moves(&next).is_empty()
+            })
+            .collect()
+    }
+
+    /// 受け方の合法な応手
+    pub fn evasion_moves(&self, board: &Board) -> Vec<Move> {
+        board.generate_evasions(get_reverse_color(self.attacker))
+    }
+
+    /// 証明数・反証数がしきい値に達するまで `board` 以下を探索し、その局面の値を返す
+    fn mid(
+        &mut self,
+        board: &Board,
+        is_attacker_turn: bool,
+        threshold_pn: u32,
+        threshold_dn: u32,
+    ) -> Entry {
+        self.nodes += 1;
+        let key = Self::key(board, is_attacker_turn);
+
+        let moves = if is_attacker_turn {
+            self.attack_moves(board)
+        } else {
+            self.evasion_moves(board)
+        };
+        if moves.is_empty() {
+            let entry = if is_attacker_turn {
+                Entry::disproven()
+            } else {
+                Entry::proven(0)
+            };
+            self.table.insert(key, entry);
+            return entry;
+        }
+
+        let children: Vec<(Board, u64)> = moves
+            .iter()
+            .map(|mv| {
+                let mut next = board.clone();
+                next.execute_move(mv);
+                let child_key = Self::key(&next, !is_attacker_turn);
+                (next, child_key)
+            })
+            .collect();
+
+        // 子の千日手による不詰は置換表にないので、この局面の探索中だけ覚えておく
+        let mut repetitions: Vec<Option<Entry>> = vec![None; children.len()];
+        self.path.push(key);
+        let entry = loop {
+            let entries: Vec<Entry> = children
+                .iter()
+                .zip(&repetitions)
+                .map(|((_, child_key), repetition)| {
+                    repetition.unwrap_or_else(|| self.lookup(*child_key))
+                })
+                .collect();
+
+            // 攻め方の手番ではpnの最小値とdnの和、受け方の手番ではその逆を取る
+            let (numbers, sums): (Vec<u32>, Vec<u32>) = if is_attacker_turn {
+                entries.iter().map(|entry| (entry.pn, entry.dn)).unzip()
+            } else {
+                entries.iter().map(|entry| (entry.dn, entry.pn)).unzip()
+            };
+            let mut best = 0;
+            let mut second = INFINITY;
+            for (index, number) in numbers.iter().enumerate().skip(1) {
+                if *number < numbers[best] {
+                    second = numbers[best];
+                    best = index;
+                } else if *number < second {
+                    second = *number;
+                }
+            }
+            let min_number = numbers[best];
+            let sum_number = sums.iter().fold(0u32, |sum, number| {
+                sum.saturating_add(*number).min(INFINITY)
+            });
+
+            let (pn, dn) = if is_attacker_turn {
+                (min_number, sum_number)
+            } else {
+                (sum_number, min_number)
+            };
+            let distance = if pn != 0 {
+                0
+            } else if is_attacker_turn {
+                entries
+                    .iter()
+                    .filter(|entry| entry.pn == 0)
+                    .map(|entry| entry.distance)
+                    .min()
+                    .unwrap_or(0)
+                    + 1
+            } else {
+                entries
+                    .iter()
+                    .map(|entry| entry.distance)
+                    .max()
+                    .unwrap_or(0)
+                    + 1
+            };
+            // 攻め方の手番では子のどれかが、受け方の手番では不詰の子のすべてが千日手に頼っていれば、
+            // この局面の不詰も経路に依存する
+            let is_repetition = dn == 0
+                && if is_attacker_turn {
+                    entries.iter().any(|entry| entry.is_repetition)
+                } else {
+                    entries
+                        .iter()
+                        .all(|entry| entry.dn != 0 || entry.is_repetition)
+                };
+            let entry = Entry {
+                pn,
+                dn,
+                distance,
+                is_repetition,
+            };
+            if is_repetition {
+                self.table.remove(&key);
+            } else {
+                self.table.insert(key, entry);
+            }
+
+            if pn >= threshold_pn || dn >= threshold_dn || self.nodes >= self.max_nodes {
+                break entry;
+            }
+
+            let (child_threshold_pn, child_threshold_dn) = if is_attacker_turn {
+                (
+                    threshold_pn.min(second.saturating_add(1)),
+                    threshold_dn - dn + entries[best].dn,
+                )
+            } else {
+                (
+                    threshold_pn - pn + entries[best].pn,
+                    threshold_dn.min(second.saturating_add(1)),
+                )
+            };
+            let child = &children[best].0;
+            let child_entry = self.mid(
+                child,
+                !is_attacker_turn,
+                child_threshold_pn,
+                child_threshold_dn,
+            );
+            if child_entry.is_repetition {
+                repetitions[best] = Some(child_entry);
+            }
+        };
+        self.path.pop();
+        entry
+    }
+
+    /// 詰みを証明した局面から、最短の詰み手順（受け方は最長の応手）をたどる
+    fn principal_variation(&self, board: &Board) -> Vec<Move> {
+        let mut moves = Vec::new();
+        let mut current = board.clone();
+        let mut is_attacker_turn = true;
+        let mut visited = HashSet::new();
+
+        while visited.insert(Self::key(&current, is_attacker_turn)) {
+            let candidates = if is_attacker_turn {
+                self.attack_moves(&current)
+            } else {
+                self.evasion_moves(&current)
+            };
+            let mut selected: Option<(Board, Move, u32)> = None;
+            for mv in candidates {
+                let mut next = current.clone();
+                next.execute_move(&mv);
+                let entry = match self.table.get(&Self::key(&next, !is_attacker_turn)) {
+                    Some(entry) if entry.pn == 0 => *entry,
+                    _ => continue,
+                };
+                let is_better = match &selected {
+                    Some((_, _, distance)) if is_attacker_turn => entry.distance < *distance,
+                    Some((_, _, distance)) => entry.distance > *distance,
+                    None => true,
+                };
+                if is_better {
+                    selected = Some((next, mv, entry.distance));
+                }
+            }
+            match selected {
+                Some((next, mv, _)) => {
+                    moves.push(mv);
+                    current = next;
+                    is_attacker_turn = !is_attacker_turn;
+                }
+                None => break,
+            }
+        }
+        moves
+    }
+
+    pub fn solve(&mut self, board: &Board) -> MateResult {
+        self.nodes = 0;
+        self.table.clear();
+        self.path.clear();
+
+        let defender = get_reverse_color(self.attacker);
+        if board.get_king_index(defender).is_none() {
+            return MateResult {
+                status: MateStatus::Disproven,
+                moves: Vec::new(),
+                nodes: 0,
+            };
+        }
+
+        let root = self.mid(board, true, INFINITY, INFINITY);
+        let status = if root.pn == 0 {
+            MateStatus::Proven
+        } else if root.dn == 0 {
+            MateStatus::Disproven
+        } else {
+            MateStatus::Unknown
+        };
+        let moves = if status == MateStatus::Proven {
+            self.principal_variation(board)
+        } else {
+            Vec::new()
+        };
+        MateResult {
+            status,
+            moves,
+            nodes: self.nodes,
+        }
+    }
+}
+
+#[pymethods]
+impl DfPnSolver {
+    #[new]
+    #[pyo3(signature = (attacker = ColorType::Black, max_nodes = 100000))]
+    pub fn new_for_python(attacker: ColorType, max_nodes: u64) -> Self {
+        Self::new(attacker, max_nodes)
+    }
+
+    #[pyo3(name = "solve")]
+    pub fn python_solve(&mut self, board: &Board) -> MateResult {
+        self.solve(board)
+    }
+}
+
+/// `attacker` 側から見た詰みを `max_nodes` ノードまで探索する
+pub fn solve_mate(board: &Board, attacker: ColorType, max_nodes: u64) -> MateResult {
+    DfPnSolver::new(attacker, max_nodes).solve(board)
+}
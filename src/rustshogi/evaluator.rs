@@ -0,0 +1,135 @@
+use super::board::Board;
+use super::color::ColorType;
+use super::move_label::MOVE_LABEL_NUMBER;
+use super::moves::Move;
+
+use numpy::PyReadonlyArray2;
+use pyo3::prelude::*;
+
+/// 評価する局面（探索木の葉）
+#[derive(Clone, Debug)]
+pub struct EvalPosition {
+    pub board: Board,
+    pub turn: ColorType,
+    /// `turn` 側の合法手（空でないこと）
+    pub moves: Vec<Move>,
+}
+
+/// 局面の評価
+#[derive(Clone, Debug, PartialEq)]
+pub struct Evaluation {
+    /// 手番側から見た評価値（-1〜1）
+    pub value: f32,
+    /// `EvalPosition::moves` と同じ順の事前確率
+    pub priors: Vec<f32>,
+}
+
+/// 探索の葉をまとめて評価する
+pub trait Evaluator: Send + Sync {
+    /// `positions` と同じ順に評価を返す
+    fn evaluate(&self, positions: &[EvalPosition]) -> Result<Vec<Evaluation>, String>;
+}
+
+/// すべての手を同じ確率とし、評価値を0とする（テストと動作確認用）
+pub struct UniformEvaluator;
+
+impl Evaluator for UniformEvaluator {
+    fn evaluate(&self, positions: &[EvalPosition]) -> Result<Vec<Evaluation>, String> {
+        Ok(positions
+            .iter()
+            .map(|position| Evaluation {
+                value: 0.0,
+                priors: vec![1.0 / position.moves.len() as f32; position.moves.len()],
+            })
+            .collect())
+    }
+}
+
+/// 指し手のラベルごとの方策（`MOVE_LABEL_NUMBER` 個）から合法手の事前確率を作る
+///
+/// 合法手の値を合計1になるように正規化する。負の値は0とみなし、合計が0なら一様にする。
+pub fn priors_from_policy(policy: &[f32], turn: ColorType, moves: &[Move]) -> Vec<f32> {
+    let mut priors: Vec<f32> = moves
+        .iter()
+        .map(|mv| {
+            mv.to_label(turn)
+                .and_then(|label| policy.get(label))
+                .copied()
+                .filter(|p| p.is_finite() && *p > 0.0)
+                .unwrap_or(0.0)
+        })
+        .collect();
+    let sum: f32 = priors.iter().sum();
+    if sum > 0.0 {
+        priors.iter_mut().for_each(|p| *p /= sum);
+    } else {
+        priors.fill(1.0 / moves.len() as f32);
+    }
+    priors
+}
+
+/// Pythonの関数を呼んで評価する
+///
+/// 関数は `(boards: list[Board], turns: list[ColorType])` を受け取り、手番側から見た評価値の
+/// 列と、`(局面数, MOVE_LABEL_NUMBER)` の方策（`Move.to_label` の順）の組を返す。
+/// 探索はGILを解放して進め、評価のときだけGILを取る。
+pub struct PyEvaluator {
+    pub callback: Py<PyAny>,
+}
+
+impl PyEvaluator {
+    pub fn new(callback: Py<PyAny>) -> Self {
+        Self { callback }
+    }
+
+    fn extract_policies(policies: &Bound<'_, PyAny>) -> PyResult<Vec<Vec<f32>>> {
+        if let Ok(array) = policies.extract::<PyReadonlyArray2<f32>>() {
+            return Ok(array
+                .as_array()
+                .outer_iter()
+                .map(|row| row.to_vec())
+                .collect());
+        }
+        policies.extract()
+    }
+
+    fn call(&self, py: Python<'_>, positions: &[EvalPosition]) -> PyResult<Vec<Evaluation>> {
+        let boards: Vec<Board> = positions.iter().map(|p| p.board.clone()).collect();
+        let turns: Vec<ColorType> = positions.iter().map(|p| p.turn).collect();
+        let output = self.callback.call1(py, (boards, turns))?;
+        let (values, policies): (Bound<'_, PyAny>, Bound<'_, PyAny>) = output.extract(py)?;
+        let values: Vec<f32> = values.extract()?;
+        let policies = Self::extract_policies(&policies)?;
+
+        if values.len() != positions.len() || policies.len() != positions.len() {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "evaluator returned {} values and {} policies for {} positions",
+                values.len(),
+                policies.len(),
+                positions.len()
+            )));
+        }
+        if let Some(policy) = policies.iter().find(|p| p.len() != MOVE_LABEL_NUMBER) {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "policy must have {} labels, got {}",
+                MOVE_LABEL_NUMBER,
+                policy.len()
+            )));
+        }
+
+        Ok(positions
+            .iter()
+            .zip(values.iter().zip(policies.iter()))
+            .map(|(position, (value, policy))| Evaluation {
+                value: value.clamp(-1.0, 1.0),
+                priors: priors_from_policy(policy, position.turn, &position.moves),
+            })
+            .collect())
+    }
+}
+
+impl Evaluator for PyEvaluator {
+    fn evaluate(&self, positions: &[EvalPosition]) -> Result<Vec<Evaluation>, String> {
+        Python::with_gil(|py| self.call(py, positions).map_err(|e| e.to_string()))
+    }
+}
@@ -0,0 +1,450 @@
+use super::bitboard::BitBoard;
+use super::board::{Board, BoardSource, VECTOR_DIMENSIONS};
+use super::color::ColorType;
+use super::pca::{global_pca_transform, PCASolver, PCATransform};
+use super::piece::PieceType;
+
+use ndarray::Array2;
+use numpy::{IntoPyArray, PyArray2};
+use pyo3::prelude::*;
+use rayon::prelude::*;
+use std::sync::Arc;
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+/// 盤面の1マスあたりの特徴量の数（`Board` のビットボードの長さ）
+const SQUARES: usize = 128;
+
+/// 持ち駒の特徴量の数（2色 × 8駒種）
+const HAND_FEATURES: usize = 16;
+
+/// 持ち駒の最大枚数（`Hand::to_vector` と同じ玉から歩の順）
+const HAND_MAX_COUNTS: [f32; 8] = [1.0, 4.0, 2.0, 2.0, 4.0, 4.0, 4.0, 18.0];
+
+/// 特徴量としてマスごとに並べるビットボード
+#[pyclass(eq, eq_int)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug, EnumIter)]
+pub enum FeaturePlane {
+    Occupied = 0,
+    White,
+    Black,
+    Empty,
+    King,
+    Gold,
+    Rook,
+    Bishop,
+    Silver,
+    Knight,
+    Lance,
+    Pawn,
+    Dragon,
+    Horse,
+    ProSilver,
+    ProKnight,
+    ProLance,
+    ProPawn,
+}
+
+impl FeaturePlane {
+    fn bitboard(&self, board: &Board) -> BitBoard {
+        let piece_type = match self {
+            FeaturePlane::Occupied => return board.has_piece,
+            FeaturePlane::White => return board.player_prossesion[ColorType::White as usize],
+            FeaturePlane::Black => return board.player_prossesion[ColorType::Black as usize],
+            FeaturePlane::Empty => PieceType::None,
+            FeaturePlane::King => PieceType::King,
+            FeaturePlane::Gold => PieceType::Gold,
+            FeaturePlane::Rook => PieceType::Rook,
+            FeaturePlane::Bishop => PieceType::Bichop,
+            FeaturePlane::Silver => PieceType::Silver,
+            FeaturePlane::Knight => PieceType::Knight,
+            FeaturePlane::Lance => PieceType::Lance,
+            FeaturePlane::Pawn => PieceType::Pawn,
+            FeaturePlane::Dragon => PieceType::Dragon,
+            FeaturePlane::Horse => PieceType::Horse,
+            FeaturePlane::ProSilver => PieceType::ProSilver,
+            FeaturePlane::ProKnight => PieceType::ProKnight,
+            FeaturePlane::ProLance => PieceType::ProLance,
+            FeaturePlane::ProPawn => PieceType::ProPawn,
+        };
+        board.has_specific_piece[piece_type as usize]
+    }
+}
+
+/// 特徴量の正規化
+#[pyclass(eq, eq_int)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum FeatureNormalization {
+    /// そのまま
+    Raw = 0,
+    /// 持ち駒の枚数を最大枚数で割って0〜1にそろえる（圧縮の前に行う）
+    HandRatio,
+    /// 長さを1にする（圧縮の後に行う）
+    L2,
+    /// 主成分ごとに標準偏差で割る（圧縮するときのみ）
+    Whiten,
+}
+
+/// 盤面から特徴量を作る設定と、次元圧縮に使うPCA変換
+///
+/// グローバルなPCA変換を参照しないので、設定の異なる抽出器を同じプロセスで同時に使える。
+#[pyclass]
+#[derive(Clone, Debug)]
+pub struct FeatureExtractor {
+    /// マスごとに並べるビットボード（この順に並べる）
+    pub planes: Vec<FeaturePlane>,
+    /// 持ち駒の特徴量を最後に並べるか
+    pub hand: bool,
+    pub normalization: FeatureNormalization,
+    /// 次元圧縮に使うPCA変換（`raw_dimensions` 次元を入力にする）
+    pub transform: Option<Arc<PCATransform>>,
+}
+
+impl Default for FeatureExtractor {
+    /// `Board::to_vector(None)` と同じ特徴量
+    fn default() -> Self {
+        Self {
+            planes: FeaturePlane::iter().collect(),
+            hand: true,
+            normalization: FeatureNormalization::Raw,
+            transform: None,
+        }
+    }
+}
+
+impl FeatureExtractor {
+    pub fn new(
+        planes: Vec<FeaturePlane>,
+        hand: bool,
+        normalization: FeatureNormalization,
+        transform: Option<Arc<PCATransform>>,
+    ) -> Result<Self, String> {
+        let extractor = Self {
+            planes,
+            hand,
+            normalization,
+            transform: None,
+        };
+        if extractor.raw_dimensions() == 0 {
+            return Err("Feature extractor must use at least one plane or the hand".to_string());
+        }
+        match transform {
+            Some(transform) => extractor.with_transform(transform),
+            None if normalization == FeatureNormalization::Whiten => {
+                Err("Whiten normalization requires a PCA transform".to_string())
+            }
+            None => Ok(extractor),
+        }
+    }
+
+    /// `transform` で次元圧縮する抽出器
+    pub fn with_transform(self, transform: Arc<PCATransform>) -> Result<Self, String> {
+        if !transform.is_fitted() {
+            return Err("PCA transform is not fitted".to_string());
+        }
+        if transform.n_features() != self.raw_dimensions() {
+            return Err(format!(
+                "PCA transform expects {} features, but the extractor produces {}",
+                transform.n_features(),
+                self.raw_dimensions()
+            ));
+        }
+        Ok(Self {
+            transform: Some(transform),
+            ..self
+        })
+    }
+
+    /// `Board::to_vector(target_dimensions)` の抽出器
+    ///
+    /// 圧縮するときはグローバルなPCA変換をここで1回だけ取り出す。
+    pub fn from_global(target_dimensions: Option<usize>) -> Result<Self, String> {
+        let extractor = Self::default();
+        let target_dims = match target_dimensions {
+            Some(target_dims) if target_dims < VECTOR_DIMENSIONS => target_dims,
+            _ => return Ok(extractor),
+        };
+        match global_pca_transform() {
+            Some(transform) if transform.n_components == target_dims => {
+                extractor.with_transform(transform)
+            }
+            Some(transform) => Err(format!(
+                "Global PCA transform has {} components, but {} dimensions were requested",
+                transform.n_components, target_dims
+            )),
+            None => Err(format!(
+                "No global PCA transform is set for {} dimensions; pass a FeatureExtractor or call set_global() first",
+                target_dims
+            )),
+        }
+    }
+
+    /// 圧縮前の特徴量の次元数
+    pub fn raw_dimensions(&self) -> usize {
+        SQUARES * self.planes.len() + if self.hand { HAND_FEATURES } else { 0 }
+    }
+
+    /// 特徴量の次元数
+    pub fn dimensions(&self) -> usize {
+        match &self.transform {
+            Some(transform) => transform.n_components,
+            None => self.raw_dimensions(),
+        }
+    }
+
+    /// 圧縮前の特徴量（`raw_dimensions` 次元）を `out` に書き込む
+    fn write_raw(&self, board: &Board, out: &mut [f32]) {
+        let (board_vector, hand_vector) = out.split_at_mut(SQUARES * self.planes.len());
+
+        // 各マスについて、選んだビットボードの値を連続配置
+        let bitboards: Vec<u128> = self
+            .planes
+            .iter()
+            .map(|plane| plane.bitboard(board).to_u128())
+            .collect();
+        for (index, square) in board_vector.chunks_mut(bitboards.len().max(1)).enumerate() {
+            for (value, bitboard) in square.iter_mut().zip(&bitboards) {
+                *value = ((bitboard >> (127 - index)) & 1) as f32;
+            }
+        }
+
+        if self.hand {
+            hand_vector.copy_from_slice(&board.hand.to_vector());
+            if self.normalization == FeatureNormalization::HandRatio {
+                for (value, max) in hand_vector.iter_mut().zip(HAND_MAX_COUNTS.iter().cycle()) {
+                    *value /= max;
+                }
+            }
+        }
+    }
+
+    /// 特徴量（`dimensions` 次元）を `out` に書き込む
+    pub fn write(&self, board: &Board, out: &mut [f32]) -> Result<(), String> {
+        match &self.transform {
+            None => self.write_raw(board, out),
+            Some(transform) => {
+                let mut raw = vec![0f32; self.raw_dimensions()];
+                self.write_raw(board, &mut raw);
+                out.copy_from_slice(&transform.transform(&raw)?);
+            }
+        }
+
+        match (self.normalization, &self.transform) {
+            (FeatureNormalization::L2, _) => {
+                let norm = out.iter().map(|value| value * value).sum::<f32>().sqrt();
+                if norm > 0.0 {
+                    out.iter_mut().for_each(|value| *value /= norm);
+                }
+            }
+            (FeatureNormalization::Whiten, Some(transform)) => {
+                for (value, variance) in out.iter_mut().zip(&transform.explained_variance) {
+                    if *variance > 0.0 {
+                        *value /= variance.sqrt();
+                    }
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// 盤面の特徴量
+    pub fn extract(&self, board: &Board) -> Result<Vec<f32>, String> {
+        let mut features = vec![0f32; self.dimensions()];
+        self.write(board, &mut features)?;
+        Ok(features)
+    }
+
+    /// 複数の盤面の特徴量を、1行1局面の連続した配列に並べて返す
+    ///
+    /// 行ごとに `num_threads` 個のスレッドで並列に作る。SFENはそのスレッドの中で読む。
+    pub fn extract_batch(
+        &self,
+        sources: &[BoardSource],
+        num_threads: usize,
+    ) -> Result<Vec<f32>, String> {
+        let dimensions = self.dimensions();
+        let mut features = vec![0f32; sources.len() * dimensions];
+        if dimensions == 0 {
+            return Ok(features);
+        }
+
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(num_threads)
+            .build()
+            .map_err(|e| e.to_string())?;
+        pool.install(|| {
+            features
+                .par_chunks_mut(dimensions)
+                .zip(sources.par_iter())
+                .try_for_each(|(row, source)| {
+                    let parsed;
+                    let board = match source {
+                        BoardSource::Board(board) => board,
+                        BoardSource::Sfen(sfen) => {
+                            parsed = Board::from_sfen(sfen.clone());
+                            &parsed
+                        }
+                    };
+                    self.write(board, row)
+                })
+        })?;
+        Ok(features)
+    }
+
+    /// 圧縮前の特徴量からPCA変換を学習する
+    ///
+    /// `Full` 以外は `batch_size` 局面ずつ特徴量を作るので、全局面の特徴量を一度に持たない。
+    pub fn learn_transform(
+        &self,
+        sources: &[BoardSource],
+        n_components: usize,
+        solver: PCASolver,
+        batch_size: usize,
+        num_threads: usize,
+    ) -> Result<PCATransform, String> {
+        if batch_size == 0 {
+            return Err("Batch size must be positive".to_string());
+        }
+        let raw = Self {
+            // 圧縮の後に行う正規化は学習に使わない
+            normalization: match self.normalization {
+                FeatureNormalization::HandRatio => FeatureNormalization::HandRatio,
+                _ => FeatureNormalization::Raw,
+            },
+            transform: None,
+            ..self.clone()
+        };
+        PCATransform::fit_batches(self.raw_dimensions(), n_components, solver, || {
+            sources
+                .chunks(batch_size)
+                .map(|chunk| raw.extract_batch(chunk, num_threads))
+        })
+    }
+
+    /// PCA変換を学習し、それで次元圧縮する抽出器を返す
+    pub fn fit(
+        self,
+        sources: &[BoardSource],
+        n_components: usize,
+        solver: PCASolver,
+        batch_size: usize,
+        num_threads: usize,
+    ) -> Result<Self, String> {
+        let transform =
+            self.learn_transform(sources, n_components, solver, batch_size, num_threads)?;
+        self.with_transform(Arc::new(transform))
+    }
+}
+
+#[pymethods]
+impl FeatureExtractor {
+    #[new]
+    #[pyo3(signature = (planes = None, hand = true, normalization = FeatureNormalization::Raw, transform = None))]
+    pub fn new_for_python(
+        planes: Option<Vec<FeaturePlane>>,
+        hand: bool,
+        normalization: FeatureNormalization,
+        transform: Option<PCATransform>,
+    ) -> PyResult<Self> {
+        Self::new(
+            planes.unwrap_or_else(|| FeaturePlane::iter().collect()),
+            hand,
+            normalization,
+            transform.map(Arc::new),
+        )
+        .map_err(pyo3::exceptions::PyValueError::new_err)
+    }
+
+    #[getter]
+    #[pyo3(name = "planes")]
+    pub fn python_planes(&self) -> Vec<FeaturePlane> {
+        self.planes.clone()
+    }
+
+    #[getter]
+    #[pyo3(name = "hand")]
+    pub fn python_hand(&self) -> bool {
+        self.hand
+    }
+
+    #[getter]
+    #[pyo3(name = "normalization")]
+    pub fn python_normalization(&self) -> FeatureNormalization {
+        self.normalization
+    }
+
+    #[getter]
+    #[pyo3(name = "transform")]
+    pub fn python_transform(&self) -> Option<PCATransform> {
+        self.transform.as_deref().cloned()
+    }
+
+    #[getter]
+    #[pyo3(name = "raw_dimensions")]
+    pub fn python_raw_dimensions(&self) -> usize {
+        self.raw_dimensions()
+    }
+
+    #[getter]
+    #[pyo3(name = "dimensions")]
+    pub fn python_dimensions(&self) -> usize {
+        self.dimensions()
+    }
+
+    /// 盤面かSFENのリストから `n_components` 次元のPCA変換を学習して使う（学習中はGILを解放する）
+    #[pyo3(name = "fit")]
+    #[pyo3(signature = (boards, n_components, threads = None, solver = PCASolver::Full, batch_size = 1024))]
+    pub fn python_fit(
+        &mut self,
+        py: Python<'_>,
+        boards: Vec<BoardSource>,
+        n_components: usize,
+        threads: Option<usize>,
+        solver: PCASolver,
+        batch_size: usize,
+    ) -> PyResult<()> {
+        let num_threads = threads.unwrap_or_else(num_cpus::get);
+        *self = py
+            .allow_threads(|| {
+                self.clone()
+                    .fit(&boards, n_components, solver, batch_size, num_threads)
+            })
+            .map_err(pyo3::exceptions::PyValueError::new_err)?;
+        Ok(())
+    }
+
+    #[pyo3(name = "extract")]
+    pub fn python_extract(&self, board: &Board) -> PyResult<Vec<f32>> {
+        self.extract(board)
+            .map_err(pyo3::exceptions::PyValueError::new_err)
+    }
+
+    /// 盤面かSFENのリストから `(局面数, dimensions)` の特徴量の配列を作る（作る間はGILを解放する）
+    #[pyo3(name = "extract_batch")]
+    #[pyo3(signature = (boards, threads = None))]
+    pub fn python_extract_batch<'py>(
+        &self,
+        py: Python<'py>,
+        boards: Vec<BoardSource>,
+        threads: Option<usize>,
+    ) -> PyResult<Bound<'py, PyArray2<f32>>> {
+        let num_threads = threads.unwrap_or_else(num_cpus::get);
+        let data = py
+            .allow_threads(|| self.extract_batch(&boards, num_threads))
+            .map_err(pyo3::exceptions::PyValueError::new_err)?;
+        let array = Array2::from_shape_vec((boards.len(), self.dimensions()), data)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        Ok(array.into_pyarray(py))
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!(
+            "FeatureExtractor(planes={}, hand={}, normalization={:?}, dimensions={})",
+            self.planes.len(),
+            self.hand,
+            self.normalization,
+            self.dimensions()
+        )
+    }
+}
@@ -1,15 +1,26 @@
-use super::address::Address;
 use super::board::Board;
-use super::color::{convert_from_string, get_reverse_color, ColorType};
+use super::budget::{is_leader_decided, SearchBudget};
+use super::color::{convert_from_string, convert_string, get_reverse_color, ColorType};
+use super::evaluator::{Evaluator, PyEvaluator, UniformEvaluator};
+use super::handicap::Handicap;
 use super::mctsresult::MctsResult;
-use super::moves::Move;
+use super::move_list::MoveList;
+use super::moves::{ExtendedMove, Move};
 use super::piece::Piece;
+use super::puct::{PuctConfig, PuctResult, PuctSearch};
 use super::random::Random;
+use super::rollout::{RolloutConfig, RolloutPolicy, UniformPolicy};
+use super::variant::Variant;
+use super::zobrist::ZOBRIST;
 use num_cpus;
 use rayon::prelude::*;
+use std::time::Instant;
 
 use pyo3::prelude::*;
 
+/// 駒得を勝率に変換するときの尺度
+const EVALUATION_SCALE: f64 = 600.0;
+
 #[pyclass]
 #[derive(Clone)]
 pub struct Game {
@@ -48,32 +59,57 @@ impl Game {
         }
     }
 
-    pub fn input_board(&mut self, sfen: String) {
-        let startpos = String::from("startpos");
-        if startpos == sfen {
-            self.board.startpos();
-            return;
+    /// 手番と手数を含むSFEN（"盤面 手番 持ち駒 手数"）から作る
+    pub fn from_sfen(sfen: &str) -> Self {
+        let parts: Vec<&str> = sfen.split_whitespace().collect();
+        let mut game = Self::from(
+            Board::from_sfen(sfen.to_string()),
+            1,
+            ColorType::Black,
+            ColorType::None,
+        );
+        if parts.len() > 2 {
+            game.input_turn(parts[1].to_string());
         }
-
-        let parts: Vec<&str> = sfen.split('/').collect();
-        for (row, part) in parts.iter().enumerate().rev() {
-            let mut column = 0;
-            let chars = part.chars();
-            for ch in chars {
-                if ch.is_ascii_digit() {
-                    let empty_spaces = ch.to_digit(10).unwrap() as usize;
-                    column += empty_spaces;
-                } else {
-                    let piece = Piece::from_char(ch);
-                    let piece_type = piece.piece_type;
-                    let owner = piece.owner;
-                    let index =
-                        Address::from_numbers((1 + column) as u8, (9 - row) as u8).to_index();
-                    self.board.deploy(index, piece_type, owner);
-                    column += 1;
-                }
-            }
+        if parts.len() > 3 {
+            game.input_move_number(parts[3].to_string());
         }
+        game
+    }
+
+    /// 手合割の初期局面から始める（駒落ちは上手の後手から指す）
+    pub fn from_handicap(name: &str) -> Result<Self, String> {
+        let handicap =
+            Handicap::from_name(name).ok_or_else(|| format!("unknown handicap: {}", name))?;
+        Ok(Self::from_sfen(&handicap.sfen()))
+    }
+
+    /// `variant` の初期局面から始める
+    pub fn from_variant(variant: Variant) -> Self {
+        let mut board = Board::with_variant(variant);
+        board.startpos();
+        Self::from(board, 1, ColorType::Black, ColorType::None)
+    }
+
+    /// KIFのヘッダーの手合割から初期局面を作る
+    pub fn from_kif_header(kif: &str) -> Result<Self, String> {
+        let handicap = Handicap::from_kif_header(kif)?;
+        Ok(Self::from_sfen(&handicap.sfen()))
+    }
+
+    pub fn input_board(&mut self, sfen: String) {
+        self.board.input_board(&sfen);
+    }
+
+    /// 手番と手数を含むSFEN（"盤面 手番 持ち駒 手数"）
+    pub fn to_sfen(&self) -> String {
+        format!(
+            "{} {} {} {}",
+            self.board.to_string(),
+            convert_string(self.turn),
+            self.board.hand.to_sfen(),
+            self.move_number
+        )
     }
 
     pub fn input_hand(&mut self, sfen: String) {
@@ -117,6 +153,38 @@ impl Game {
         self.turn = get_reverse_color(self.turn);
     }
 
+    /// 手番を含めた局面のハッシュ値
+    pub fn hash_key(&self) -> u64 {
+        if self.turn == ColorType::White {
+            self.board.hash_key() ^ ZOBRIST.turn
+        } else {
+            self.board.hash_key()
+        }
+    }
+
+    /// 手を指し、`undo_move` で戻すための `ExtendedMove` を返す
+    pub fn make_move(&mut self, mv: &Move) -> ExtendedMove {
+        let extended = self.board.make_move(mv);
+        self.move_number += 1;
+        self.turn = get_reverse_color(self.turn);
+        extended
+    }
+
+    pub fn undo_move(&mut self, mv: &ExtendedMove) {
+        self.board.undo_move(mv);
+        self.move_number -= 1;
+        self.turn = get_reverse_color(self.turn);
+    }
+
+    /// 盤面を変えずに手番だけを相手に渡す（探索のヌルムーブ枝刈り用）
+    pub fn make_null_move(&mut self) -> ExtendedMove {
+        self.make_move(&Move::pass())
+    }
+
+    pub fn undo_null_move(&mut self) {
+        self.undo_move(&ExtendedMove::from_move(&Move::pass(), &self.board));
+    }
+
     pub fn one_play(&mut self) -> Self {
         // used for benchmark only
         while !self.is_finished().0 {
@@ -132,79 +200,228 @@ impl Game {
         self.clone()
     }
 
-    pub fn random_play(&mut self) -> Self {
-        while !self.is_finished().0 {
-            let moves = self.board.search_moves(self.turn);
-            let mut random = Random::new(0, (moves.len() - 1) as u16);
-            let amove = &moves[random.generate_one() as usize];
-            self.execute_move(amove);
-            let is_finish = self.is_finished();
-            if is_finish.0 {
-                self.winner = is_finish.1;
-                break;
+    /// 方策に従って終局まで（`cutoff` 手で打ち切る場合はその時点まで）指し進め、勝者と先手視点の値を返す
+    ///
+    /// 打ち切った場合は駒得が `cutoff_margin` を超えている側を勝ちとし、値は駒得をシグモイドで0.0〜1.0に写したものとする。
+    pub fn playout(
+        &mut self,
+        policy: &dyn RolloutPolicy,
+        cutoff: Option<u16>,
+        cutoff_margin: i32,
+    ) -> (ColorType, f64) {
+        let start_move_number = self.move_number;
+        // 手の生成のたびに確保しないよう、同じリストを使い回す
+        let mut moves = MoveList::new();
+        loop {
+            let (is_finished, winner) = self.is_finished();
+            if is_finished {
+                self.winner = winner;
+                return (winner, MctsResult::winner_to_value(winner));
+            }
+
+            if let Some(cutoff) = cutoff {
+                if self.move_number - start_move_number >= cutoff {
+                    let score = self.board.evaluate(ColorType::Black);
+                    let value = 1.0 / (1.0 + (-score as f64 / EVALUATION_SCALE).exp());
+                    let winner = if score > cutoff_margin {
+                        ColorType::Black
+                    } else if score < -cutoff_margin {
+                        ColorType::White
+                    } else {
+                        ColorType::None
+                    };
+                    return (winner, value);
+                }
+            }
+
+            self.board.search_moves_into(self.turn, &mut moves);
+            if moves.is_empty() {
+                return (
+                    ColorType::None,
+                    MctsResult::winner_to_value(ColorType::None),
+                );
             }
+            let index = policy.select_move(&self.board, self.turn, &moves);
+            self.execute_move(&moves[index]);
         }
+    }
+
+    pub fn random_play(&mut self) -> Self {
+        self.playout(&UniformPolicy, None, 0);
         self.clone()
     }
 
     pub fn random_move_parallel(&self, num: usize, num_threads: usize) -> Vec<MctsResult> {
+        self.rollout_parallel(num, num_threads, &RolloutConfig::default())
+    }
+
+    pub fn rollout_parallel(
+        &self,
+        num: usize,
+        num_threads: usize,
+        config: &RolloutConfig,
+    ) -> Vec<MctsResult> {
         let pool = rayon::ThreadPoolBuilder::new()
             .num_threads(num_threads)
             .build()
             .unwrap();
 
         let next_moves = self.board.search_moves(self.turn);
-        let next_move_count = next_moves.len();
 
-        if next_move_count == 0 {
+        if next_moves.is_empty() {
             return vec![];
         }
 
         // 各手に対してMctsResultを初期化
         let mut results: Vec<MctsResult> = next_moves
             .iter()
-            .map(|mv| MctsResult::from(self.board.clone(), mv.clone()))
+            .map(|mv| MctsResult::from(self.board.clone(), *mv))
             .collect();
 
+        let policy = config.build_policy();
+
         // 全体でnum回のシミュレーションを実行
-        let simulation_results: Vec<(ColorType, usize)> = pool.install(|| {
+        let simulation_results: Vec<(ColorType, u64, f64, usize)> = pool.install(|| {
             (0..num)
                 .into_par_iter()
-                .map(|_| {
-                    // ランダムに手を選択
-                    let mut random = Random::new(0, (next_move_count - 1) as u16);
-                    let selected_move_index = random.generate_one() as usize;
-
-                    // 選択された手でゲームを開始
-                    let mut game_clone = self.clone();
-                    game_clone.execute_move(&next_moves[selected_move_index]);
-
-                    // ランダムプレイでゲーム終了まで実行
-                    while !game_clone.is_finished().0 {
-                        let moves = game_clone.board.search_moves(game_clone.turn);
-                        if moves.is_empty() {
-                            break;
-                        }
-                        let move_count = moves.len();
-                        let mut random = Random::new(0, (move_count - 1) as u16);
-                        let random_move = &moves[random.generate_one() as usize];
-                        game_clone.execute_move(random_move);
-                    }
-
-                    let (_is_finished, winner) = game_clone.is_finished();
-                    (winner, selected_move_index)
-                })
+                .map(|_| self.simulate(&next_moves, policy.as_ref(), config))
                 .collect()
         });
 
         // 結果を各MctsResultに集計
-        for (winner, move_index) in simulation_results {
-            results[move_index].plus_result(winner);
+        for (winner, game_length, value, move_index) in simulation_results {
+            results[move_index].plus_simulation(winner, game_length, value);
         }
 
         results
     }
 
+    /// ランダムに選んだ次の手から1回プレイアウトし、(勝者, 手数, 値, 選んだ手) を返す
+    fn simulate(
+        &self,
+        next_moves: &[Move],
+        policy: &dyn RolloutPolicy,
+        config: &RolloutConfig,
+    ) -> (ColorType, u64, f64, usize) {
+        // ランダムに手を選択
+        let mut random = Random::new(0, (next_moves.len() - 1) as u16);
+        let selected_move_index = random.generate_one() as usize;
+
+        // 選択された手でゲームを開始
+        let mut game_clone = self.clone();
+        game_clone.execute_move(&next_moves[selected_move_index]);
+
+        // 方策に従って終局まで実行
+        let (winner, value) = game_clone.playout(policy, config.cutoff, config.cutoff_margin);
+        let game_length = (game_clone.move_number - self.move_number) as u64;
+        (winner, game_length, value, selected_move_index)
+    }
+
+    /// `budget` の条件を満たすまで `batch_size` 回ずつシミュレーションを繰り返す
+    ///
+    /// 終了条件がひとつもない（停止フラグもない）場合は探索が終わらないためエラーを返す。
+    pub fn rollout_with_budget(
+        &self,
+        budget: &SearchBudget,
+        num_threads: usize,
+        config: &RolloutConfig,
+    ) -> Result<Vec<MctsResult>, String> {
+        if !budget.is_bounded() {
+            return Err(
+                "search budget needs a simulation limit, a time limit or a stop flag".to_string(),
+            );
+        }
+
+        let start = Instant::now();
+        let deadline = budget.deadline(start);
+        let is_expired =
+            || budget.is_stopped() || deadline.is_some_and(|deadline| Instant::now() >= deadline);
+
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(num_threads)
+            .build()
+            .unwrap();
+
+        let next_moves = self.board.search_moves(self.turn);
+        if next_moves.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut results: Vec<MctsResult> = next_moves
+            .iter()
+            .map(|mv| MctsResult::from(self.board.clone(), *mv))
+            .collect();
+
+        let policy = config.build_policy();
+        let batch_size = budget.batch_size.max(1);
+        let mut simulations = 0;
+
+        loop {
+            let remaining_simulations = budget
+                .max_simulations
+                .map(|max_simulations| max_simulations.saturating_sub(simulations));
+            if remaining_simulations == Some(0) || is_expired() {
+                break;
+            }
+            let count =
+                remaining_simulations.map_or(batch_size, |remaining| remaining.min(batch_size));
+
+            // 停止フラグや期限はシミュレーションごとにも確認し、途中で打ち切れるようにする
+            let simulation_results: Vec<(ColorType, u64, f64, usize)> = pool.install(|| {
+                (0..count)
+                    .into_par_iter()
+                    .filter_map(|_| {
+                        if is_expired() {
+                            None
+                        } else {
+                            Some(self.simulate(&next_moves, policy.as_ref(), config))
+                        }
+                    })
+                    .collect()
+            });
+
+            simulations += simulation_results.len();
+            for (winner, game_length, value, move_index) in simulation_results {
+                results[move_index].plus_simulation(winner, game_length, value);
+            }
+
+            if budget.early_stop {
+                // 残り回数は上限回数と、これまでの速度から見積もった残り時間での回数の小さい方
+                let elapsed = start.elapsed().as_secs_f64();
+                let remaining_by_time = deadline.map(|deadline| {
+                    let left = deadline
+                        .saturating_duration_since(Instant::now())
+                        .as_secs_f64();
+                    (simulations as f64 / elapsed.max(f64::EPSILON) * left).ceil() as u64
+                });
+                let remaining_by_count = budget
+                    .max_simulations
+                    .map(|max_simulations| max_simulations.saturating_sub(simulations) as u64);
+                let remaining = match (remaining_by_count, remaining_by_time) {
+                    (Some(by_count), Some(by_time)) => Some(by_count.min(by_time)),
+                    (by_count, None) => by_count,
+                    (None, by_time) => by_time,
+                };
+                if let Some(remaining) = remaining {
+                    if is_leader_decided(&results, self.turn, remaining) {
+                        break;
+                    }
+                }
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// 評価器の評価値と事前確率を使ったPUCT探索で、現在の局面の手を調べる
+    pub fn puct_search(
+        &self,
+        evaluator: &dyn Evaluator,
+        config: &PuctConfig,
+    ) -> Result<PuctResult, String> {
+        PuctSearch::new(evaluator, config.clone()).search(&self.board, self.turn)
+    }
+
     pub fn generate_random_board(&mut self) -> Board {
         let mut random = Random::new(0, 300);
         let move_count = random.generate_one() as usize;
@@ -226,14 +443,57 @@ impl Game {
 #[pymethods]
 impl Game {
     #[pyo3(name = "random_move")]
-    #[pyo3(signature = (num, threads = None))]
-    pub fn python_random_move(&self, num: usize, threads: Option<usize>) -> Vec<MctsResult> {
+    #[pyo3(signature = (num, threads = None, rollout = None))]
+    pub fn python_random_move(
+        &self,
+        num: usize,
+        threads: Option<usize>,
+        rollout: Option<RolloutConfig>,
+    ) -> Vec<MctsResult> {
         let num_threads = threads.unwrap_or_else(num_cpus::get);
-        self.random_move_parallel(num, num_threads)
+        self.rollout_parallel(num, num_threads, &rollout.unwrap_or_default())
+    }
+
+    /// 予算に従ってシミュレーションする（探索中はGILを解放するので別スレッドから停止できる）
+    #[pyo3(name = "random_move_with_budget")]
+    #[pyo3(signature = (budget, threads = None, rollout = None))]
+    pub fn python_random_move_with_budget(
+        &self,
+        py: Python<'_>,
+        budget: SearchBudget,
+        threads: Option<usize>,
+        rollout: Option<RolloutConfig>,
+    ) -> PyResult<Vec<MctsResult>> {
+        let num_threads = threads.unwrap_or_else(num_cpus::get);
+        let config = rollout.unwrap_or_default();
+        py.allow_threads(|| self.rollout_with_budget(&budget, num_threads, &config))
+            .map_err(pyo3::exceptions::PyValueError::new_err)
+    }
+
+    /// PUCT探索をする（`evaluator` を省くと一様な評価器を使う）
+    ///
+    /// 木をたどる間はGILを解放し、`evaluator` を呼ぶときだけGILを取る。
+    #[pyo3(name = "puct_search")]
+    #[pyo3(signature = (evaluator = None, config = None))]
+    pub fn python_puct_search(
+        &self,
+        py: Python<'_>,
+        evaluator: Option<Py<PyAny>>,
+        config: Option<PuctConfig>,
+    ) -> PyResult<PuctResult> {
+        let config = config.unwrap_or_default();
+        let result = match evaluator {
+            Some(callback) => {
+                let evaluator = PyEvaluator::new(callback);
+                py.allow_threads(|| self.puct_search(&evaluator, &config))
+            }
+            None => py.allow_threads(|| self.puct_search(&UniformEvaluator, &config)),
+        };
+        result.map_err(pyo3::exceptions::PyValueError::new_err)
     }
 
     #[new]
-    #[pyo3(signature = (board = Board::new_for_python("startpos".to_string()), move_number = 1, turn = ColorType::Black, winner = ColorType::None))]
+    #[pyo3(signature = (board = Board::new_for_python("startpos".to_string(), Variant::Standard), move_number = 1, turn = ColorType::Black, winner = ColorType::None))]
     pub fn new_for_python(
         board: Board,
         move_number: u16,
@@ -248,6 +508,30 @@ impl Game {
         }
     }
 
+    #[staticmethod]
+    #[pyo3(name = "from_sfen")]
+    pub fn python_from_sfen(sfen: &str) -> Self {
+        Self::from_sfen(sfen)
+    }
+
+    #[staticmethod]
+    #[pyo3(name = "from_variant")]
+    pub fn python_from_variant(variant: Variant) -> Self {
+        Self::from_variant(variant)
+    }
+
+    #[staticmethod]
+    #[pyo3(name = "from_handicap")]
+    pub fn python_from_handicap(name: &str) -> PyResult<Self> {
+        Self::from_handicap(name).map_err(pyo3::exceptions::PyValueError::new_err)
+    }
+
+    #[staticmethod]
+    #[pyo3(name = "from_kif_header")]
+    pub fn python_from_kif_header(kif: &str) -> PyResult<Self> {
+        Self::from_kif_header(kif).map_err(pyo3::exceptions::PyValueError::new_err)
+    }
+
     #[pyo3(name = "input_board")]
     pub fn python_input_board(&mut self, sfen: String) {
         self.input_board(sfen);
@@ -268,6 +552,11 @@ impl Game {
         self.input_turn(sfen);
     }
 
+    #[pyo3(name = "to_sfen")]
+    pub fn python_to_sfen(&self) -> String {
+        self.to_sfen()
+    }
+
     #[pyo3(name = "is_finished")]
     pub fn python_is_finished(&self) -> (bool, ColorType) {
         self.is_finished()
@@ -278,6 +567,31 @@ impl Game {
         self.execute_move(moves);
     }
 
+    #[pyo3(name = "hash_key")]
+    pub fn python_hash_key(&self) -> u64 {
+        self.hash_key()
+    }
+
+    #[pyo3(name = "make_move")]
+    pub fn python_make_move(&mut self, mv: &Move) -> ExtendedMove {
+        self.make_move(mv)
+    }
+
+    #[pyo3(name = "undo_move")]
+    pub fn python_undo_move(&mut self, mv: &ExtendedMove) {
+        self.undo_move(mv);
+    }
+
+    #[pyo3(name = "make_null_move")]
+    pub fn python_make_null_move(&mut self) -> ExtendedMove {
+        self.make_null_move()
+    }
+
+    #[pyo3(name = "undo_null_move")]
+    pub fn python_undo_null_move(&mut self) {
+        self.undo_null_move();
+    }
+
     #[pyo3(name = "random_play")]
     pub fn python_random_play(&mut self) -> Self {
         self.random_play()
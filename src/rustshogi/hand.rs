@@ -107,14 +107,48 @@ impl Hand {
     }
 
     pub fn get_player_pieces(&self, color_type: color::ColorType) -> Vec<piece::Piece> {
-        let mut res: Vec<piece::Piece> = Vec::new();
-        for i in piece::PieceType::King as usize..=piece::NOT_PRO_PIECE_TYPE_NUMBER as usize {
-            let index = Self::calc_index(color_type, piece::PieceType::from_usize(i));
-            if self.counts[index as usize] > 0 {
-                res.push(self.pieces[index as usize]);
+        self.player_pieces(color_type).collect()
+    }
+
+    /// 1枚以上ある持ち駒を、`Vec` を作らずに順に返す
+    pub fn player_pieces(
+        &self,
+        color_type: color::ColorType,
+    ) -> impl Iterator<Item = piece::Piece> + '_ {
+        (piece::PieceType::King as usize..=piece::NOT_PRO_PIECE_TYPE_NUMBER as usize).filter_map(
+            move |i| {
+                let index = Self::calc_index(color_type, piece::PieceType::from_usize(i)) as usize;
+                (self.counts[index] > 0).then_some(self.pieces[index])
+            },
+        )
+    }
+
+    /// SFENの持ち駒の部分（先手の飛角金銀桂香歩、後手の順。持ち駒がなければ "-"）
+    pub fn to_sfen(&self) -> String {
+        let mut result = String::new();
+        for color_type in [color::ColorType::Black, color::ColorType::White] {
+            for piece_type in [
+                piece::PieceType::Rook,
+                piece::PieceType::Bichop,
+                piece::PieceType::Gold,
+                piece::PieceType::Silver,
+                piece::PieceType::Knight,
+                piece::PieceType::Lance,
+                piece::PieceType::Pawn,
+            ] {
+                let count = self.get_count(color_type, piece_type);
+                if count > 1 {
+                    result.push_str(&count.to_string());
+                }
+                if count > 0 {
+                    result.push_str(&piece::Piece::convert_string(piece_type, color_type));
+                }
             }
         }
-        res
+        if result.is_empty() {
+            result.push('-');
+        }
+        result
     }
 }
 
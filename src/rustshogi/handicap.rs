@@ -0,0 +1,151 @@
+use pyo3::prelude::*;
+
+/// 駒落ちで先手（下手）側の駒を並べた、共通の盤面の後半
+const BLACK_SIDE: &str = "ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL";
+
+/// 手合割（駒落ちでは上手が後手として盤面の駒を落とし、先に指す）
+#[pyclass(eq, eq_int)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+pub enum Handicap {
+    Even = 0,
+    Lance,
+    Bishop,
+    Rook,
+    RookLance,
+    TwoPiece,
+    FourPiece,
+    SixPiece,
+    EightPiece,
+    TenPiece,
+}
+
+impl Handicap {
+    pub const ALL: [Handicap; 10] = [
+        Handicap::Even,
+        Handicap::Lance,
+        Handicap::Bishop,
+        Handicap::Rook,
+        Handicap::RookLance,
+        Handicap::TwoPiece,
+        Handicap::FourPiece,
+        Handicap::SixPiece,
+        Handicap::EightPiece,
+        Handicap::TenPiece,
+    ];
+
+    /// 上手（後手）側の1段目と2段目
+    fn white_side(&self) -> &'static str {
+        match self {
+            Handicap::Even => "lnsgkgsnl/1r5b1",
+            Handicap::Lance => "lnsgkgsn1/1r5b1",
+            Handicap::Bishop => "lnsgkgsnl/1r7",
+            Handicap::Rook => "lnsgkgsnl/7b1",
+            Handicap::RookLance => "lnsgkgsn1/7b1",
+            Handicap::TwoPiece => "lnsgkgsnl/9",
+            Handicap::FourPiece => "1nsgkgsn1/9",
+            Handicap::SixPiece => "2sgkgs2/9",
+            Handicap::EightPiece => "3gkg3/9",
+            Handicap::TenPiece => "4k4/9",
+        }
+    }
+
+    /// 盤面部分のSFEN
+    pub fn board_sfen(&self) -> String {
+        format!("{}/{}", self.white_side(), BLACK_SIDE)
+    }
+
+    /// 先に指す側を含めたSFEN（平手は先手、駒落ちは上手の後手から指す）
+    pub fn sfen(&self) -> String {
+        let turn = if *self == Handicap::Even { 'b' } else { 'w' };
+        format!("{} {} - 1", self.board_sfen(), turn)
+    }
+
+    /// KIFの「手合割」に書く名前
+    pub fn kif_name(&self) -> &'static str {
+        match self {
+            Handicap::Even => "平手",
+            Handicap::Lance => "香落ち",
+            Handicap::Bishop => "角落ち",
+            Handicap::Rook => "飛車落ち",
+            Handicap::RookLance => "飛香落ち",
+            Handicap::TwoPiece => "二枚落ち",
+            Handicap::FourPiece => "四枚落ち",
+            Handicap::SixPiece => "六枚落ち",
+            Handicap::EightPiece => "八枚落ち",
+            Handicap::TenPiece => "十枚落ち",
+        }
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Handicap::Even => "even",
+            Handicap::Lance => "lance",
+            Handicap::Bishop => "bishop",
+            Handicap::Rook => "rook",
+            Handicap::RookLance => "rook-lance",
+            Handicap::TwoPiece => "2-piece",
+            Handicap::FourPiece => "4-piece",
+            Handicap::SixPiece => "6-piece",
+            Handicap::EightPiece => "8-piece",
+            Handicap::TenPiece => "10-piece",
+        }
+    }
+
+    /// 英語の名前（"2-piece" など）かKIFの名前（"二枚落ち" など）から手合割を返す
+    pub fn from_name(name: &str) -> Option<Handicap> {
+        let name = name.trim();
+        let normalized = name.to_ascii_lowercase().replace(['_', ' '], "-");
+        Self::ALL
+            .into_iter()
+            .find(|handicap| handicap.get_name() == normalized || handicap.kif_name() == name)
+    }
+
+    /// KIFのヘッダーの「手合割：〜」の行から手合割を読む（行がなければ平手）
+    pub fn from_kif_header(kif: &str) -> Result<Handicap, String> {
+        for line in kif.lines() {
+            let line = line.trim();
+            if let Some(value) = line.strip_prefix("手合割") {
+                let value = value.trim_start_matches(['：', ':']).trim();
+                return Self::from_name(value)
+                    .ok_or_else(|| format!("unknown handicap: {}", value));
+            }
+        }
+        Ok(Handicap::Even)
+    }
+}
+
+#[pymethods]
+impl Handicap {
+    #[staticmethod]
+    #[pyo3(name = "from_name")]
+    pub fn python_from_name(name: &str) -> PyResult<Handicap> {
+        Self::from_name(name).ok_or_else(|| {
+            pyo3::exceptions::PyValueError::new_err(format!("unknown handicap: {}", name))
+        })
+    }
+
+    #[staticmethod]
+    #[pyo3(name = "from_kif_header")]
+    pub fn python_from_kif_header(kif: &str) -> PyResult<Handicap> {
+        Self::from_kif_header(kif).map_err(pyo3::exceptions::PyValueError::new_err)
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!("<Handicap.{}: {}>", self.get_name(), *self as u8)
+    }
+
+    #[getter]
+    pub fn name(&self) -> String {
+        self.get_name().to_string()
+    }
+
+    #[getter]
+    pub fn kif(&self) -> String {
+        self.kif_name().to_string()
+    }
+
+    #[pyo3(name = "sfen")]
+    pub fn python_sfen(&self) -> String {
+        self.sfen()
+    }
+}
@@ -15,7 +15,15 @@ pub struct MctsResult {
     #[pyo3(get, set)]
     pub black_wins: u64,
     #[pyo3(get, set)]
+    pub draws: u64,
+    #[pyo3(get, set)]
     pub total_games: u64,
+    #[pyo3(get, set)]
+    pub total_moves: u64,
+    #[pyo3(get, set)]
+    pub value_sum: f64,
+    #[pyo3(get, set)]
+    pub value_square_sum: f64,
 }
 
 #[pymethods]
@@ -23,7 +31,56 @@ impl MctsResult {
     pub fn merge(&mut self, other: &MctsResult) {
         self.white_wins += other.white_wins;
         self.black_wins += other.black_wins;
+        self.draws += other.draws;
         self.total_games += other.total_games;
+        self.total_moves += other.total_moves;
+        self.value_sum += other.value_sum;
+        self.value_square_sum += other.value_square_sum;
+    }
+
+    #[pyo3(name = "calc_result")]
+    pub fn python_calc_result(&self, turn: ColorType) -> f64 {
+        self.calc_result(turn)
+    }
+
+    #[pyo3(name = "average_game_length")]
+    pub fn python_average_game_length(&self) -> f64 {
+        self.average_game_length()
+    }
+
+    #[pyo3(name = "mean_value")]
+    pub fn python_mean_value(&self, turn: ColorType) -> f64 {
+        self.mean_value(turn)
+    }
+
+    #[pyo3(name = "value_variance")]
+    pub fn python_value_variance(&self) -> f64 {
+        self.value_variance()
+    }
+
+    #[pyo3(name = "value_interval")]
+    #[pyo3(signature = (turn, z = 1.96))]
+    pub fn python_value_interval(&self, turn: ColorType, z: f64) -> (f64, f64) {
+        self.value_interval(turn, z)
+    }
+
+    #[pyo3(name = "wilson_interval")]
+    #[pyo3(signature = (turn, z = 1.96))]
+    pub fn python_wilson_interval(&self, turn: ColorType, z: f64) -> (f64, f64) {
+        self.wilson_interval(turn, z)
+    }
+
+    #[pyo3(name = "ucb")]
+    #[pyo3(signature = (turn, parent_games, c = std::f64::consts::SQRT_2))]
+    pub fn python_ucb(&self, turn: ColorType, parent_games: u64, c: f64) -> f64 {
+        self.ucb(turn, parent_games, c)
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!(
+            "MctsResult(mv={}, black_wins={}, white_wins={}, draws={}, total_games={})",
+            self.mv, self.black_wins, self.white_wins, self.draws, self.total_games
+        )
     }
 }
 
@@ -35,13 +92,7 @@ impl Default for MctsResult {
 
 impl MctsResult {
     pub fn new() -> Self {
-        Self {
-            board: Board::new(),
-            mv: Move::new(),
-            white_wins: 0,
-            black_wins: 0,
-            total_games: 0,
-        }
+        Self::from(Board::new(), Move::new())
     }
 
     pub fn from(board: Board, mv: Move) -> Self {
@@ -50,17 +101,40 @@ impl MctsResult {
             mv,
             white_wins: 0,
             black_wins: 0,
+            draws: 0,
             total_games: 0,
+            total_moves: 0,
+            value_sum: 0.0,
+            value_square_sum: 0.0,
+        }
+    }
+
+    /// 勝敗から先手視点の値（勝ち1.0、引き分け0.5、負け0.0）を求める
+    pub fn winner_to_value(winner: ColorType) -> f64 {
+        match winner {
+            ColorType::Black => 1.0,
+            ColorType::White => 0.0,
+            _ => 0.5,
         }
     }
 
     pub fn plus_result(&mut self, winner: ColorType) {
+        self.plus_simulation(winner, 0, Self::winner_to_value(winner));
+    }
+
+    /// シミュレーション1回分の結果を加える
+    ///
+    /// `value` は先手視点の値（0.0〜1.0）。打ち切りによる評価値など勝敗以外の結果にも使う。
+    pub fn plus_simulation(&mut self, winner: ColorType, game_length: u64, value: f64) {
         match winner {
             ColorType::White => self.white_wins += 1,
             ColorType::Black => self.black_wins += 1,
-            _ => {}
+            _ => self.draws += 1,
         }
         self.total_games += 1;
+        self.total_moves += game_length;
+        self.value_sum += value;
+        self.value_square_sum += value * value;
     }
 
     pub fn calc_result(&self, turn: ColorType) -> f64 {
@@ -77,12 +151,80 @@ impl MctsResult {
         current_turn_wins / self.total_games as f64
     }
 
+    pub fn average_game_length(&self) -> f64 {
+        if self.total_games == 0 {
+            return 0.0;
+        }
+        self.total_moves as f64 / self.total_games as f64
+    }
+
+    /// 手番側から見た値の平均
+    pub fn mean_value(&self, turn: ColorType) -> f64 {
+        if self.total_games == 0 {
+            return 0.5;
+        }
+        let mean = self.value_sum / self.total_games as f64;
+        if turn == ColorType::White {
+            1.0 - mean
+        } else {
+            mean
+        }
+    }
+
+    /// 値の不偏分散（手番によらない）
+    pub fn value_variance(&self) -> f64 {
+        if self.total_games < 2 {
+            return 0.0;
+        }
+        let n = self.total_games as f64;
+        let mean = self.value_sum / n;
+        ((self.value_square_sum - n * mean * mean) / (n - 1.0)).max(0.0)
+    }
+
+    /// 値の平均に対する正規近似の信頼区間
+    pub fn value_interval(&self, turn: ColorType, z: f64) -> (f64, f64) {
+        if self.total_games == 0 {
+            return (0.0, 1.0);
+        }
+        let mean = self.mean_value(turn);
+        let margin = z * (self.value_variance() / self.total_games as f64).sqrt();
+        ((mean - margin).max(0.0), (mean + margin).min(1.0))
+    }
+
+    /// 勝率に対するWilsonスコア区間（引き分けは0.5勝として数える）
+    pub fn wilson_interval(&self, turn: ColorType, z: f64) -> (f64, f64) {
+        if self.total_games == 0 {
+            return (0.0, 1.0);
+        }
+        let n = self.total_games as f64;
+        let wins = if turn == ColorType::White {
+            self.white_wins
+        } else {
+            self.black_wins
+        } as f64;
+        let p = (wins + 0.5 * self.draws as f64) / n;
+        let z2 = z * z;
+        let denominator = 1.0 + z2 / n;
+        let center = (p + z2 / (2.0 * n)) / denominator;
+        let margin = z * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt() / denominator;
+        ((center - margin).max(0.0), (center + margin).min(1.0))
+    }
+
+    /// UCB1の値（未試行の手は無限大）
+    pub fn ucb(&self, turn: ColorType, parent_games: u64, c: f64) -> f64 {
+        if self.total_games == 0 {
+            return f64::INFINITY;
+        }
+        let exploration = c * ((parent_games.max(1) as f64).ln() / self.total_games as f64).sqrt();
+        self.mean_value(turn) + exploration
+    }
+
     pub fn print_result(&self) {
         println!("{} times played!", self.total_games);
         println!("bestmove {}", self.mv.to_string());
         println!(
-            "White wins: {}, Black wins: {}",
-            self.white_wins, self.black_wins
+            "White wins: {}, Black wins: {}, Draws: {}",
+            self.white_wins, self.black_wins, self.draws
         );
     }
 }
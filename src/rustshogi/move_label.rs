@@ -0,0 +1,166 @@
+use super::address::{index_to_square, square_to_index, Address};
+use super::board::Board;
+use super::color::ColorType;
+use super::move_list::MoveList;
+use super::moves::Move;
+use super::piece::{Piece, PieceType};
+
+/// 盤上の駒を動かす方向の数（8方向と桂馬の2方向）
+pub const MOVE_DIRECTIONS: usize = 10;
+
+/// 駒を打つ手の駒の種類
+pub const DROP_PIECE_TYPES: [PieceType; 7] = [
+    PieceType::Pawn,
+    PieceType::Lance,
+    PieceType::Knight,
+    PieceType::Silver,
+    PieceType::Gold,
+    PieceType::Bichop,
+    PieceType::Rook,
+];
+
+/// 指し手の平面の数（方向 × 成・不成と、打つ駒の種類）
+pub const MOVE_PLANES: usize = 2 * MOVE_DIRECTIONS + DROP_PIECE_TYPES.len();
+
+/// 指し手のラベルの数（平面 × 移動先の81マス）
+pub const MOVE_LABEL_NUMBER: usize = MOVE_PLANES * 81;
+
+/// 手番側から見た各方向の（前へ進む段数, 筋の増減）
+///
+/// 上, 斜め上（筋が減る側）, 斜め上（筋が増える側）, 横（減る側）, 横（増える側）,
+/// 下, 斜め下（減る側）, 斜め下（増える側）, 桂馬（減る側）, 桂馬（増える側）の順。
+const DIRECTION_VECTORS: [(i8, i8); MOVE_DIRECTIONS] = [
+    (1, 0),
+    (1, -1),
+    (1, 1),
+    (0, -1),
+    (0, 1),
+    (-1, 0),
+    (-1, -1),
+    (-1, 1),
+    (2, -1),
+    (2, 1),
+];
+
+/// 後手番のときに盤を180度回したマスの通し番号
+fn oriented_square(square: u8, turn: ColorType) -> u8 {
+    if turn == ColorType::White {
+        80 - square
+    } else {
+        square
+    }
+}
+
+/// 手番側から見た移動の向きから方向の番号を求める
+fn direction_of(forward: i8, side: i8) -> Option<usize> {
+    let is_knight = forward == 2 && side.abs() == 1;
+    let is_line = forward == 0 || side == 0 || forward.abs() == side.abs();
+    if forward == 0 && side == 0 {
+        return None;
+    }
+    let vector = if is_knight {
+        (forward, side)
+    } else if is_line {
+        (forward.signum(), side.signum())
+    } else {
+        return None;
+    };
+    DIRECTION_VECTORS.iter().position(|v| *v == vector)
+}
+
+/// 手を `turn` 側から見た方策ネットワークのラベル（`平面 * 81 + 移動先`）に変換する
+///
+/// パスと、縦横斜め・桂馬のどれでもない移動はラベルを持たない。
+pub fn move_to_label(mv: &Move, turn: ColorType) -> Option<usize> {
+    if mv.get_is_pass() {
+        return None;
+    }
+    let to_square = oriented_square(index_to_square(mv.get_to().to_index())?, turn) as usize;
+
+    if mv.get_is_drop() {
+        let piece_type = mv.get_piece().piece_type;
+        let plane = 2 * MOVE_DIRECTIONS
+            + DROP_PIECE_TYPES
+                .iter()
+                .position(|drop| *drop == piece_type)?;
+        return Some(plane * 81 + to_square);
+    }
+
+    let from = mv.get_from();
+    let to = mv.get_to();
+    let sign = if turn == ColorType::White { -1 } else { 1 };
+    let forward = (to.get_row() as i8 - from.get_row() as i8) * sign;
+    let side = (to.get_column() as i8 - from.get_column() as i8) * sign;
+    let direction = direction_of(forward, side)?;
+    let plane = if mv.get_is_promote() {
+        MOVE_DIRECTIONS + direction
+    } else {
+        direction
+    };
+    Some(plane * 81 + to_square)
+}
+
+/// `turn` 側の手番の盤面でラベルが表す手を返す
+///
+/// 盤上の駒を動かす手は、移動先から逆向きにたどって最初に見つかった駒が `turn` 側の駒で、
+/// 移動先まで動ける場合だけ作れる。
+pub fn label_to_move(label: usize, board: &Board, turn: ColorType) -> Result<Move, String> {
+    if label >= MOVE_LABEL_NUMBER {
+        return Err(format!("move label out of range: {}", label));
+    }
+    let plane = label / 81;
+    let to_index = square_to_index(oriented_square((label % 81) as u8, turn));
+    let to = Address::from_number(to_index);
+
+    if plane >= 2 * MOVE_DIRECTIONS {
+        let piece_type = DROP_PIECE_TYPES[plane - 2 * MOVE_DIRECTIONS];
+        return Ok(Move::from_drop(Piece::from(turn, piece_type), to));
+    }
+
+    let direction = plane % MOVE_DIRECTIONS;
+    let promote = plane >= MOVE_DIRECTIONS;
+    let (forward, side) = DIRECTION_VECTORS[direction];
+    let sign = if turn == ColorType::White { -1 } else { 1 };
+    let row_step = forward * sign;
+    let column_step = side * sign;
+    // 桂馬以外は飛び駒の可能性があるので、駒に当たるまでたどる
+    let max_distance = if direction >= 8 { 1 } else { 8 };
+
+    let mut row = to.get_row() as i8;
+    let mut column = to.get_column() as i8;
+    for _ in 0..max_distance {
+        row -= row_step;
+        column -= column_step;
+        if !(1..=9).contains(&row) || !(1..=9).contains(&column) {
+            break;
+        }
+        let from_index = Address::from_numbers(column as u8, row as u8).to_index();
+        let piece = board.get_piece(from_index);
+        if piece.piece_type == PieceType::None {
+            continue;
+        }
+        let reachable = board.get_able_move_squares(from_index).to_u128() >> (127 - to_index) & 1;
+        if piece.owner == turn && reachable != 0 {
+            return Ok(Move::from_standart(
+                Address::from_number(from_index),
+                to,
+                promote,
+            ));
+        }
+        break;
+    }
+    Err(format!("no piece can make move label: {}", label))
+}
+
+/// `turn` 側の合法手のラベルに `true` を立てた、長さ `MOVE_LABEL_NUMBER` の配列
+pub fn legal_move_mask(board: &Board, turn: ColorType) -> Vec<bool> {
+    let mut mask = vec![false; MOVE_LABEL_NUMBER];
+    let mut moves = MoveList::new();
+    board.search_moves_into(turn, &mut moves);
+    for mv in moves.iter().filter(|mv| board.is_legal(mv)) {
+        if let Some(label) = move_to_label(mv, turn) {
+            mask[label] = true;
+        }
+    }
+    mask
+}
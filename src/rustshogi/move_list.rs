@@ -0,0 +1,95 @@
+use super::moves::Move;
+
+use std::ops::{Deref, DerefMut};
+
+/// 1局面で生成する指し手の最大数
+///
+/// 合法手の最大は593手だが、`Board::search_moves_into` は自玉への王手を残す手や成らない手も含む
+/// 疑似合法手を生成するので、593手では溢れる局面がある。例えば持ち駒7種と飛車・角・銀の成り不成がある
+/// `R8/2K1S1SSk/4B4/9/9/9/9/9/1L1L1L3 b RBGSNLP3g3n17p3l 1` では596手になる
+/// （`test_board_search_moves_more_than_legal_maximum`）。
+/// 盤上の駒の手（成り・不成を別に数えて428手以下）と駒打ち（7種×81マス）の合計を上回るようにしておく。
+pub const MAX_MOVES: usize = 1024;
+
+const _: () = assert!(MAX_MOVES >= 428 + 7 * 81);
+
+/// 指し手を `MAX_MOVES` 個まで持てる固定長のリスト
+///
+/// スタック上に置いて使い回せるので、プレイアウトのように手の生成を繰り返す処理で
+/// `Vec` の確保と解放を避けられる。スライスとして読み書きできる。
+#[derive(Clone)]
+pub struct MoveList {
+    moves: [Move; MAX_MOVES],
+    len: usize,
+}
+
+impl MoveList {
+    pub fn new() -> Self {
+        Self {
+            moves: [Move { value: 0 }; MAX_MOVES],
+            len: 0,
+        }
+    }
+
+    /// 末尾に手を加える（`MAX_MOVES` を超えるとパニックする）
+    pub fn push(&mut self, mv: Move) {
+        assert!(self.len < MAX_MOVES, "MoveList is full");
+        self.moves[self.len] = mv;
+        self.len += 1;
+    }
+
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    pub fn as_slice(&self) -> &[Move] {
+        &self.moves[..self.len]
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [Move] {
+        &mut self.moves[..self.len]
+    }
+}
+
+impl Default for MoveList {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deref for MoveList {
+    type Target = [Move];
+
+    fn deref(&self) -> &[Move] {
+        self.as_slice()
+    }
+}
+
+impl DerefMut for MoveList {
+    fn deref_mut(&mut self) -> &mut [Move] {
+        self.as_mut_slice()
+    }
+}
+
+impl Extend<Move> for MoveList {
+    fn extend<T: IntoIterator<Item = Move>>(&mut self, iter: T) {
+        for mv in iter {
+            self.push(mv);
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a MoveList {
+    type Item = &'a Move;
+    type IntoIter = std::slice::Iter<'a, Move>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.as_slice().iter()
+    }
+}
+
+impl std::fmt::Debug for MoveList {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_list().entries(self.as_slice()).finish()
+    }
+}
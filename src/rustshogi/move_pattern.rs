@@ -1,5 +1,5 @@
 use crate::direction::DirectionName;
-use crate::piece::MoveType;
+use crate::piece::{MoveType, PieceType};
 
 pub struct MovePattern {
     pub move_type: MoveType,
@@ -225,3 +225,23 @@ pub const PRO_SILVER_MOVE_PATTERNS: &[MovePattern] = GOLD_MOVE_PATTERNS;
 pub const PRO_KNIGHT_MOVE_PATTERNS: &[MovePattern] = GOLD_MOVE_PATTERNS;
 pub const PRO_LANCE_MOVE_PATTERNS: &[MovePattern] = GOLD_MOVE_PATTERNS;
 pub const PRO_PAWN_MOVE_PATTERNS: &[MovePattern] = GOLD_MOVE_PATTERNS;
+
+pub fn get_move_patterns(piece_type: PieceType) -> &'static [MovePattern] {
+    match piece_type {
+        PieceType::King => KING_MOVE_PATTERNS,
+        PieceType::Gold => GOLD_MOVE_PATTERNS,
+        PieceType::Rook => ROOK_MOVE_PATTERNS,
+        PieceType::Bichop => BICHOP_MOVE_PATTERNS,
+        PieceType::Silver => SILVER_MOVE_PATTERNS,
+        PieceType::Knight => KNIGHT_MOVE_PATTERNS,
+        PieceType::Lance => LANCE_MOVE_PATTERNS,
+        PieceType::Pawn => PAWN_MOVE_PATTERNS,
+        PieceType::Dragon => DRAGON_MOVE_PATTERNS,
+        PieceType::Horse => HORSE_MOVE_PATTERNS,
+        PieceType::ProSilver => PRO_SILVER_MOVE_PATTERNS,
+        PieceType::ProKnight => PRO_KNIGHT_MOVE_PATTERNS,
+        PieceType::ProLance => PRO_LANCE_MOVE_PATTERNS,
+        PieceType::ProPawn => PRO_PAWN_MOVE_PATTERNS,
+        _ => &[],
+    }
+}
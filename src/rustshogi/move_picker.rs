@@ -0,0 +1,318 @@
+use super::address::Address;
+use super::board::Board;
+use super::color::{get_reverse_color, ColorType};
+use super::moves::Move;
+use super::piece::Piece;
+
+/// 指し手を生成する段階（この順に手を返す）
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Stage {
+    /// 駒を取る手と成る手
+    Captures,
+    /// 駒を取らず成りもしない王手（駒打ちを含む）
+    Checks,
+    /// 残りの盤上の駒を動かす手
+    Quiets,
+    /// 残りの駒打ち
+    Drops,
+    /// すべての手を返し終えた
+    Done,
+}
+
+impl Stage {
+    fn next(&self) -> Stage {
+        match self {
+            Stage::Captures => Stage::Checks,
+            Stage::Checks => Stage::Quiets,
+            Stage::Quiets => Stage::Drops,
+            Stage::Drops | Stage::Done => Stage::Done,
+        }
+    }
+}
+
+/// 指し手の並べ替えに使う点数を加えるフック
+pub trait MoveScorer {
+    fn score(&self, board: &Board, turn: ColorType, mv: &Move, stage: Stage) -> i32;
+}
+
+/// MVV-LVA（価値の高い駒を、価値の低い駒で取る手を優先する）の点数
+///
+/// 成りによる駒の価値の増分も加える。
+pub fn mvv_lva(board: &Board, mv: &Move) -> i32 {
+    if mv.get_is_drop() {
+        return 0;
+    }
+    let attacker = board.get_piece_type_from_index(mv.get_from().to_index());
+    let victim = board.get_piece_type_from_index(mv.get_to().to_index());
+    let mut score = Piece::get_value(victim) * 16 - Piece::get_value(attacker);
+    if mv.get_is_promote() {
+        score += (Piece::get_value(attacker.promote()) - Piece::get_value(attacker)) * 16;
+    }
+    score
+}
+
+/// 探索の深さ（ply）ごとに、β カットを起こした駒を取らない手を2つまで覚える表
+#[derive(Clone, Debug)]
+pub struct KillerTable {
+    killers: Vec<[u16; 2]>,
+}
+
+impl KillerTable {
+    pub fn new(max_ply: usize) -> Self {
+        Self {
+            killers: vec![[0; 2]; max_ply],
+        }
+    }
+
+    pub fn store(&mut self, ply: usize, mv: &Move) {
+        let Some(slot) = self.killers.get_mut(ply) else {
+            return;
+        };
+        if slot[0] != mv.value {
+            slot[1] = slot[0];
+            slot[0] = mv.value;
+        }
+    }
+
+    /// キラー手なら何番目か（0か1）を返す
+    pub fn rank(&self, ply: usize, mv: &Move) -> Option<usize> {
+        self.killers.get(ply).and_then(|slot| {
+            slot.iter()
+                .position(|value| *value != 0 && *value == mv.value)
+        })
+    }
+
+    pub fn clear(&mut self) {
+        self.killers.iter_mut().for_each(|slot| *slot = [0; 2]);
+    }
+}
+
+/// 手番・移動元（打つ駒）・移動先ごとに、良かった手の点数を積み上げる表
+#[derive(Clone, Debug)]
+pub struct HistoryTable {
+    table: Vec<i32>,
+}
+
+/// 点数がこの値を超えたら全体を半分にする
+const HISTORY_LIMIT: i32 = 1 << 20;
+
+impl HistoryTable {
+    pub fn new() -> Self {
+        Self {
+            table: vec![0; ColorType::ColorNumber as usize * 128 * 128],
+        }
+    }
+
+    fn index(color: ColorType, mv: &Move) -> usize {
+        // 下位14ビットは移動先と、移動元または打つ駒
+        (color as usize) * 128 * 128 + (mv.value & 0x3FFF) as usize
+    }
+
+    /// 深さ `depth` で良かった手の点数を加える
+    pub fn update(&mut self, color: ColorType, mv: &Move, depth: u32) {
+        let index = Self::index(color, mv);
+        self.table[index] += (depth * depth) as i32;
+        if self.table[index] > HISTORY_LIMIT {
+            self.table.iter_mut().for_each(|value| *value /= 2);
+        }
+    }
+
+    pub fn get(&self, color: ColorType, mv: &Move) -> i32 {
+        self.table[Self::index(color, mv)]
+    }
+
+    pub fn clear(&mut self) {
+        self.table.iter_mut().for_each(|value| *value = 0);
+    }
+}
+
+impl Default for HistoryTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// キラー手に与える点数（1番目、2番目）
+const KILLER_SCORES: [i32; 2] = [1 << 24, 1 << 23];
+
+/// 手を段階的に生成し、良さそうな手から順に返すイテレータ
+///
+/// 駒を取る手と成る手（MVV-LVA順）、王手、その他の駒を動かす手、駒打ちの順に返す。
+/// 段階ごとに必要になってから手を生成するので、最初の数手だけを使う場合は
+/// `Board::search_moves` で全ての手を作るより軽い。返す手の集合は `search_moves` と同じ。
+pub struct MovePicker<'a> {
+    board: &'a Board,
+    turn: ColorType,
+    stage: Stage,
+    moves: Vec<(Move, i32)>,
+    position: usize,
+    generated: bool,
+    /// 王手の段階で振り分けた、王手でない駒を動かす手と駒打ち
+    quiets: Vec<Move>,
+    drops: Vec<Move>,
+    killers: Option<(&'a KillerTable, usize)>,
+    history: Option<&'a HistoryTable>,
+    scorer: Option<&'a dyn MoveScorer>,
+}
+
+impl<'a> MovePicker<'a> {
+    pub fn new(board: &'a Board, turn: ColorType) -> Self {
+        Self {
+            board,
+            turn,
+            stage: Stage::Captures,
+            moves: Vec::new(),
+            position: 0,
+            generated: false,
+            quiets: Vec::new(),
+            drops: Vec::new(),
+            killers: None,
+            history: None,
+            scorer: None,
+        }
+    }
+
+    /// 深さ `ply` のキラー手を、王手とその他の駒を動かす手の先頭に並べる
+    pub fn with_killers(mut self, killers: &'a KillerTable, ply: usize) -> Self {
+        self.killers = Some((killers, ply));
+        self
+    }
+
+    /// 取らない手（王手・その他の手・駒打ち）をヒストリーの点数順に並べる
+    pub fn with_history(mut self, history: &'a HistoryTable) -> Self {
+        self.history = Some(history);
+        self
+    }
+
+    /// 全ての段階で `scorer` の点数を加えて並べる
+    pub fn with_scorer(mut self, scorer: &'a dyn MoveScorer) -> Self {
+        self.scorer = Some(scorer);
+        self
+    }
+
+    /// 直前に返した手の段階（まだ手を返していなければ最初の段階）
+    pub fn stage(&self) -> Stage {
+        self.stage
+    }
+
+    fn own_pieces(&self) -> &'a [u8] {
+        self.board.get_piece_list(self.turn)
+    }
+
+    fn score(&self, mv: &Move, stage: Stage) -> i32 {
+        let mut score = match stage {
+            Stage::Captures => mvv_lva(self.board, mv),
+            _ => self.history.map_or(0, |history| history.get(self.turn, mv)),
+        };
+        if stage == Stage::Checks || stage == Stage::Quiets {
+            if let Some((killers, ply)) = self.killers {
+                if let Some(rank) = killers.rank(ply, mv) {
+                    score += KILLER_SCORES[rank];
+                }
+            }
+        }
+        if let Some(scorer) = self.scorer {
+            score += scorer.score(self.board, self.turn, mv, stage);
+        }
+        score
+    }
+
+    fn generate_captures(&self) -> Vec<Move> {
+        let enemy = self.board.player_prossesion[get_reverse_color(self.turn) as usize];
+        let mut moves = Vec::new();
+        for &from in self.own_pieces() {
+            let movable = self.board.get_able_move_squares(from);
+            let from_address = Address::from_number(from);
+            moves.extend((movable & enemy).get_trues_iter().map(|to| {
+                Move::from_standart(from_address.clone(), Address::from_number(to), false)
+            }));
+            let promotable = self.board.get_able_pro_move_squares(from, movable);
+            moves.extend(promotable.get_trues_iter().map(|to| {
+                Move::from_standart(from_address.clone(), Address::from_number(to), true)
+            }));
+        }
+        moves
+    }
+
+    /// 駒を取らず成りもしない手を作り、王手を返して残りを `quiets` と `drops` に振り分ける
+    fn generate_checks(&mut self) -> Vec<Move> {
+        let mut not_captures = self.board.player_prossesion[get_reverse_color(self.turn) as usize];
+        not_captures.flip();
+
+        let mut checks = Vec::new();
+        for &from in self.own_pieces() {
+            let movable = self.board.get_able_move_squares(from) & not_captures;
+            let from_address = Address::from_number(from);
+            for to in movable.get_trues_iter() {
+                let mv = Move::from_standart(from_address.clone(), Address::from_number(to), false);
+                if self.board.gives_check(&mv) {
+                    checks.push(mv);
+                } else {
+                    self.quiets.push(mv);
+                }
+            }
+        }
+        for piece in self.board.hand.get_player_pieces(self.turn) {
+            let squares = self
+                .board
+                .get_able_drop_squares(piece.owner, piece.piece_type);
+            for to in squares.get_trues_iter() {
+                let mv = Move::from_drop(piece, Address::from_number(to));
+                if self.board.gives_check(&mv) {
+                    checks.push(mv);
+                } else {
+                    self.drops.push(mv);
+                }
+            }
+        }
+        checks
+    }
+
+    fn generate(&mut self, stage: Stage) {
+        let moves = match stage {
+            Stage::Captures => self.generate_captures(),
+            Stage::Checks => self.generate_checks(),
+            Stage::Quiets => std::mem::take(&mut self.quiets),
+            Stage::Drops => std::mem::take(&mut self.drops),
+            Stage::Done => Vec::new(),
+        };
+        let mut scored: Vec<(Move, i32)> = moves
+            .into_iter()
+            .map(|mv| {
+                let score = self.score(&mv, stage);
+                (mv, score)
+            })
+            .collect();
+        // 同点の手は生成順を保つ
+        scored.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+        self.moves = scored;
+        self.position = 0;
+    }
+}
+
+impl Iterator for MovePicker<'_> {
+    type Item = Move;
+
+    fn next(&mut self) -> Option<Move> {
+        loop {
+            if !self.generated {
+                self.generate(self.stage);
+                self.generated = true;
+            }
+            if let Some((mv, _)) = self.moves.get(self.position) {
+                self.position += 1;
+                return Some(*mv);
+            }
+            if self.stage == Stage::Done {
+                return None;
+            }
+            self.stage = self.stage.next();
+            self.generated = false;
+        }
+    }
+}
+
+/// 全ての手を段階順・点数順に並べて返す
+pub fn ordered_moves(board: &Board, turn: ColorType) -> Vec<Move> {
+    MovePicker::new(board, turn).collect()
+}
@@ -1,13 +1,20 @@
 use crate::address::Address;
+use crate::board::Board;
+use crate::color::{get_reverse_color, ColorType};
+use crate::move_label;
 use crate::piece::Piece;
+use crate::variant::Variant;
 
 use super::address;
 use super::piece;
 
 use pyo3::prelude::*;
 
+/// パスを表す値（移動元と移動先がどちらも盤外の127番）
+const PASS_VALUE: u16 = (127 << 7) | 127;
+
 #[pyclass]
-#[derive(Clone, Debug)]
+#[derive(Clone, Copy, Debug)]
 pub struct Move {
     pub value: u16,
 }
@@ -68,7 +75,15 @@ impl Move {
         res
     }
 
+    /// 駒を動かさずに手番だけを相手に渡す手
+    pub fn pass() -> Self {
+        Self { value: PASS_VALUE }
+    }
+
     pub fn from_csa(csa: &str) -> Self {
+        if csa == "0000" || csa == "pass" {
+            return Self::pass();
+        }
         let mut res = Self::new();
         let to = address::Address::from_string(&csa[2..]);
         if Self::is_drop(csa) {
@@ -85,6 +100,10 @@ impl Move {
         (self.value & (1 << 15)) != 0
     }
 
+    pub fn get_is_pass(&self) -> bool {
+        self.value == PASS_VALUE
+    }
+
     pub fn get_is_promote(&self) -> bool {
         (self.value & (1 << 14)) != 0
     }
@@ -108,6 +127,10 @@ impl Move {
     }
 
     pub fn to_string(&self) -> String {
+        // USIのパスの表記
+        if self.get_is_pass() {
+            return "0000".to_string();
+        }
         let mut first = String::with_capacity(2);
         if self.get_is_drop() {
             let piece = self.get_piece();
@@ -127,6 +150,52 @@ impl Move {
         }
         res
     }
+
+    /// `turn` 側から見た方策ネットワークのラベル（`move_label` を参照）
+    pub fn to_label(&self, turn: ColorType) -> Option<usize> {
+        move_label::move_to_label(self, turn)
+    }
+
+    /// `turn` 側の手番の盤面で、ラベルが表す手を作る
+    pub fn from_label(label: usize, board: &Board, turn: ColorType) -> Result<Self, String> {
+        move_label::label_to_move(label, board, turn)
+    }
+
+    /// `Board::mirrored` した盤での同じ手
+    pub fn mirrored(&self, variant: Variant) -> Self {
+        let width = variant.width();
+        if self.get_is_pass() {
+            *self
+        } else if self.get_is_drop() {
+            Self::from_drop(self.get_piece(), self.get_to().mirrored(width))
+        } else {
+            Self::from_standart(
+                self.get_from().mirrored(width),
+                self.get_to().mirrored(width),
+                self.get_is_promote(),
+            )
+        }
+    }
+
+    /// `Board::flipped` した盤での同じ手（打つ駒の持ち主も入れ替える）
+    pub fn flipped(&self, variant: Variant) -> Self {
+        let (width, height) = (variant.width(), variant.height());
+        if self.get_is_pass() {
+            *self
+        } else if self.get_is_drop() {
+            let piece = self.get_piece();
+            Self::from_drop(
+                Piece::from(get_reverse_color(piece.owner), piece.piece_type),
+                self.get_to().rotated(width, height),
+            )
+        } else {
+            Self::from_standart(
+                self.get_from().rotated(width, height),
+                self.get_to().rotated(width, height),
+                self.get_is_promote(),
+            )
+        }
+    }
 }
 
 #[pymethods]
@@ -167,11 +236,47 @@ impl Move {
         self.value != other.value
     }
 
+    #[staticmethod]
+    #[pyo3(name = "pass_move")]
+    pub fn python_pass_move() -> Self {
+        Self::pass()
+    }
+
+    #[staticmethod]
+    #[pyo3(name = "from_label")]
+    pub fn python_from_label(label: usize, board: &Board, turn: ColorType) -> PyResult<Self> {
+        Self::from_label(label, board, turn).map_err(pyo3::exceptions::PyValueError::new_err)
+    }
+
+    #[pyo3(name = "to_label")]
+    pub fn python_to_label(&self, turn: ColorType) -> PyResult<usize> {
+        self.to_label(turn).ok_or_else(|| {
+            pyo3::exceptions::PyValueError::new_err(format!("move has no label: {}", self))
+        })
+    }
+
+    #[pyo3(name = "mirrored")]
+    #[pyo3(signature = (variant = Variant::Standard))]
+    pub fn python_mirrored(&self, variant: Variant) -> Self {
+        self.mirrored(variant)
+    }
+
+    #[pyo3(name = "flipped")]
+    #[pyo3(signature = (variant = Variant::Standard))]
+    pub fn python_flipped(&self, variant: Variant) -> Self {
+        self.flipped(variant)
+    }
+
     #[pyo3(name = "is_drop")]
     pub fn python_is_drop(&self) -> bool {
         self.get_is_drop()
     }
 
+    #[pyo3(name = "is_pass")]
+    pub fn python_is_pass(&self) -> bool {
+        self.get_is_pass()
+    }
+
     #[pyo3(name = "is_promote")]
     pub fn python_is_promote(&self) -> bool {
         self.get_is_promote()
@@ -198,3 +303,169 @@ impl std::fmt::Display for Move {
         write!(f, "{}", self.to_string())
     }
 }
+
+/// 動かした駒と取った駒を持つ32ビットの指し手
+///
+/// 盤面を見なくても手の内容がわかるので、手を戻す処理や棋譜の出力に使う。
+#[pyclass]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct ExtendedMove {
+    pub value: u32,
+}
+
+impl ExtendedMove {
+    // ビット配置:
+    // bit 31: 未使用
+    // bit 30: 王手になる手か
+    // bits 29-23: 取った駒（`Piece::to_u8`、取らない手は0）
+    // bits 22-16: 動かした駒（成る前の駒、駒打ちは打った駒）
+    // bits 15-0: `Move`
+    const MOVED_SHIFT: u32 = 16;
+    const CAPTURED_SHIFT: u32 = 23;
+    const CHECK_BIT: u32 = 1 << 30;
+    const PIECE_MASK: u32 = 0x7F;
+
+    /// 駒のない `Piece` は0として持つ
+    fn encode_piece(piece: piece::Piece) -> u32 {
+        if piece.piece_type == piece::PieceType::None {
+            0
+        } else {
+            piece.to_u8() as u32
+        }
+    }
+
+    pub fn new(mv: &Move, moved: piece::Piece, captured: piece::Piece, is_check: bool) -> Self {
+        Self {
+            value: mv.value as u32
+                | Self::encode_piece(moved) << Self::MOVED_SHIFT
+                | Self::encode_piece(captured) << Self::CAPTURED_SHIFT
+                | if is_check { Self::CHECK_BIT } else { 0 },
+        }
+    }
+
+    /// 手を指す前の盤面から、動かす駒・取る駒・王手かどうかを調べて作る
+    pub fn from_move(mv: &Move, board: &Board) -> Self {
+        if mv.get_is_pass() {
+            return Self::new(mv, piece::Piece::new(), piece::Piece::new(), false);
+        }
+        let to_index = mv.get_to().to_index();
+        let moved = if mv.get_is_drop() {
+            mv.get_piece()
+        } else {
+            board.get_piece(mv.get_from().to_index())
+        };
+        let captured = if mv.get_is_drop() {
+            piece::Piece::new()
+        } else {
+            board.get_piece(to_index)
+        };
+        Self::new(mv, moved, captured, board.gives_check(mv))
+    }
+
+    pub fn to_move(&self) -> Move {
+        Move {
+            value: (self.value & 0xFFFF) as u16,
+        }
+    }
+
+    fn decode_piece(code: u32) -> piece::Piece {
+        if code == 0 {
+            piece::Piece::new()
+        } else {
+            piece::Piece::from_u8(code as u8)
+        }
+    }
+
+    /// 動かした駒（パスは駒の種類が `PieceType::None`）
+    pub fn get_moved_piece(&self) -> piece::Piece {
+        Self::decode_piece((self.value >> Self::MOVED_SHIFT) & Self::PIECE_MASK)
+    }
+
+    /// 取った駒（取らない手は駒の種類が `PieceType::None`）
+    pub fn get_captured_piece(&self) -> piece::Piece {
+        Self::decode_piece((self.value >> Self::CAPTURED_SHIFT) & Self::PIECE_MASK)
+    }
+
+    pub fn get_is_capture(&self) -> bool {
+        (self.value >> Self::CAPTURED_SHIFT) & Self::PIECE_MASK != 0
+    }
+
+    pub fn get_is_check(&self) -> bool {
+        self.value & Self::CHECK_BIT != 0
+    }
+
+    pub fn get_is_drop(&self) -> bool {
+        self.to_move().get_is_drop()
+    }
+
+    pub fn get_is_promote(&self) -> bool {
+        self.to_move().get_is_promote()
+    }
+
+    pub fn get_from(&self) -> address::Address {
+        self.to_move().get_from()
+    }
+
+    pub fn get_to(&self) -> address::Address {
+        self.to_move().get_to()
+    }
+}
+
+impl std::fmt::Display for ExtendedMove {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.to_move())
+    }
+}
+
+impl From<ExtendedMove> for Move {
+    fn from(mv: ExtendedMove) -> Self {
+        mv.to_move()
+    }
+}
+
+#[pymethods]
+impl ExtendedMove {
+    #[new]
+    pub fn new_for_python(mv: &Move, board: &Board) -> Self {
+        Self::from_move(mv, board)
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!(
+            "ExtendedMove(csa={}, moved={}, captured={}, check={})",
+            self,
+            self.get_moved_piece().to_string(),
+            self.get_captured_piece().to_string().trim(),
+            self.get_is_check()
+        )
+    }
+
+    pub fn __eq__(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+
+    #[pyo3(name = "to_move")]
+    pub fn python_to_move(&self) -> Move {
+        self.to_move()
+    }
+
+    #[pyo3(name = "get_moved_piece")]
+    pub fn python_get_moved_piece(&self) -> piece::Piece {
+        self.get_moved_piece()
+    }
+
+    #[pyo3(name = "get_captured_piece")]
+    pub fn python_get_captured_piece(&self) -> piece::Piece {
+        self.get_captured_piece()
+    }
+
+    #[pyo3(name = "is_capture")]
+    pub fn python_is_capture(&self) -> bool {
+        self.get_is_capture()
+    }
+
+    #[pyo3(name = "is_check")]
+    pub fn python_is_check(&self) -> bool {
+        self.get_is_check()
+    }
+}
@@ -0,0 +1,822 @@
+use super::address::Address;
+use super::board::Board;
+use super::color::ColorType;
+use super::moves::Move;
+use super::piece::{Piece, PieceType};
+use super::variant::Variant;
+
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use std::fs::{File, OpenOptions};
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::marker::PhantomData;
+
+/// ハフマン符号化した局面のバイト数（PackedSfenとHuffmanCodedPosで共通）
+pub const PACKED_POSITION_SIZE: usize = 32;
+
+/// `PackedSfenValue` の1件のバイト数
+pub const PACKED_SFEN_VALUE_SIZE: usize = 40;
+
+/// `Hcpe` の1件のバイト数
+pub const HCPE_SIZE: usize = 38;
+
+/// 玉がいないことを表すマスの番号
+const NO_SQUARE: u8 = 81;
+
+/// 駒を打つ手のマスを表す値の先頭（HuffmanCodedPosの指し手）
+const DROP_BASE: u16 = 81;
+
+/// 符号化する駒の種類（やねうら王とAperyで共通の順番）
+const RAW_PIECE_TYPES: [PieceType; 7] = [
+    PieceType::Pawn,
+    PieceType::Lance,
+    PieceType::Knight,
+    PieceType::Silver,
+    PieceType::Bichop,
+    PieceType::Rook,
+    PieceType::Gold,
+];
+
+/// ハフマン符号化した局面の形式
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PackedFormat {
+    /// やねうら王のPackedSfen
+    PackedSfen,
+    /// Apery・dlshogiのHuffmanCodedPos
+    HuffmanCodedPos,
+}
+
+impl PackedFormat {
+    /// 盤上の駒の符号（駒があることを表す1ビットを含む）と長さ
+    ///
+    /// 下位ビットから順に書く。手駒は先頭の1ビットを除いた符号を使う。
+    fn code(&self, piece_type: PieceType) -> (u32, u32) {
+        match (self, piece_type) {
+            (_, PieceType::Pawn) => (0b1, 2),
+            (_, PieceType::Lance) => (0b11, 4),
+            (PackedFormat::PackedSfen, PieceType::Knight) => (0b1011, 4),
+            (PackedFormat::PackedSfen, PieceType::Silver) => (0b111, 4),
+            (PackedFormat::HuffmanCodedPos, PieceType::Knight) => (0b111, 4),
+            (PackedFormat::HuffmanCodedPos, PieceType::Silver) => (0b1011, 4),
+            (_, PieceType::Bichop) => (0b11111, 6),
+            (_, PieceType::Rook) => (0b111111, 6),
+            (_, PieceType::Gold) => (0b1111, 5),
+            _ => (0, 1),
+        }
+    }
+
+    /// 持ち駒を書く順番
+    fn hand_order(&self) -> [PieceType; 7] {
+        match self {
+            PackedFormat::PackedSfen => RAW_PIECE_TYPES,
+            PackedFormat::HuffmanCodedPos => [
+                PieceType::Pawn,
+                PieceType::Lance,
+                PieceType::Knight,
+                PieceType::Silver,
+                PieceType::Gold,
+                PieceType::Bichop,
+                PieceType::Rook,
+            ],
+        }
+    }
+
+    /// 盤上の駒の成りと手番のビットを書く順番が手番を先にするか
+    fn color_first(&self) -> bool {
+        *self == PackedFormat::HuffmanCodedPos
+    }
+}
+
+/// マスのインデックスとやねうら王・Aperyのマスの番号（1一が0、筋ごとに並ぶ）の変換
+fn index_to_packed_square(index: u8) -> u8 {
+    let file = 10 - Address::from_number(index).get_column();
+    let rank = 10 - Address::from_number(index).get_row();
+    (file - 1) * 9 + rank - 1
+}
+
+fn packed_square_to_index(square: u8) -> u8 {
+    let file = square / 9 + 1;
+    let rank = square % 9 + 1;
+    Address::from_numbers(10 - file, 10 - rank).to_index()
+}
+
+struct BitWriter {
+    data: [u8; PACKED_POSITION_SIZE],
+    cursor: usize,
+}
+
+impl BitWriter {
+    fn write(&mut self, value: u32, bits: u32) -> Result<(), String> {
+        for i in 0..bits {
+            if self.cursor >= PACKED_POSITION_SIZE * 8 {
+                return Err("position does not fit in 256 bits".to_string());
+            }
+            if (value >> i) & 1 != 0 {
+                self.data[self.cursor / 8] |= 1 << (self.cursor % 8);
+            }
+            self.cursor += 1;
+        }
+        Ok(())
+    }
+}
+
+struct BitReader<'a> {
+    data: &'a [u8],
+    cursor: usize,
+}
+
+impl BitReader<'_> {
+    fn read_bit(&mut self) -> Result<u32, String> {
+        if self.cursor >= PACKED_POSITION_SIZE * 8 {
+            return Err("unexpected end of packed position".to_string());
+        }
+        let bit = (self.data[self.cursor / 8] >> (self.cursor % 8)) & 1;
+        self.cursor += 1;
+        Ok(bit as u32)
+    }
+
+    fn read(&mut self, bits: u32) -> Result<u32, String> {
+        let mut value = 0;
+        for i in 0..bits {
+            value |= self.read_bit()? << i;
+        }
+        Ok(value)
+    }
+
+    fn is_end(&self) -> bool {
+        self.cursor >= PACKED_POSITION_SIZE * 8
+    }
+
+    /// 駒の種類の符号を読む（`skip_first` なら先頭の1ビットを除いた手駒の符号）
+    fn read_piece_type(
+        &mut self,
+        format: PackedFormat,
+        skip_first: bool,
+    ) -> Result<PieceType, String> {
+        let (mut code, mut bits) = (0, 0);
+        loop {
+            code |= self.read_bit()? << bits;
+            bits += 1;
+            if !skip_first && code == 0 && bits == 1 {
+                return Ok(PieceType::None);
+            }
+            let found = RAW_PIECE_TYPES.iter().find(|piece_type| {
+                let (c, b) = format.code(**piece_type);
+                if skip_first {
+                    (c >> 1, b - 1) == (code, bits)
+                } else {
+                    (c, b) == (code, bits)
+                }
+            });
+            if let Some(piece_type) = found {
+                return Ok(*piece_type);
+            }
+            if bits >= 6 {
+                return Err("invalid huffman code in packed position".to_string());
+            }
+        }
+    }
+}
+
+fn color_from_bit(bit: u32) -> ColorType {
+    if bit == 0 {
+        ColorType::Black
+    } else {
+        ColorType::White
+    }
+}
+
+/// `turn` 側の手番の `board` を32バイトに符号化する
+///
+/// 本将棋の盤で、両者の玉を含む40枚の駒がそろっている局面だけを符号化できる。
+pub fn pack_position(
+    board: &Board,
+    turn: ColorType,
+    format: PackedFormat,
+) -> Result<[u8; PACKED_POSITION_SIZE], String> {
+    if board.variant != Variant::Standard {
+        return Err("packed positions support only the standard variant".to_string());
+    }
+    let mut writer = BitWriter {
+        data: [0; PACKED_POSITION_SIZE],
+        cursor: 0,
+    };
+    writer.write((turn == ColorType::White) as u32, 1)?;
+    for color in [ColorType::Black, ColorType::White] {
+        let square = board
+            .get_king_index(color)
+            .map(index_to_packed_square)
+            .unwrap_or(NO_SQUARE);
+        writer.write(square as u32, 7)?;
+    }
+
+    for square in 0..81 {
+        let piece = board.get_piece(packed_square_to_index(square));
+        if piece.piece_type == PieceType::King {
+            continue;
+        }
+        let raw = piece.piece_type.demote();
+        let (code, bits) = format.code(raw);
+        writer.write(code, bits)?;
+        if raw == PieceType::None {
+            continue;
+        }
+        let color = (piece.owner == ColorType::White) as u32;
+        let promoted = (piece.piece_type != raw) as u32;
+        if raw == PieceType::Gold {
+            writer.write(color, 1)?;
+        } else if format.color_first() {
+            writer.write(color, 1)?;
+            writer.write(promoted, 1)?;
+        } else {
+            writer.write(promoted, 1)?;
+            writer.write(color, 1)?;
+        }
+    }
+
+    for color in [ColorType::Black, ColorType::White] {
+        for piece_type in format.hand_order() {
+            let (code, bits) = format.code(piece_type);
+            for _ in 0..board.hand.get_count(color, piece_type) {
+                writer.write(code >> 1, bits - 1)?;
+                if piece_type != PieceType::Gold {
+                    writer.write(0, 1)?;
+                }
+                writer.write((color == ColorType::White) as u32, 1)?;
+            }
+        }
+    }
+
+    if writer.cursor != PACKED_POSITION_SIZE * 8 {
+        return Err("packed positions need all 40 pieces".to_string());
+    }
+    Ok(writer.data)
+}
+
+/// 32バイトの局面を盤面と手番に戻す
+pub fn unpack_position(data: &[u8], format: PackedFormat) -> Result<(Board, ColorType), String> {
+    if data.len() != PACKED_POSITION_SIZE {
+        return Err(format!(
+            "packed position must be {} bytes, got {}",
+            PACKED_POSITION_SIZE,
+            data.len()
+        ));
+    }
+    let mut reader = BitReader { data, cursor: 0 };
+    let mut board = Board::new();
+    let turn = color_from_bit(reader.read_bit()?);
+    let mut kings = [NO_SQUARE; 2];
+    for king in kings.iter_mut() {
+        *king = reader.read(7)? as u8;
+        if *king > NO_SQUARE {
+            return Err(format!("invalid king square: {}", king));
+        }
+    }
+    for (color, square) in [ColorType::Black, ColorType::White].into_iter().zip(kings) {
+        if square != NO_SQUARE {
+            board.deploy(packed_square_to_index(square), PieceType::King, color);
+        }
+    }
+
+    for square in 0..81 {
+        if kings.contains(&square) {
+            continue;
+        }
+        let raw = reader.read_piece_type(format, false)?;
+        if raw == PieceType::None {
+            continue;
+        }
+        let (color, promoted) = if raw == PieceType::Gold {
+            (reader.read_bit()?, 0)
+        } else if format.color_first() {
+            let color = reader.read_bit()?;
+            (color, reader.read_bit()?)
+        } else {
+            let promoted = reader.read_bit()?;
+            (reader.read_bit()?, promoted)
+        };
+        let piece_type = if promoted != 0 { raw.promote() } else { raw };
+        board.deploy(
+            packed_square_to_index(square),
+            piece_type,
+            color_from_bit(color),
+        );
+    }
+
+    while !reader.is_end() {
+        let piece_type = reader.read_piece_type(format, true)?;
+        if piece_type != PieceType::Gold && reader.read_bit()? != 0 {
+            return Err("hand pieces cannot be promoted".to_string());
+        }
+        board
+            .hand
+            .add_piece(color_from_bit(reader.read_bit()?), piece_type);
+    }
+    Ok((board, turn))
+}
+
+/// 指し手を16ビットの形式にする（パスは0）
+///
+/// やねうら王の形式は打つ手を14ビット目、成りを15ビット目で表し、
+/// HuffmanCodedPosの形式は成りを14ビット目で表して打つ手の移動元を81以降の値にする。
+pub fn move_to_move16(mv: &Move, format: PackedFormat) -> u16 {
+    if mv.get_is_pass() {
+        return 0;
+    }
+    let to = index_to_packed_square(mv.get_to().to_index()) as u16;
+    if mv.get_is_drop() {
+        let piece_type = mv.get_piece().piece_type;
+        let code = RAW_PIECE_TYPES
+            .iter()
+            .position(|raw| *raw == piece_type)
+            .unwrap_or(0) as u16
+            + 1;
+        return match format {
+            PackedFormat::PackedSfen => to | code << 7 | 1 << 14,
+            PackedFormat::HuffmanCodedPos => to | (DROP_BASE + code - 1) << 7,
+        };
+    }
+    let from = index_to_packed_square(mv.get_from().to_index()) as u16;
+    let promote = match format {
+        PackedFormat::PackedSfen => 1 << 15,
+        PackedFormat::HuffmanCodedPos => 1 << 14,
+    };
+    to | from << 7 | if mv.get_is_promote() { promote } else { 0 }
+}
+
+/// 16ビットの指し手を `turn` 側の手に戻す（0は `None`）
+pub fn move16_to_move(
+    value: u16,
+    turn: ColorType,
+    format: PackedFormat,
+) -> Result<Option<Move>, String> {
+    if value == 0 {
+        return Ok(None);
+    }
+    let to = (value & 0x7F) as u8;
+    let from = (value >> 7) & 0x7F;
+    let (drop_code, promote) = match format {
+        PackedFormat::PackedSfen => (
+            (value & (1 << 14) != 0).then_some(from),
+            value & (1 << 15) != 0,
+        ),
+        PackedFormat::HuffmanCodedPos => (
+            (from >= DROP_BASE).then(|| from - DROP_BASE + 1),
+            value & (1 << 14) != 0,
+        ),
+    };
+    if to >= NO_SQUARE {
+        return Err(format!("invalid move16: {}", value));
+    }
+    let to = Address::from_number(packed_square_to_index(to));
+    match drop_code {
+        Some(code) => {
+            let piece_type = *RAW_PIECE_TYPES
+                .get((code as usize).wrapping_sub(1))
+                .ok_or_else(|| format!("invalid move16: {}", value))?;
+            Ok(Some(Move::from_drop(Piece::from(turn, piece_type), to)))
+        }
+        None if from < NO_SQUARE as u16 => Ok(Some(Move::from_standart(
+            Address::from_number(packed_square_to_index(from as u8)),
+            to,
+            promote,
+        ))),
+        None => Err(format!("invalid move16: {}", value)),
+    }
+}
+
+/// 固定長のバイナリで読み書きする学習データの1件
+pub trait TrainingRecord: Sized {
+    const SIZE: usize;
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, String>;
+
+    fn to_bytes(&self) -> Vec<u8>;
+}
+
+/// ファイルなどから学習データを1件ずつ読む
+pub struct RecordReader<R: Read, T: TrainingRecord> {
+    reader: R,
+    buffer: Vec<u8>,
+    record: PhantomData<T>,
+}
+
+impl<R: Read, T: TrainingRecord> RecordReader<R, T> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buffer: vec![0; T::SIZE],
+            record: PhantomData,
+        }
+    }
+}
+
+impl<R: Read, T: TrainingRecord> Iterator for RecordReader<R, T> {
+    type Item = Result<T, String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut filled = 0;
+        while filled < T::SIZE {
+            match self.reader.read(&mut self.buffer[filled..]) {
+                Ok(0) => break,
+                Ok(n) => filled += n,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(e) => return Some(Err(e.to_string())),
+            }
+        }
+        match filled {
+            0 => None,
+            n if n < T::SIZE => Some(Err(format!("truncated record: {} of {} bytes", n, T::SIZE))),
+            _ => Some(T::from_bytes(&self.buffer)),
+        }
+    }
+}
+
+/// `records` を続けて書き出す
+pub fn write_records<'a, W: Write, T: TrainingRecord + 'a>(
+    writer: &mut W,
+    records: impl IntoIterator<Item = &'a T>,
+) -> Result<(), String> {
+    for record in records {
+        writer
+            .write_all(&record.to_bytes())
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// `path` の `offset` 件目から最大 `count` 件を読む（大きなファイルを分けて読むのに使う）
+pub fn read_record_file<T: TrainingRecord>(
+    path: &str,
+    offset: usize,
+    count: Option<usize>,
+) -> Result<Vec<T>, String> {
+    let mut file = File::open(path).map_err(|e| format!("{}: {}", path, e))?;
+    file.seek(SeekFrom::Start((offset * T::SIZE) as u64))
+        .map_err(|e| e.to_string())?;
+    RecordReader::<_, T>::new(BufReader::new(file))
+        .take(count.unwrap_or(usize::MAX))
+        .collect()
+}
+
+/// `path` に書き出す（`append` なら末尾に追加する）
+pub fn write_record_file<T: TrainingRecord>(
+    path: &str,
+    records: &[T],
+    append: bool,
+) -> Result<(), String> {
+    let file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(append)
+        .truncate(!append)
+        .open(path)
+        .map_err(|e| format!("{}: {}", path, e))?;
+    let mut writer = BufWriter::new(file);
+    write_records(&mut writer, records)?;
+    writer.flush().map_err(|e| e.to_string())
+}
+
+fn packed_array(bytes: &[u8]) -> Result<[u8; PACKED_POSITION_SIZE], String> {
+    bytes
+        .try_into()
+        .map_err(|_| format!("packed position must be {} bytes", PACKED_POSITION_SIZE))
+}
+
+/// やねうら王の学習データの1件（PackedSfenと評価値、指し手、手数、結果）
+#[pyclass]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PackedSfenValue {
+    pub sfen: [u8; PACKED_POSITION_SIZE],
+    /// 手番側から見た評価値
+    #[pyo3(get, set)]
+    pub score: i16,
+    /// やねうら王の16ビットの指し手
+    #[pyo3(get, set)]
+    pub move16: u16,
+    #[pyo3(get, set)]
+    pub game_ply: u16,
+    /// 手番側から見た対局の結果（勝ち1、負け-1、引き分け0）
+    #[pyo3(get, set)]
+    pub game_result: i8,
+}
+
+impl PackedSfenValue {
+    pub fn from_board(
+        board: &Board,
+        turn: ColorType,
+        score: i16,
+        mv: Option<&Move>,
+        game_ply: u16,
+        game_result: i8,
+    ) -> Result<Self, String> {
+        Ok(Self {
+            sfen: pack_position(board, turn, PackedFormat::PackedSfen)?,
+            score,
+            move16: mv.map_or(0, |mv| move_to_move16(mv, PackedFormat::PackedSfen)),
+            game_ply,
+            game_result,
+        })
+    }
+
+    pub fn board(&self) -> Result<(Board, ColorType), String> {
+        unpack_position(&self.sfen, PackedFormat::PackedSfen)
+    }
+
+    /// 指し手（手番は局面から決める）
+    pub fn get_move(&self) -> Result<Option<Move>, String> {
+        let turn = color_from_bit((self.sfen[0] & 1) as u32);
+        move16_to_move(self.move16, turn, PackedFormat::PackedSfen)
+    }
+}
+
+impl TrainingRecord for PackedSfenValue {
+    const SIZE: usize = PACKED_SFEN_VALUE_SIZE;
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.len() != Self::SIZE {
+            return Err(format!(
+                "PackedSfenValue must be {} bytes, got {}",
+                Self::SIZE,
+                bytes.len()
+            ));
+        }
+        Ok(Self {
+            sfen: packed_array(&bytes[..32])?,
+            score: i16::from_le_bytes([bytes[32], bytes[33]]),
+            move16: u16::from_le_bytes([bytes[34], bytes[35]]),
+            game_ply: u16::from_le_bytes([bytes[36], bytes[37]]),
+            game_result: bytes[38] as i8,
+        })
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(Self::SIZE);
+        bytes.extend_from_slice(&self.sfen);
+        bytes.extend_from_slice(&self.score.to_le_bytes());
+        bytes.extend_from_slice(&self.move16.to_le_bytes());
+        bytes.extend_from_slice(&self.game_ply.to_le_bytes());
+        bytes.push(self.game_result as u8);
+        bytes.push(0);
+        bytes
+    }
+}
+
+/// dlshogiの学習データの1件（HuffmanCodedPosと評価値、最善手、結果）
+#[pyclass]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Hcpe {
+    pub hcp: [u8; PACKED_POSITION_SIZE],
+    /// 手番側から見た評価値
+    #[pyo3(get, set)]
+    pub eval: i16,
+    /// Aperyの16ビットの指し手
+    #[pyo3(get, set)]
+    pub best_move16: u16,
+    /// 対局の結果（引き分け0、先手勝ち1、後手勝ち2）
+    #[pyo3(get, set)]
+    pub game_result: u8,
+}
+
+impl Hcpe {
+    pub fn from_board(
+        board: &Board,
+        turn: ColorType,
+        eval: i16,
+        best_move: Option<&Move>,
+        winner: ColorType,
+    ) -> Result<Self, String> {
+        Ok(Self {
+            hcp: pack_position(board, turn, PackedFormat::HuffmanCodedPos)?,
+            eval,
+            best_move16: best_move
+                .map_or(0, |mv| move_to_move16(mv, PackedFormat::HuffmanCodedPos)),
+            game_result: match winner {
+                ColorType::Black => 1,
+                ColorType::White => 2,
+                _ => 0,
+            },
+        })
+    }
+
+    pub fn board(&self) -> Result<(Board, ColorType), String> {
+        unpack_position(&self.hcp, PackedFormat::HuffmanCodedPos)
+    }
+
+    pub fn get_best_move(&self) -> Result<Option<Move>, String> {
+        let turn = color_from_bit((self.hcp[0] & 1) as u32);
+        move16_to_move(self.best_move16, turn, PackedFormat::HuffmanCodedPos)
+    }
+
+    /// 勝った側（引き分けなら `ColorType::None`）
+    pub fn winner(&self) -> ColorType {
+        match self.game_result {
+            1 => ColorType::Black,
+            2 => ColorType::White,
+            _ => ColorType::None,
+        }
+    }
+}
+
+impl TrainingRecord for Hcpe {
+    const SIZE: usize = HCPE_SIZE;
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.len() != Self::SIZE {
+            return Err(format!(
+                "Hcpe must be {} bytes, got {}",
+                Self::SIZE,
+                bytes.len()
+            ));
+        }
+        Ok(Self {
+            hcp: packed_array(&bytes[..32])?,
+            eval: i16::from_le_bytes([bytes[32], bytes[33]]),
+            best_move16: u16::from_le_bytes([bytes[34], bytes[35]]),
+            game_result: bytes[36],
+        })
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(Self::SIZE);
+        bytes.extend_from_slice(&self.hcp);
+        bytes.extend_from_slice(&self.eval.to_le_bytes());
+        bytes.extend_from_slice(&self.best_move16.to_le_bytes());
+        bytes.push(self.game_result);
+        bytes.push(0);
+        bytes
+    }
+}
+
+#[pymethods]
+impl PackedSfenValue {
+    #[new]
+    #[pyo3(signature = (board, turn, score = 0, mv = None, game_ply = 1, game_result = 0))]
+    pub fn new_for_python(
+        board: &Board,
+        turn: ColorType,
+        score: i16,
+        mv: Option<Move>,
+        game_ply: u16,
+        game_result: i8,
+    ) -> PyResult<Self> {
+        Self::from_board(board, turn, score, mv.as_ref(), game_ply, game_result)
+            .map_err(pyo3::exceptions::PyValueError::new_err)
+    }
+
+    #[getter]
+    #[pyo3(name = "sfen")]
+    pub fn python_sfen<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &self.sfen)
+    }
+
+    #[pyo3(name = "board")]
+    pub fn python_board(&self) -> PyResult<(Board, ColorType)> {
+        self.board()
+            .map_err(pyo3::exceptions::PyValueError::new_err)
+    }
+
+    #[pyo3(name = "move")]
+    pub fn python_move(&self) -> PyResult<Option<Move>> {
+        self.get_move()
+            .map_err(pyo3::exceptions::PyValueError::new_err)
+    }
+
+    #[pyo3(name = "to_bytes")]
+    pub fn python_to_bytes<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &self.to_bytes())
+    }
+
+    #[staticmethod]
+    #[pyo3(name = "from_bytes")]
+    pub fn python_from_bytes(data: &[u8]) -> PyResult<Self> {
+        Self::from_bytes(data).map_err(pyo3::exceptions::PyValueError::new_err)
+    }
+
+    /// `path` の `offset` 件目から最大 `count` 件を読む
+    #[staticmethod]
+    #[pyo3(name = "read_file")]
+    #[pyo3(signature = (path, offset = 0, count = None))]
+    pub fn python_read_file(
+        py: Python<'_>,
+        path: String,
+        offset: usize,
+        count: Option<usize>,
+    ) -> PyResult<Vec<Self>> {
+        py.allow_threads(|| read_record_file(&path, offset, count))
+            .map_err(pyo3::exceptions::PyValueError::new_err)
+    }
+
+    #[staticmethod]
+    #[pyo3(name = "write_file")]
+    #[pyo3(signature = (path, records, append = false))]
+    pub fn python_write_file(
+        py: Python<'_>,
+        path: String,
+        records: Vec<Self>,
+        append: bool,
+    ) -> PyResult<()> {
+        py.allow_threads(|| write_record_file(&path, &records, append))
+            .map_err(pyo3::exceptions::PyValueError::new_err)
+    }
+
+    pub fn __eq__(&self, other: &Self) -> bool {
+        self == other
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!(
+            "PackedSfenValue(score={}, move16={}, game_ply={}, game_result={})",
+            self.score, self.move16, self.game_ply, self.game_result
+        )
+    }
+}
+
+#[pymethods]
+impl Hcpe {
+    #[new]
+    #[pyo3(signature = (board, turn, eval = 0, best_move = None, winner = None))]
+    pub fn new_for_python(
+        board: &Board,
+        turn: ColorType,
+        eval: i16,
+        best_move: Option<Move>,
+        winner: Option<ColorType>,
+    ) -> PyResult<Self> {
+        let winner = winner.unwrap_or(ColorType::None);
+        Self::from_board(board, turn, eval, best_move.as_ref(), winner)
+            .map_err(pyo3::exceptions::PyValueError::new_err)
+    }
+
+    #[getter]
+    #[pyo3(name = "hcp")]
+    pub fn python_hcp<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &self.hcp)
+    }
+
+    #[pyo3(name = "board")]
+    pub fn python_board(&self) -> PyResult<(Board, ColorType)> {
+        self.board()
+            .map_err(pyo3::exceptions::PyValueError::new_err)
+    }
+
+    #[pyo3(name = "best_move")]
+    pub fn python_best_move(&self) -> PyResult<Option<Move>> {
+        self.get_best_move()
+            .map_err(pyo3::exceptions::PyValueError::new_err)
+    }
+
+    #[pyo3(name = "winner")]
+    pub fn python_winner(&self) -> Option<ColorType> {
+        Some(self.winner()).filter(|winner| *winner != ColorType::None)
+    }
+
+    #[pyo3(name = "to_bytes")]
+    pub fn python_to_bytes<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &self.to_bytes())
+    }
+
+    #[staticmethod]
+    #[pyo3(name = "from_bytes")]
+    pub fn python_from_bytes(data: &[u8]) -> PyResult<Self> {
+        Self::from_bytes(data).map_err(pyo3::exceptions::PyValueError::new_err)
+    }
+
+    /// `path` の `offset` 件目から最大 `count` 件を読む
+    #[staticmethod]
+    #[pyo3(name = "read_file")]
+    #[pyo3(signature = (path, offset = 0, count = None))]
+    pub fn python_read_file(
+        py: Python<'_>,
+        path: String,
+        offset: usize,
+        count: Option<usize>,
+    ) -> PyResult<Vec<Self>> {
+        py.allow_threads(|| read_record_file(&path, offset, count))
+            .map_err(pyo3::exceptions::PyValueError::new_err)
+    }
+
+    #[staticmethod]
+    #[pyo3(name = "write_file")]
+    #[pyo3(signature = (path, records, append = false))]
+    pub fn python_write_file(
+        py: Python<'_>,
+        path: String,
+        records: Vec<Self>,
+        append: bool,
+    ) -> PyResult<()> {
+        py.allow_threads(|| write_record_file(&path, &records, append))
+            .map_err(pyo3::exceptions::PyValueError::new_err)
+    }
+
+    pub fn __eq__(&self, other: &Self) -> bool {
+        self == other
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!(
+            "Hcpe(eval={}, best_move16={}, game_result={})",
+            self.eval, self.best_move16, self.game_result
+        )
+    }
+}
@@ -1,35 +1,121 @@
-use nalgebra::DMatrix;
-use ndarray::{Array1, Array2, Axis};
+use super::board::{Board, BoardSource, VECTOR_DIMENSIONS};
+use super::feature::FeatureExtractor;
+
+use nalgebra::{DMatrix, DVector};
+use ndarray::{Array1, Array2, ArrayView2, Axis};
+use numpy::{IntoPyArray, PyArray1, PyArray2};
 use once_cell::sync::Lazy;
-use std::sync::Mutex;
+use pyo3::prelude::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rand_distr::StandardNormal;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::sync::{Arc, Mutex};
+
+/// `PCATransform::save` のファイルの先頭に書くマジックナンバー
+const PCA_FILE_MAGIC: &[u8; 4] = b"RSPC";
+/// `PCATransform::save` のファイル形式のバージョン
+const PCA_FILE_VERSION: u32 = 1;
+
+/// `PCATransform::fit` で主成分を求める方法
+#[pyclass(eq, eq_int)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum PCASolver {
+    /// 中心化したデータ行列を特異値分解する（`learn_pca`）
+    Full = 0,
+    /// ランダム化SVD（`learn_randomized_pca`）
+    Randomized,
+    /// ミニバッチごとに更新する（`IncrementalPCA`）
+    Incremental,
+}
 
 /// PCA変換行列を保存するための構造体（ndarray使用）
+#[pyclass]
 #[derive(Clone, Debug, PartialEq)]
 pub struct PCATransform {
-    pub components: Array2<f32>, // 主成分行列
-    pub mean: Array1<f32>,       // 平均ベクトル
-    pub n_components: usize,     // 主成分数
+    pub components: Array2<f32>,         // 主成分行列
+    pub mean: Array1<f32>,               // 平均ベクトル
+    pub explained_variance: Array1<f32>, // 各主成分の分散
+    pub total_variance: f32,             // 学習データの分散の合計
+    #[pyo3(get)]
+    pub n_components: usize, // 主成分数
 }
 
 impl PCATransform {
     /// 新しいPCA変換を作成
     pub fn new(components: Array2<f32>, mean: Array1<f32>, n_components: usize) -> Self {
+        Self::with_variance(
+            components,
+            mean,
+            n_components,
+            Array1::zeros(n_components),
+            0.0,
+        )
+    }
+
+    /// 各主成分の分散と学習データの分散の合計を持つPCA変換を作成
+    pub fn with_variance(
+        components: Array2<f32>,
+        mean: Array1<f32>,
+        n_components: usize,
+        explained_variance: Array1<f32>,
+        total_variance: f32,
+    ) -> Self {
         Self {
             components,
             mean,
             n_components,
+            explained_variance,
+            total_variance,
         }
     }
 
-    /// 特徴量を変換
-    pub fn transform(&self, features: &[f32]) -> Vec<f32> {
-        if features.len() != self.mean.len() {
-            panic!(
+    /// 学習前の変換（`fit` するまで `transform` はエラーになる）
+    pub fn unfitted(n_components: usize) -> Self {
+        Self::new(
+            Array2::zeros((n_components, 0)),
+            Array1::zeros(0),
+            n_components,
+        )
+    }
+
+    /// 学習済みか
+    pub fn is_fitted(&self) -> bool {
+        !self.mean.is_empty()
+    }
+
+    /// 入力の特徴量の次元数
+    pub fn n_features(&self) -> usize {
+        self.mean.len()
+    }
+
+    /// 各主成分の分散が学習データの分散の合計に占める割合
+    pub fn explained_variance_ratio(&self) -> Array1<f32> {
+        if self.total_variance > 0.0 {
+            &self.explained_variance / self.total_variance
+        } else {
+            Array1::zeros(self.explained_variance.len())
+        }
+    }
+
+    fn check_dimensions(&self, n_features: usize) -> Result<(), String> {
+        if !self.is_fitted() {
+            return Err("PCA transform is not fitted".to_string());
+        }
+        if n_features != self.n_features() {
+            return Err(format!(
                 "Feature dimension mismatch: expected {}, got {}",
-                self.mean.len(),
-                features.len()
-            );
+                self.n_features(),
+                n_features
+            ));
         }
+        Ok(())
+    }
+
+    /// 特徴量を変換
+    pub fn transform(&self, features: &[f32]) -> Result<Vec<f32>, String> {
+        self.check_dimensions(features.len())?;
 
         // 特徴量をndarrayに変換
         let data = Array1::from_vec(features.to_vec());
@@ -40,22 +126,338 @@ impl PCATransform {
         // 主成分を適用
         let transformed = self.components.dot(&centered);
 
-        transformed.to_vec()
+        Ok(transformed.to_vec())
+    }
+
+    /// 1行1サンプルに並べた特徴量をまとめて変換し、1行 `n_components` 次元に並べて返す
+    pub fn transform_batch(&self, features: &[f32]) -> Result<Vec<f32>, String> {
+        if !self.is_fitted() {
+            return Err("PCA transform is not fitted".to_string());
+        }
+        if !features.len().is_multiple_of(self.n_features()) {
+            return Err(format!(
+                "Feature length {} is not a multiple of {}",
+                features.len(),
+                self.n_features()
+            ));
+        }
+        let rows = features.len() / self.n_features();
+        let data = ArrayView2::from_shape((rows, self.n_features()), features)
+            .map_err(|e| e.to_string())?;
+        let centered = &data - &self.mean;
+        let transformed = centered.dot(&self.components.t());
+        Ok(transformed.into_iter().collect())
+    }
+
+    /// 盤面かSFENの特徴量（`Board::to_vector(None)`）から学習する
+    ///
+    /// `Full` 以外は `batch_size` 局面ずつ特徴量を作るので、全局面の特徴量を一度に持たない。
+    pub fn fit(
+        sources: &[BoardSource],
+        n_components: usize,
+        solver: PCASolver,
+        batch_size: usize,
+        num_threads: usize,
+    ) -> Result<Self, String> {
+        FeatureExtractor::default().learn_transform(
+            sources,
+            n_components,
+            solver,
+            batch_size,
+            num_threads,
+        )
+    }
+
+    /// `batches` が返す1行 `n_features` 次元の特徴量のバッチから学習する
+    ///
+    /// `Randomized` は `batches` を何度か呼んでデータを読み直す。
+    pub fn fit_batches<F, I>(
+        n_features: usize,
+        n_components: usize,
+        solver: PCASolver,
+        mut batches: F,
+    ) -> Result<Self, String>
+    where
+        F: FnMut() -> I,
+        I: Iterator<Item = Result<Vec<f32>, String>>,
+    {
+        match solver {
+            PCASolver::Full => {
+                let mut samples: Vec<Vec<f32>> = Vec::new();
+                for batch in batches() {
+                    let batch = batch?;
+                    if !batch.len().is_multiple_of(n_features) {
+                        return Err(format!(
+                            "Feature length {} is not a multiple of {}",
+                            batch.len(),
+                            n_features
+                        ));
+                    }
+                    samples.extend(batch.chunks(n_features).map(|row| row.to_vec()));
+                }
+                learn_pca(&samples, n_components)
+            }
+            PCASolver::Randomized => learn_randomized_pca(
+                n_features,
+                n_components,
+                &RandomizedPCAConfig::default(),
+                batches,
+            ),
+            PCASolver::Incremental => {
+                let mut pca = IncrementalPCA::new(n_features, n_components)?;
+                for batch in batches() {
+                    pca.partial_fit(&batch?)?;
+                }
+                pca.to_transform()
+            }
+        }
+    }
+
+    /// リトルエンディアンのバイナリで書き出す
+    ///
+    /// マジックナンバー、バージョン（u32）、主成分数（u64）、特徴量の次元数（u64）、
+    /// 分散の合計（f32）、平均、各主成分の分散、主成分行列（行優先）の順に並べる。
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        if !self.is_fitted() {
+            return Err("PCA transform is not fitted".to_string());
+        }
+        let file = File::create(path).map_err(|e| format!("{}: {}", path, e))?;
+        let mut writer = BufWriter::new(file);
+        let mut data = Vec::new();
+        data.extend_from_slice(PCA_FILE_MAGIC);
+        data.extend_from_slice(&PCA_FILE_VERSION.to_le_bytes());
+        data.extend_from_slice(&(self.n_components as u64).to_le_bytes());
+        data.extend_from_slice(&(self.n_features() as u64).to_le_bytes());
+        data.extend_from_slice(&self.total_variance.to_le_bytes());
+        for value in self
+            .mean
+            .iter()
+            .chain(self.explained_variance.iter())
+            .chain(self.components.iter())
+        {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        writer
+            .write_all(&data)
+            .and_then(|_| writer.flush())
+            .map_err(|e| format!("{}: {}", path, e))
+    }
+
+    /// `save` で書き出したファイルを読む
+    pub fn load(path: &str) -> Result<Self, String> {
+        let file = File::open(path).map_err(|e| format!("{}: {}", path, e))?;
+        let mut data = Vec::new();
+        BufReader::new(file)
+            .read_to_end(&mut data)
+            .map_err(|e| format!("{}: {}", path, e))?;
+        Self::from_bytes(&data).map_err(|e| format!("{}: {}", path, e))
+    }
+
+    fn from_bytes(data: &[u8]) -> Result<Self, String> {
+        const HEADER_SIZE: usize = 28;
+        if data.len() < HEADER_SIZE || &data[..4] != PCA_FILE_MAGIC {
+            return Err("not a PCA transform file".to_string());
+        }
+        let version = u32::from_le_bytes(data[4..8].try_into().unwrap());
+        if version != PCA_FILE_VERSION {
+            return Err(format!("unsupported PCA file version {}", version));
+        }
+        let n_components = u64::from_le_bytes(data[8..16].try_into().unwrap()) as usize;
+        let n_features = u64::from_le_bytes(data[16..24].try_into().unwrap()) as usize;
+        let total_variance = f32::from_le_bytes(data[24..28].try_into().unwrap());
+
+        let expected = n_features
+            .checked_add(n_components)
+            .and_then(|n| n_components.checked_mul(n_features)?.checked_add(n))
+            .and_then(|n| n.checked_mul(4)?.checked_add(HEADER_SIZE));
+        if n_features == 0 || expected != Some(data.len()) {
+            return Err(format!(
+                "PCA file size {} does not match {} components of {} features",
+                data.len(),
+                n_components,
+                n_features
+            ));
+        }
+
+        let values: Vec<f32> = data[HEADER_SIZE..]
+            .chunks_exact(4)
+            .map(|bytes| f32::from_le_bytes(bytes.try_into().unwrap()))
+            .collect();
+        let (mean, rest) = values.split_at(n_features);
+        let (explained_variance, components) = rest.split_at(n_components);
+        let components = Array2::from_shape_vec((n_components, n_features), components.to_vec())
+            .map_err(|e| e.to_string())?;
+        Ok(Self::with_variance(
+            components,
+            Array1::from_vec(mean.to_vec()),
+            n_components,
+            Array1::from_vec(explained_variance.to_vec()),
+            total_variance,
+        ))
+    }
+}
+
+#[pymethods]
+impl PCATransform {
+    #[new]
+    #[pyo3(signature = (n_components))]
+    pub fn new_for_python(n_components: usize) -> PyResult<Self> {
+        if n_components == 0 {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "n_components must be positive",
+            ));
+        }
+        Ok(Self::unfitted(n_components))
+    }
+
+    /// 盤面かSFENのリストから学習する（学習中はGILを解放する）
+    #[pyo3(name = "fit")]
+    #[pyo3(signature = (boards, threads = None, solver = PCASolver::Full, batch_size = 1024))]
+    pub fn python_fit(
+        &mut self,
+        py: Python<'_>,
+        boards: Vec<BoardSource>,
+        threads: Option<usize>,
+        solver: PCASolver,
+        batch_size: usize,
+    ) -> PyResult<()> {
+        let num_threads = threads.unwrap_or_else(num_cpus::get);
+        let n_components = self.n_components;
+        *self = py
+            .allow_threads(|| Self::fit(&boards, n_components, solver, batch_size, num_threads))
+            .map_err(pyo3::exceptions::PyValueError::new_err)?;
+        Ok(())
+    }
+
+    /// 盤面かSFENのリストを `(局面数, n_components)` の配列に変換する
+    #[pyo3(name = "transform")]
+    #[pyo3(signature = (boards, threads = None))]
+    pub fn python_transform<'py>(
+        &self,
+        py: Python<'py>,
+        boards: Vec<BoardSource>,
+        threads: Option<usize>,
+    ) -> PyResult<Bound<'py, PyArray2<f32>>> {
+        let num_threads = threads.unwrap_or_else(num_cpus::get);
+        let data = py
+            .allow_threads(|| {
+                let features = Board::to_vector_batch(&boards, None, num_threads)?;
+                self.transform_batch(&features)
+            })
+            .map_err(pyo3::exceptions::PyValueError::new_err)?;
+        let array = Array2::from_shape_vec((boards.len(), self.n_components), data)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        Ok(array.into_pyarray(py))
+    }
+
+    /// `Board.to_vector()` の特徴量を1つ変換する
+    #[pyo3(name = "transform_vector")]
+    pub fn python_transform_vector(&self, features: Vec<f32>) -> PyResult<Vec<f32>> {
+        self.transform(&features)
+            .map_err(pyo3::exceptions::PyValueError::new_err)
+    }
+
+    #[pyo3(name = "save")]
+    pub fn python_save(&self, path: &str) -> PyResult<()> {
+        self.save(path)
+            .map_err(pyo3::exceptions::PyValueError::new_err)
+    }
+
+    #[staticmethod]
+    #[pyo3(name = "load")]
+    pub fn python_load(path: &str) -> PyResult<Self> {
+        Self::load(path).map_err(pyo3::exceptions::PyValueError::new_err)
+    }
+
+    /// `Board.to_vector(n_components)` で使うグローバルな変換にする
+    #[pyo3(name = "set_global")]
+    pub fn python_set_global(&self) -> PyResult<()> {
+        if !self.is_fitted() {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "PCA transform is not fitted",
+            ));
+        }
+        set_global_pca_transform(self.clone());
+        Ok(())
+    }
+
+    #[getter]
+    #[pyo3(name = "is_fitted")]
+    pub fn python_is_fitted(&self) -> bool {
+        self.is_fitted()
+    }
+
+    #[getter]
+    #[pyo3(name = "n_features")]
+    pub fn python_n_features(&self) -> usize {
+        self.n_features()
+    }
+
+    /// `(n_components, n_features)` の主成分行列
+    #[getter]
+    #[pyo3(name = "components")]
+    pub fn python_components<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray2<f32>> {
+        self.components.clone().into_pyarray(py)
+    }
+
+    #[getter]
+    #[pyo3(name = "mean")]
+    pub fn python_mean<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray1<f32>> {
+        self.mean.clone().into_pyarray(py)
+    }
+
+    #[getter]
+    #[pyo3(name = "explained_variance")]
+    pub fn python_explained_variance<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray1<f32>> {
+        self.explained_variance.clone().into_pyarray(py)
+    }
+
+    #[getter]
+    #[pyo3(name = "explained_variance_ratio")]
+    pub fn python_explained_variance_ratio<'py>(
+        &self,
+        py: Python<'py>,
+    ) -> Bound<'py, PyArray1<f32>> {
+        self.explained_variance_ratio().into_pyarray(py)
+    }
+
+    pub fn __eq__(&self, other: &Self) -> bool {
+        self == other
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!(
+            "PCATransform(n_components={}, n_features={}, explained_variance_ratio={})",
+            self.n_components,
+            self.n_features(),
+            self.explained_variance_ratio().sum()
+        )
     }
 }
 
-// グローバルなPCA変換を保存
-static PCA_TRANSFORM: Lazy<Mutex<Option<PCATransform>>> = Lazy::new(|| Mutex::new(None));
+// グローバルなPCA変換を保存（取り出すときに行列を複製しないよう `Arc` に入れる）
+static PCA_TRANSFORM: Lazy<Mutex<Option<Arc<PCATransform>>>> = Lazy::new(|| Mutex::new(None));
+
+/// グローバルなPCA変換を書き換えるテストを直列にする
+#[cfg(test)]
+pub(crate) static GLOBAL_PCA_TEST_LOCK: Mutex<()> = Mutex::new(());
 
 /// グローバルなPCA変換を設定
+///
+/// `Board::to_vector(Some(n))` だけが使う。設定の異なる変換を同時に使うときは `FeatureExtractor` に持たせる。
 pub fn set_global_pca_transform(pca_transform: PCATransform) {
     if let Ok(mut transform) = PCA_TRANSFORM.lock() {
-        *transform = Some(pca_transform);
+        *transform = Some(Arc::new(pca_transform));
     }
 }
 
 /// グローバルなPCA変換を取得
 pub fn get_global_pca_transform() -> Option<PCATransform> {
+    global_pca_transform().as_deref().cloned()
+}
+
+/// グローバルなPCA変換を共有したまま取得
+pub(crate) fn global_pca_transform() -> Option<Arc<PCATransform>> {
     if let Ok(transform) = PCA_TRANSFORM.lock() {
         transform.clone()
     } else {
@@ -79,6 +481,13 @@ pub fn learn_pca(samples: &[Vec<f32>], n_components: usize) -> Result<PCATransfo
         ));
     }
 
+    if n_samples < 2 {
+        return Err("Cannot learn PCA from fewer than 2 samples".to_string());
+    }
+    if n_components == 0 {
+        return Err("Number of components must be positive".to_string());
+    }
+
     // データをndarrayに変換
     let mut data = Array2::zeros((n_samples, n_features));
     for (i, sample) in samples.iter().enumerate() {
@@ -104,38 +513,38 @@ pub fn learn_pca(samples: &[Vec<f32>], n_components: usize) -> Result<PCATransfo
         }
     }
 
-    // 共分散行列を計算: C = (X^T * X) / (n-1)
-    let covariance = nalgebra_data.transpose() * &nalgebra_data / (n_samples - 1) as f32;
-
-    // 固有値分解を実行（nalgeb raの対称固有値分解を使用）
-    let eigen = match covariance.symmetric_eigen() {
-        eigen => eigen,
-    };
+    let scale = (n_samples - 1) as f32;
 
-    // 固有値と固有ベクトルを取得
-    let eigenvalues = eigen.eigenvalues;
-    let eigenvectors = eigen.eigenvectors;
+    // 分散の合計は共分散行列の対角和
+    let total_variance = nalgebra_data.norm_squared() / scale;
 
-    // 固有値の大きい順にソート
-    let mut eigenval_vec: Vec<(usize, f32)> = Vec::new();
-    for i in 0..eigenvalues.len() {
-        eigenval_vec.push((i, eigenvalues[i]));
-    }
+    // 中心化したデータを特異値分解する（共分散行列 C = (X^T * X) / (n-1) の固有ベクトルは右特異ベクトル）
+    // 低ランクの共分散行列の対称固有値分解はNaNになることがあるので使わない
+    let svd = nalgebra_data.svd(false, true);
+    let singular_values = svd.singular_values;
+    let v_t = svd.v_t.ok_or_else(|| "SVD did not converge".to_string())?;
 
-    eigenval_vec.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+    // 特異値の大きい順にソート
+    let mut order: Vec<usize> = (0..singular_values.len()).collect();
+    order.sort_by(|&a, &b| singular_values[b].total_cmp(&singular_values[a]));
 
     // 上位n_components個の主成分を選択
     let mut components = Array2::zeros((n_components, n_features));
-    for i in 0..n_components {
-        if i < eigenval_vec.len() {
-            let idx = eigenval_vec[i].0;
-            for j in 0..n_features {
-                components[[i, j]] = eigenvectors[(j, idx)];
-            }
+    let mut explained_variance = Array1::zeros(n_components);
+    for (i, &idx) in order.iter().take(n_components).enumerate() {
+        for j in 0..n_features {
+            components[[i, j]] = v_t[(idx, j)];
         }
+        explained_variance[i] = singular_values[idx].powi(2) / scale;
     }
 
-    Ok(PCATransform::new(components, mean, n_components))
+    Ok(PCATransform::with_variance(
+        components,
+        mean,
+        n_components,
+        explained_variance,
+        total_variance,
+    ))
 }
 
 /// 簡易的なPCA学習（分散ベースの選択）
@@ -173,35 +582,353 @@ pub fn learn_simple_pca(samples: &[Vec<f32>], n_components: usize) -> PCATransfo
 
     // 主成分を作成（簡易実装：単位ベクトル）
     let mut components = Array2::zeros((n_components, n_features));
+    let mut explained_variance = Array1::zeros(n_components);
     for i in 0..n_components {
         if i < indices.len() {
             components[[i, indices[i]]] = 1.0;
+            explained_variance[i] = variances[indices[i]];
         }
     }
 
-    PCATransform::new(components, mean, n_components)
+    PCATransform::with_variance(
+        components,
+        mean,
+        n_components,
+        explained_variance,
+        variances.sum(),
+    )
 }
 
-/// PCAによる次元圧縮を適用
-pub fn apply_pca_compression(features: &[f32], target_dims: usize) -> Vec<f32> {
-    if target_dims >= features.len() {
-        return features.to_vec();
+/// ランダム化SVDの設定
+#[derive(Clone, Debug)]
+pub struct RandomizedPCAConfig {
+    /// 主成分数に足して探す次元数
+    pub oversamples: usize,
+    /// 部分空間反復の回数（データを読む回数は `power_iterations + 2`）
+    pub power_iterations: usize,
+    /// 初期の乱数行列のシード
+    pub seed: u64,
+}
+
+impl Default for RandomizedPCAConfig {
+    fn default() -> Self {
+        Self {
+            oversamples: 10,
+            power_iterations: 4,
+            seed: 0,
+        }
+    }
+}
+
+/// 1行1サンプルに並べた特徴量を行列にする
+fn batch_matrix(batch: &[f32], n_features: usize) -> Result<DMatrix<f64>, String> {
+    if !batch.len().is_multiple_of(n_features) {
+        return Err(format!(
+            "Feature length {} is not a multiple of {}",
+            batch.len(),
+            n_features
+        ));
+    }
+    Ok(DMatrix::from_row_iterator(
+        batch.len() / n_features,
+        n_features,
+        batch.iter().map(|&value| value as f64),
+    ))
+}
+
+/// 半正定値対称行列の固有値と固有ベクトルを固有値の大きい順に返す
+///
+/// 低ランクの行列では対称固有値分解がNaNになることがあるので、特異値分解で求める。
+fn sorted_symmetric_eigen(matrix: DMatrix<f64>) -> Result<Vec<(f64, DVector<f64>)>, String> {
+    let svd = matrix.svd(true, false);
+    let u = svd.u.ok_or_else(|| "SVD did not converge".to_string())?;
+    let mut pairs: Vec<(f64, DVector<f64>)> = svd
+        .singular_values
+        .iter()
+        .zip(u.column_iter())
+        .map(|(&value, vector)| (value, vector.into_owned()))
+        .collect();
+    pairs.sort_by(|a, b| b.0.total_cmp(&a.0));
+    Ok(pairs)
+}
+
+/// データを1回読み、中心化したデータ `A` について `A^T A Q` と平均、分散の合計、サンプル数を求める
+///
+/// `A^T A = X^T X - n μ μ^T` を使うので、平均が分かる前に1回で集計できる。
+#[allow(clippy::type_complexity)]
+fn centered_gram_product<I>(
+    batches: I,
+    n_features: usize,
+    q: &DMatrix<f64>,
+) -> Result<(DMatrix<f64>, DVector<f64>, f64, usize), String>
+where
+    I: Iterator<Item = Result<Vec<f32>, String>>,
+{
+    let mut product = DMatrix::zeros(n_features, q.ncols());
+    let mut sum = DVector::zeros(n_features);
+    let mut sum_squares = DVector::zeros(n_features);
+    let mut n_samples = 0;
+    for batch in batches {
+        let x = batch_matrix(&batch?, n_features)?;
+        product += x.tr_mul(&(&x * q));
+        sum += x.row_sum().transpose();
+        sum_squares += x.component_mul(&x).row_sum().transpose();
+        n_samples += x.nrows();
+    }
+    if n_samples < 2 {
+        return Err("Cannot learn PCA from fewer than 2 samples".to_string());
+    }
+
+    let n = n_samples as f64;
+    let mean = sum / n;
+    product -= &mean * (mean.transpose() * q) * n;
+    let total_variance = (sum_squares - mean.component_mul(&mean) * n).sum() / (n - 1.0);
+    Ok((product, mean, total_variance, n_samples))
+}
+
+/// ランダム化SVDによるPCA学習
+///
+/// `batches` は呼ぶたびに同じデータを1行1サンプルの特徴量のバッチで先頭から返す。
+/// 共分散行列を作らず `n_features × (n_components + oversamples)` の行列だけを持つ。
+pub fn learn_randomized_pca<F, I>(
+    n_features: usize,
+    n_components: usize,
+    config: &RandomizedPCAConfig,
+    mut batches: F,
+) -> Result<PCATransform, String>
+where
+    F: FnMut() -> I,
+    I: Iterator<Item = Result<Vec<f32>, String>>,
+{
+    if n_components == 0 {
+        return Err("Number of components must be positive".to_string());
+    }
+    if n_components > n_features {
+        return Err(format!(
+            "Number of components ({}) cannot be greater than number of features ({})",
+            n_components, n_features
+        ));
+    }
+
+    // ガウス乱数の行列から始めて、共分散行列を掛けては直交化する
+    let size = (n_components + config.oversamples).min(n_features);
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let random = DMatrix::from_fn(n_features, size, |_, _| rng.sample(StandardNormal));
+    let mut q = random.qr().q();
+    let mut result = centered_gram_product(batches(), n_features, &q)?;
+    for _ in 0..=config.power_iterations {
+        q = result.0.qr().q();
+        result = centered_gram_product(batches(), n_features, &q)?;
+    }
+    let (product, mean, total_variance, n_samples) = result;
+
+    // 部分空間に射影した小さな行列を固有値分解する
+    let projected = q.tr_mul(&product);
+    let projected = (&projected + projected.transpose()) * 0.5;
+    let pairs = sorted_symmetric_eigen(projected)?;
+
+    let scale = (n_samples - 1) as f64;
+    let mut components = Array2::zeros((n_components, n_features));
+    let mut explained_variance = Array1::zeros(n_components);
+    for (i, (value, vector)) in pairs.iter().take(n_components).enumerate() {
+        let component = &q * vector;
+        for j in 0..n_features {
+            components[[i, j]] = component[j] as f32;
+        }
+        explained_variance[i] = (value / scale).max(0.0) as f32;
+    }
+
+    Ok(PCATransform::with_variance(
+        components,
+        mean.iter().map(|&value| value as f32).collect(),
+        n_components,
+        explained_variance,
+        total_variance as f32,
+    ))
+}
+
+/// ミニバッチごとに主成分を更新するPCA
+///
+/// 前回までの主成分に特異値を掛けた行、中心化した新しいバッチ、平均のずれを補正する行を
+/// 縦に並べてSVDし直す（Ross et al. の逐次SVD）。
+#[pyclass]
+#[derive(Clone, Debug)]
+pub struct IncrementalPCA {
+    #[pyo3(get)]
+    pub n_components: usize,
+    #[pyo3(get)]
+    pub n_features: usize,
+    #[pyo3(get)]
+    pub n_samples_seen: usize,
+    mean: DVector<f64>,
+    /// 平均からの偏差の2乗の和
+    squared_deviations: DVector<f64>,
+    /// 主成分（1行1主成分）
+    components: DMatrix<f64>,
+    singular_values: DVector<f64>,
+}
+
+impl IncrementalPCA {
+    pub fn new(n_features: usize, n_components: usize) -> Result<Self, String> {
+        if n_components == 0 {
+            return Err("Number of components must be positive".to_string());
+        }
+        if n_components > n_features {
+            return Err(format!(
+                "Number of components ({}) cannot be greater than number of features ({})",
+                n_components, n_features
+            ));
+        }
+        Ok(Self {
+            n_components,
+            n_features,
+            n_samples_seen: 0,
+            mean: DVector::zeros(n_features),
+            squared_deviations: DVector::zeros(n_features),
+            components: DMatrix::zeros(0, n_features),
+            singular_values: DVector::zeros(0),
+        })
+    }
+
+    /// 1行1サンプルに並べた特徴量のバッチで更新する
+    ///
+    /// 最初のバッチは `n_components` 個以上のサンプルを含む必要がある。
+    pub fn partial_fit(&mut self, batch: &[f32]) -> Result<(), String> {
+        let x = batch_matrix(batch, self.n_features)?;
+        let n_batch = x.nrows();
+        if n_batch == 0 {
+            return Ok(());
+        }
+        if self.n_samples_seen == 0 && n_batch < self.n_components {
+            return Err(format!(
+                "The first batch must have at least {} samples, got {}",
+                self.n_components, n_batch
+            ));
+        }
+
+        let n_seen = self.n_samples_seen as f64;
+        let n_total = n_seen + n_batch as f64;
+        let batch_mean = x.row_mean();
+        let mut centered = x;
+        for mut row in centered.row_iter_mut() {
+            row -= &batch_mean;
+        }
+        let mean_difference = &self.mean - batch_mean.transpose();
+        let weight = n_seen * n_batch as f64 / n_total;
+
+        // 平均と偏差の2乗の和を合わせる
+        self.squared_deviations += centered.component_mul(&centered).row_sum().transpose()
+            + mean_difference.component_mul(&mean_difference) * weight;
+        self.mean = (&self.mean * n_seen + batch_mean.transpose() * n_batch as f64) / n_total;
+
+        let stacked = if self.n_samples_seen == 0 {
+            centered
+        } else {
+            let previous = self.components.nrows();
+            let mut stacked = DMatrix::zeros(previous + n_batch + 1, self.n_features);
+            stacked
+                .rows_mut(0, previous)
+                .copy_from(&(DMatrix::from_diagonal(&self.singular_values) * &self.components));
+            stacked.rows_mut(previous, n_batch).copy_from(&centered);
+            stacked
+                .row_mut(previous + n_batch)
+                .copy_from(&(mean_difference.transpose() * weight.sqrt()));
+            stacked
+        };
+
+        let svd = stacked.svd(false, true);
+        let v_t = svd.v_t.ok_or_else(|| "SVD did not converge".to_string())?;
+        let mut order: Vec<usize> = (0..svd.singular_values.len()).collect();
+        order.sort_by(|&a, &b| svd.singular_values[b].total_cmp(&svd.singular_values[a]));
+        order.truncate(self.n_components);
+
+        self.components = DMatrix::from_fn(order.len(), self.n_features, |i, j| v_t[(order[i], j)]);
+        self.singular_values =
+            DVector::from_iterator(order.len(), order.iter().map(|&i| svd.singular_values[i]));
+        self.n_samples_seen += n_batch;
+        Ok(())
     }
 
-    // グローバルなPCA変換がある場合はそれを使用
-    if let Some(ref transform) = get_global_pca_transform() {
-        if transform.n_components == target_dims {
-            return transform.transform(features);
+    /// ここまでのバッチから求めたPCA変換
+    pub fn to_transform(&self) -> Result<PCATransform, String> {
+        if self.n_samples_seen < 2 {
+            return Err("Cannot learn PCA from fewer than 2 samples".to_string());
+        }
+        let scale = (self.n_samples_seen - 1) as f64;
+        let mut components = Array2::zeros((self.n_components, self.n_features));
+        let mut explained_variance = Array1::zeros(self.n_components);
+        for i in 0..self.components.nrows() {
+            for j in 0..self.n_features {
+                components[[i, j]] = self.components[(i, j)] as f32;
+            }
+            explained_variance[i] = (self.singular_values[i].powi(2) / scale) as f32;
         }
+        Ok(PCATransform::with_variance(
+            components,
+            self.mean.iter().map(|&value| value as f32).collect(),
+            self.n_components,
+            explained_variance,
+            (self.squared_deviations.sum() / scale) as f32,
+        ))
+    }
+}
+
+#[pymethods]
+impl IncrementalPCA {
+    #[new]
+    #[pyo3(signature = (n_components))]
+    pub fn new_for_python(n_components: usize) -> PyResult<Self> {
+        Self::new(VECTOR_DIMENSIONS, n_components).map_err(pyo3::exceptions::PyValueError::new_err)
     }
 
-    // PCA変換がない場合は簡易的なサンプリングを使用
-    let mut compressed = Vec::with_capacity(target_dims);
-    let step = features.len() as f32 / target_dims as f32;
-    for i in 0..target_dims {
-        let index = (i as f32 * step) as usize;
-        compressed.push(features[index]);
+    /// 盤面かSFENのリストの特徴量で更新する（更新中はGILを解放する）
+    #[pyo3(name = "partial_fit")]
+    #[pyo3(signature = (boards, threads = None))]
+    pub fn python_partial_fit(
+        &mut self,
+        py: Python<'_>,
+        boards: Vec<BoardSource>,
+        threads: Option<usize>,
+    ) -> PyResult<()> {
+        let num_threads = threads.unwrap_or_else(num_cpus::get);
+        py.allow_threads(|| {
+            let features = Board::to_vector_batch(&boards, None, num_threads)?;
+            self.partial_fit(&features)
+        })
+        .map_err(pyo3::exceptions::PyValueError::new_err)
     }
 
-    compressed
+    #[pyo3(name = "to_transform")]
+    pub fn python_to_transform(&self) -> PyResult<PCATransform> {
+        self.to_transform()
+            .map_err(pyo3::exceptions::PyValueError::new_err)
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!(
+            "IncrementalPCA(n_components={}, n_features={}, n_samples_seen={})",
+            self.n_components, self.n_features, self.n_samples_seen
+        )
+    }
+}
+
+/// PCAによる次元圧縮を適用
+///
+/// `target_dims` 個の主成分を持つグローバルなPCA変換がなければエラーにする。
+pub fn apply_pca_compression(features: &[f32], target_dims: usize) -> Result<Vec<f32>, String> {
+    if target_dims >= features.len() {
+        return Ok(features.to_vec());
+    }
+
+    match global_pca_transform() {
+        Some(transform) if transform.n_components == target_dims => transform.transform(features),
+        Some(transform) => Err(format!(
+            "Global PCA transform has {} components, but {} dimensions were requested",
+            transform.n_components, target_dims
+        )),
+        None => Err(format!(
+            "No global PCA transform is set for {} dimensions; fit or load a PCATransform and call set_global() first",
+            target_dims
+        )),
+    }
 }
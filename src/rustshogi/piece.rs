@@ -93,6 +93,24 @@ impl PieceType {
             PieceType::ProPawn => "ProPawn",
         }
     }
+
+    /// 成った後の駒種を返す（成れない駒はそのまま）
+    pub fn promote(&self) -> PieceType {
+        if Piece::able_pro(*self) {
+            PieceType::from_usize(*self as usize + PROMOTE_CHANGE as usize)
+        } else {
+            *self
+        }
+    }
+
+    /// 成る前の駒種を返す（成り駒でなければそのまま）
+    pub fn demote(&self) -> PieceType {
+        if (*self as u8) > PROMOTE {
+            PieceType::from_usize(*self as usize - PROMOTE_CHANGE as usize)
+        } else {
+            *self
+        }
+    }
 }
 
 #[pymethods]
@@ -431,6 +449,27 @@ impl Piece {
         )
     }
 
+    /// 駒の価値（交換値の計算に使用）
+    pub fn get_value(piece_type: PieceType) -> i32 {
+        match piece_type {
+            PieceType::None => 0,
+            PieceType::King => 15000,
+            PieceType::Gold => 540,
+            PieceType::Rook => 990,
+            PieceType::Bichop => 855,
+            PieceType::Silver => 495,
+            PieceType::Knight => 405,
+            PieceType::Lance => 315,
+            PieceType::Pawn => 90,
+            PieceType::Dragon => 1395,
+            PieceType::Horse => 945,
+            PieceType::ProSilver => 540,
+            PieceType::ProKnight => 540,
+            PieceType::ProLance => 540,
+            PieceType::ProPawn => 540,
+        }
+    }
+
     pub fn able_pro_batch(piece_types: &[PieceType]) -> u16 {
         let mut mask = 0u16;
         for (i, &pt) in piece_types.iter().enumerate().take(16) {
@@ -0,0 +1,137 @@
+use super::address::Address;
+use super::board::Board;
+use super::color::{get_reverse_color, ColorType};
+use super::piece::PieceType;
+
+/// 盤上の駒の種類の数（玉から成駒まで）
+const BOARD_PIECE_TYPES: usize = 14;
+
+/// 持ち駒になる駒の種類
+const HAND_PIECE_TYPES: [PieceType; 7] = [
+    PieceType::Gold,
+    PieceType::Rook,
+    PieceType::Bichop,
+    PieceType::Silver,
+    PieceType::Knight,
+    PieceType::Lance,
+    PieceType::Pawn,
+];
+
+/// 持ち駒の最大枚数（持ち駒の平面を0〜1にそろえるのに使う）
+const HAND_MAX_COUNTS: [f32; 7] = [4.0, 2.0, 2.0, 4.0, 4.0, 4.0, 18.0];
+
+/// 1局面あたりの平面の数（両者の盤上の駒14種と持ち駒7種）
+pub const POSITION_PLANES: usize = 2 * (BOARD_PIECE_TYPES + HAND_PIECE_TYPES.len());
+
+/// 局面の履歴によらない平面の数（手番と同一局面の出現回数）
+pub const CONSTANT_PLANES: usize = 2;
+
+/// ニューラルネットワークの入力平面の作り方
+#[derive(Clone, Copy, Debug)]
+pub struct PlaneOptions {
+    /// 現在の局面を含めて何局面分の平面を並べるか（1以上）
+    pub history_length: usize,
+    /// 後手番のときに盤を180度回し、手番側の駒を先に並べるか
+    pub orient: bool,
+}
+
+impl Default for PlaneOptions {
+    fn default() -> Self {
+        Self {
+            history_length: 1,
+            orient: true,
+        }
+    }
+}
+
+impl PlaneOptions {
+    pub fn num_planes(&self) -> usize {
+        POSITION_PLANES * self.history_length + CONSTANT_PLANES
+    }
+
+    /// (平面の数, 段の数, 筋の数)
+    pub fn shape(&self, board: &Board) -> (usize, usize, usize) {
+        (
+            self.num_planes(),
+            board.variant.height() as usize,
+            board.variant.width() as usize,
+        )
+    }
+}
+
+/// 1局面分の平面を `out` に書き込む
+///
+/// 各平面は上の段（SFENの1段目）から順に並ぶ。`orient` のときは `turn` 側から見た盤にする。
+fn write_position(board: &Board, turn: ColorType, orient: bool, out: &mut [f32]) {
+    let height = board.variant.height() as usize;
+    let width = board.variant.width() as usize;
+    let area = height * width;
+    let rotate = orient && turn == ColorType::White;
+    let colors = if orient {
+        [turn, get_reverse_color(turn)]
+    } else {
+        [ColorType::Black, ColorType::White]
+    };
+
+    for (side, color) in colors.into_iter().enumerate() {
+        for &index in board.get_piece_list(color) {
+            let piece_type = board.get_piece_type_from_index(index);
+            let address = Address::from_number(index);
+            let row = address.get_row() as usize;
+            let column = address.get_column() as usize;
+            let (y, x) = if rotate {
+                (row - 1, width - column)
+            } else {
+                (height - row, column - 1)
+            };
+            let plane = side * BOARD_PIECE_TYPES + piece_type as usize - 1;
+            out[plane * area + y * width + x] = 1.0;
+        }
+
+        for (i, piece_type) in HAND_PIECE_TYPES.iter().enumerate() {
+            let value = board.hand.get_count(color, *piece_type) as f32 / HAND_MAX_COUNTS[i];
+            let plane = 2 * BOARD_PIECE_TYPES + side * HAND_PIECE_TYPES.len() + i;
+            out[plane * area..(plane + 1) * area].fill(value);
+        }
+    }
+}
+
+/// AlphaZero形式の入力平面を `(平面, 段, 筋)` の順に平らに並べて返す
+///
+/// `history` は直前の局面から新しい順に並べる。足りない分の平面は0のまま残す。
+/// 最後の2平面は後手番なら1の手番の平面と、`repetition` で埋めた同一局面の出現回数の平面。
+pub fn to_planes(
+    board: &Board,
+    history: &[Board],
+    turn: ColorType,
+    repetition: u32,
+    options: &PlaneOptions,
+) -> Result<Vec<f32>, String> {
+    if options.history_length == 0 {
+        return Err("history_length must be at least 1".to_string());
+    }
+    let (channels, height, width) = options.shape(board);
+    let area = height * width;
+    let mut planes = vec![0f32; channels * area];
+
+    let positions = std::iter::once(board).chain(history.iter());
+    for (slot, position) in positions.take(options.history_length).enumerate() {
+        if position.variant != board.variant {
+            return Err("history boards must have the same variant".to_string());
+        }
+        let start = slot * POSITION_PLANES * area;
+        write_position(
+            position,
+            turn,
+            options.orient,
+            &mut planes[start..start + POSITION_PLANES * area],
+        );
+    }
+
+    let constant = POSITION_PLANES * options.history_length * area;
+    if turn == ColorType::White {
+        planes[constant..constant + area].fill(1.0);
+    }
+    planes[constant + area..].fill(repetition as f32);
+    Ok(planes)
+}
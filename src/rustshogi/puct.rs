@@ -0,0 +1,409 @@
+use super::board::Board;
+use super::color::{get_reverse_color, ColorType};
+use super::evaluator::{EvalPosition, Evaluation, Evaluator};
+use super::move_list::MoveList;
+use super::moves::Move;
+
+use pyo3::prelude::*;
+use rand::thread_rng;
+use rand_distr::{Distribution, Gamma};
+use std::ops::Range;
+
+/// PUCT探索の設定
+#[pyclass]
+#[derive(Clone, Debug)]
+pub struct PuctConfig {
+    /// 葉を評価する回数
+    #[pyo3(get, set)]
+    pub simulations: usize,
+    /// 1回の評価にまとめる葉の数
+    #[pyo3(get, set)]
+    pub batch_size: usize,
+    /// 事前確率による探索項の重み
+    #[pyo3(get, set)]
+    pub c_puct: f32,
+    /// 根の事前確率に混ぜるディリクレノイズの集中度
+    #[pyo3(get, set)]
+    pub dirichlet_alpha: f32,
+    /// 根の事前確率に混ぜるノイズの割合（0ならノイズなし）
+    #[pyo3(get, set)]
+    pub dirichlet_epsilon: f32,
+}
+
+impl Default for PuctConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PuctConfig {
+    pub fn new() -> Self {
+        Self {
+            simulations: 800,
+            batch_size: 16,
+            c_puct: 1.5,
+            dirichlet_alpha: 0.15,
+            dirichlet_epsilon: 0.0,
+        }
+    }
+}
+
+#[pymethods]
+impl PuctConfig {
+    #[new]
+    #[pyo3(signature = (simulations = 800, batch_size = 16, c_puct = 1.5, dirichlet_alpha = 0.15, dirichlet_epsilon = 0.0))]
+    pub fn new_for_python(
+        simulations: usize,
+        batch_size: usize,
+        c_puct: f32,
+        dirichlet_alpha: f32,
+        dirichlet_epsilon: f32,
+    ) -> Self {
+        Self {
+            simulations,
+            batch_size,
+            c_puct,
+            dirichlet_alpha,
+            dirichlet_epsilon,
+        }
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!(
+            "PuctConfig(simulations={}, batch_size={}, c_puct={}, dirichlet_alpha={}, dirichlet_epsilon={})",
+            self.simulations,
+            self.batch_size,
+            self.c_puct,
+            self.dirichlet_alpha,
+            self.dirichlet_epsilon
+        )
+    }
+}
+
+/// PUCT探索の結果（根の合法手ごとの訪問回数）
+#[pyclass]
+#[derive(Clone, Debug)]
+pub struct PuctResult {
+    #[pyo3(get)]
+    pub moves: Vec<Move>,
+    #[pyo3(get)]
+    pub visits: Vec<u32>,
+    /// 評価器が返した根の事前確率（ノイズを混ぜる前）
+    #[pyo3(get)]
+    pub priors: Vec<f32>,
+    /// 根の手番側から見た探索の評価値（-1〜1）
+    #[pyo3(get)]
+    pub value: f32,
+}
+
+impl PuctResult {
+    /// 最も訪問回数の多い手（合法手がなければ `None`）
+    pub fn best_move(&self) -> Option<Move> {
+        let best = self
+            .visits
+            .iter()
+            .enumerate()
+            .max_by_key(|(_, visits)| **visits)?;
+        Some(self.moves[best.0])
+    }
+
+    /// 訪問回数を合計1にした分布
+    pub fn visit_distribution(&self) -> Vec<f32> {
+        let total: u32 = self.visits.iter().sum();
+        if total == 0 {
+            return vec![0.0; self.visits.len()];
+        }
+        self.visits
+            .iter()
+            .map(|visits| *visits as f32 / total as f32)
+            .collect()
+    }
+}
+
+#[pymethods]
+impl PuctResult {
+    #[pyo3(name = "best_move")]
+    pub fn python_best_move(&self) -> Option<Move> {
+        self.best_move()
+    }
+
+    #[pyo3(name = "visit_distribution")]
+    pub fn python_visit_distribution(&self) -> Vec<f32> {
+        self.visit_distribution()
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!(
+            "PuctResult(best_move={}, value={}, visits={})",
+            self.best_move()
+                .map(|mv| mv.to_string())
+                .unwrap_or_else(|| "None".to_string()),
+            self.value,
+            self.visits.iter().sum::<u32>()
+        )
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Node {
+    mv: Move,
+    prior: f32,
+    visits: u32,
+    virtual_loss: u32,
+    /// この局面へ指した側から見た評価値の合計
+    value_sum: f32,
+    children: Range<usize>,
+    /// 合法手がない局面の、手番側から見た評価値
+    terminal: Option<f32>,
+    /// 評価待ちの葉として集めてあるか
+    pending: bool,
+}
+
+impl Node {
+    fn new(mv: Move, prior: f32) -> Self {
+        Self {
+            mv,
+            prior,
+            visits: 0,
+            virtual_loss: 0,
+            value_sum: 0.0,
+            children: 0..0,
+            terminal: None,
+            pending: false,
+        }
+    }
+
+    fn is_expanded(&self) -> bool {
+        !self.children.is_empty()
+    }
+}
+
+/// 評価器の評価値と事前確率で手を選ぶモンテカルロ木探索（PUCT）
+///
+/// 仮想損失を使って複数の葉を集め、評価器にまとめて渡す。
+pub struct PuctSearch<'a> {
+    evaluator: &'a dyn Evaluator,
+    config: PuctConfig,
+    nodes: Vec<Node>,
+}
+
+/// 自玉に王手が残らない手
+pub fn legal_moves(board: &Board, turn: ColorType) -> Vec<Move> {
+    let mut moves = MoveList::new();
+    board.search_moves_into(turn, &mut moves);
+    moves
+        .iter()
+        .filter(|mv| board.is_legal(mv))
+        .copied()
+        .collect()
+}
+
+impl<'a> PuctSearch<'a> {
+    pub fn new(evaluator: &'a dyn Evaluator, config: PuctConfig) -> Self {
+        Self {
+            evaluator,
+            config,
+            nodes: Vec::new(),
+        }
+    }
+
+    fn expand(&mut self, node: usize, moves: &[Move], priors: &[f32]) {
+        let start = self.nodes.len();
+        self.nodes.extend(
+            moves
+                .iter()
+                .zip(priors.iter())
+                .map(|(mv, prior)| Node::new(*mv, *prior)),
+        );
+        self.nodes[node].children = start..self.nodes.len();
+    }
+
+    /// 根の子の事前確率にディリクレノイズを混ぜる
+    fn add_root_noise(&mut self) -> Result<(), String> {
+        let epsilon = self.config.dirichlet_epsilon;
+        if epsilon <= 0.0 {
+            return Ok(());
+        }
+        let gamma = Gamma::new(self.config.dirichlet_alpha, 1.0)
+            .map_err(|e| format!("invalid dirichlet_alpha: {}", e))?;
+        let mut rng = thread_rng();
+        let children = self.nodes[0].children.clone();
+        let noise: Vec<f32> = children.clone().map(|_| gamma.sample(&mut rng)).collect();
+        let sum: f32 = noise.iter().sum();
+        if sum <= 0.0 {
+            return Ok(());
+        }
+        for (child, noise) in children.zip(noise) {
+            let node = &mut self.nodes[child];
+            node.prior = (1.0 - epsilon) * node.prior + epsilon * noise / sum;
+        }
+        Ok(())
+    }
+
+    /// 仮想損失を負けとして数えた、子へ指した側から見たPUCTの値
+    fn score(&self, parent: &Node, child: &Node) -> f32 {
+        let parent_visits = (parent.visits + parent.virtual_loss).max(1) as f32;
+        let visits = child.visits + child.virtual_loss;
+        let q = if visits == 0 {
+            0.0
+        } else {
+            (child.value_sum - child.virtual_loss as f32) / visits as f32
+        };
+        q + self.config.c_puct * child.prior * parent_visits.sqrt() / (1 + visits) as f32
+    }
+
+    /// 根から評価していない葉まで降り、経路と葉の局面を返す
+    fn select(&mut self, root: &Board, root_turn: ColorType) -> (Vec<usize>, Board, ColorType) {
+        let mut board = root.clone();
+        let mut turn = root_turn;
+        let mut node = 0;
+        let mut path = vec![0];
+        while self.nodes[node].is_expanded() {
+            let parent = &self.nodes[node];
+            let mut best = parent.children.start;
+            let mut best_score = f32::NEG_INFINITY;
+            for child in parent.children.clone() {
+                let score = self.score(parent, &self.nodes[child]);
+                if score > best_score {
+                    best = child;
+                    best_score = score;
+                }
+            }
+            board.execute_move(&self.nodes[best].mv);
+            turn = get_reverse_color(turn);
+            node = best;
+            path.push(node);
+        }
+        for node in &path {
+            self.nodes[*node].virtual_loss += 1;
+        }
+        (path, board, turn)
+    }
+
+    fn remove_virtual_loss(&mut self, path: &[usize]) {
+        for node in path {
+            self.nodes[*node].virtual_loss -= 1;
+        }
+    }
+
+    /// 葉の手番側から見た評価値 `value` を根まで伝える
+    fn backup(&mut self, path: &[usize], value: f32) {
+        let mut value = -value;
+        for node in path.iter().rev() {
+            let node = &mut self.nodes[*node];
+            node.visits += 1;
+            node.value_sum += value;
+            value = -value;
+        }
+    }
+
+    fn evaluate(&self, positions: &[EvalPosition]) -> Result<Vec<Evaluation>, String> {
+        let evaluations = self.evaluator.evaluate(positions)?;
+        if evaluations.len() != positions.len() {
+            return Err(format!(
+                "evaluator returned {} evaluations for {} positions",
+                evaluations.len(),
+                positions.len()
+            ));
+        }
+        if let Some((position, evaluation)) = positions
+            .iter()
+            .zip(evaluations.iter())
+            .find(|(p, e)| p.moves.len() != e.priors.len())
+        {
+            return Err(format!(
+                "evaluator returned {} priors for {} moves",
+                evaluation.priors.len(),
+                position.moves.len()
+            ));
+        }
+        Ok(evaluations)
+    }
+
+    /// `turn` 側の手番の `board` から探索する
+    pub fn search(&mut self, board: &Board, turn: ColorType) -> Result<PuctResult, String> {
+        self.nodes = vec![Node::new(Move::pass(), 1.0)];
+        let moves = legal_moves(board, turn);
+        if moves.is_empty() {
+            return Ok(PuctResult {
+                moves,
+                visits: Vec::new(),
+                priors: Vec::new(),
+                value: -1.0,
+            });
+        }
+
+        let root = EvalPosition {
+            board: board.clone(),
+            turn,
+            moves,
+        };
+        let evaluation = self.evaluate(std::slice::from_ref(&root))?.remove(0);
+        self.expand(0, &root.moves, &evaluation.priors);
+        self.add_root_noise()?;
+        self.backup(&[0], evaluation.value);
+
+        let batch_size = self.config.batch_size.max(1);
+        let mut simulations = 0;
+        while simulations < self.config.simulations {
+            // 評価待ちの葉と、根からそこまでの経路
+            let mut paths: Vec<Vec<usize>> = Vec::new();
+            let mut positions: Vec<EvalPosition> = Vec::new();
+            let wanted = batch_size.min(self.config.simulations - simulations);
+            while positions.len() < wanted && simulations < self.config.simulations {
+                let (path, leaf_board, leaf_turn) = self.select(board, turn);
+                let leaf = *path.last().unwrap();
+
+                if let Some(value) = self.nodes[leaf].terminal {
+                    self.remove_virtual_loss(&path);
+                    self.backup(&path, value);
+                    simulations += 1;
+                    continue;
+                }
+                // 同じ葉が選ばれたら、集めた分だけで評価する
+                if self.nodes[leaf].pending {
+                    self.remove_virtual_loss(&path);
+                    break;
+                }
+                let moves = legal_moves(&leaf_board, leaf_turn);
+                if moves.is_empty() {
+                    // 合法手がなければ手番側の負け
+                    self.nodes[leaf].terminal = Some(-1.0);
+                    self.remove_virtual_loss(&path);
+                    self.backup(&path, -1.0);
+                    simulations += 1;
+                    continue;
+                }
+                self.nodes[leaf].pending = true;
+                paths.push(path);
+                positions.push(EvalPosition {
+                    board: leaf_board,
+                    turn: leaf_turn,
+                    moves,
+                });
+            }
+            if positions.is_empty() {
+                continue;
+            }
+
+            let evaluations = self.evaluate(&positions)?;
+            for ((path, position), evaluation) in paths.iter().zip(&positions).zip(evaluations) {
+                let node = *path.last().unwrap();
+                self.nodes[node].pending = false;
+                self.expand(node, &position.moves, &evaluation.priors);
+                self.remove_virtual_loss(path);
+                self.backup(path, evaluation.value);
+                simulations += 1;
+            }
+        }
+
+        let root = &self.nodes[0];
+        let children = &self.nodes[root.children.clone()];
+        Ok(PuctResult {
+            moves: children.iter().map(|child| child.mv).collect(),
+            visits: children.iter().map(|child| child.visits).collect(),
+            priors: evaluation.priors,
+            value: -root.value_sum / root.visits as f32,
+        })
+    }
+}
@@ -0,0 +1,265 @@
+use super::board::Board;
+use super::color::{get_reverse_color, ColorType};
+use super::moves::Move;
+use super::piece::Piece;
+use super::random::Random;
+
+use pyo3::prelude::*;
+use rand::{thread_rng, Rng};
+
+/// プレイアウト中に指す手を選ぶ方策
+pub trait RolloutPolicy: Send + Sync {
+    /// `moves` の中から指す手のインデックスを返す（`moves` は空でないこと）
+    fn select_move(&self, board: &Board, turn: ColorType, moves: &[Move]) -> usize;
+}
+
+fn random_index(len: usize) -> usize {
+    let mut random = Random::new(0, (len - 1) as u16);
+    random.generate_one() as usize
+}
+
+fn is_capture(board: &Board, mv: &Move) -> bool {
+    !mv.get_is_drop() && (board.has_piece.to_u128() >> (127 - mv.get_to().to_index())) & 1 != 0
+}
+
+/// 手を指したときの駒得（取った駒の価値と成りによる増分）
+pub fn material_gain(board: &Board, mv: &Move) -> i32 {
+    if mv.get_is_drop() {
+        return 0;
+    }
+    let piece_type = board.get_piece_type_from_index(mv.get_from().to_index());
+    let mut gain = Piece::get_value(board.get_piece_type_from_index(mv.get_to().to_index()));
+    if mv.get_is_promote() {
+        gain += Piece::get_value(piece_type.promote()) - Piece::get_value(piece_type);
+    }
+    gain
+}
+
+/// 1手で相手玉を詰ませる（または取る）手を探す
+pub fn find_mate_in_one(board: &Board, turn: ColorType, moves: &[Move]) -> Option<usize> {
+    let enemy = get_reverse_color(turn);
+    let king_index = board.get_king_index(enemy)?;
+
+    if let Some(index) = moves
+        .iter()
+        .position(|mv| !mv.get_is_drop() && mv.get_to().to_index() == king_index)
+    {
+        return Some(index);
+    }
+
+    for (index, mv) in moves.iter().enumerate() {
+        let mut next = board.clone();
+        next.execute_move(mv);
+        if !next.is_in_check(enemy) {
+            continue;
+        }
+        let escaped = next.search_moves(enemy).iter().any(|reply| {
+            let mut after = next.clone();
+            after.execute_move(reply);
+            // 自玉を取られる応手も詰みを逃れたものとして扱う
+            after.get_king_index(turn).is_none() || !after.is_in_check(enemy)
+        });
+        if !escaped {
+            return Some(index);
+        }
+    }
+    None
+}
+
+/// 一様ランダムに手を選ぶ
+pub struct UniformPolicy;
+
+impl RolloutPolicy for UniformPolicy {
+    fn select_move(&self, _board: &Board, _turn: ColorType, moves: &[Move]) -> usize {
+        random_index(moves.len())
+    }
+}
+
+/// 駒を取る手があればその中から選び、なければ一様ランダムに選ぶ
+pub struct CaptureFirstPolicy;
+
+impl RolloutPolicy for CaptureFirstPolicy {
+    fn select_move(&self, board: &Board, turn: ColorType, moves: &[Move]) -> usize {
+        if let Some(king_index) = board.get_king_index(get_reverse_color(turn)) {
+            if let Some(index) = moves
+                .iter()
+                .position(|mv| !mv.get_is_drop() && mv.get_to().to_index() == king_index)
+            {
+                return index;
+            }
+        }
+
+        let captures: Vec<usize> = moves
+            .iter()
+            .enumerate()
+            .filter(|(_, mv)| is_capture(board, mv))
+            .map(|(index, _)| index)
+            .collect();
+        if captures.is_empty() {
+            random_index(moves.len())
+        } else {
+            captures[random_index(captures.len())]
+        }
+    }
+}
+
+/// 駒得を温度付きソフトマックスで確率に変換して選ぶ
+pub struct SoftmaxPolicy {
+    pub temperature: f64,
+}
+
+impl RolloutPolicy for SoftmaxPolicy {
+    fn select_move(&self, board: &Board, _turn: ColorType, moves: &[Move]) -> usize {
+        let gains: Vec<f64> = moves
+            .iter()
+            .map(|mv| material_gain(board, mv) as f64)
+            .collect();
+        let max_gain = gains.iter().cloned().fold(f64::MIN, f64::max);
+        let temperature = self.temperature.max(f64::EPSILON);
+        let weights: Vec<f64> = gains
+            .iter()
+            .map(|gain| ((gain - max_gain) / temperature).exp())
+            .collect();
+
+        let total: f64 = weights.iter().sum();
+        let mut threshold = thread_rng().gen::<f64>() * total;
+        for (index, weight) in weights.iter().enumerate() {
+            if threshold < *weight {
+                return index;
+            }
+            threshold -= weight;
+        }
+        moves.len() - 1
+    }
+}
+
+/// 確率 `epsilon` で一様ランダムに、それ以外はSEEが最大の手を選ぶ
+pub struct EpsilonGreedyPolicy {
+    pub epsilon: f64,
+}
+
+impl RolloutPolicy for EpsilonGreedyPolicy {
+    fn select_move(&self, board: &Board, _turn: ColorType, moves: &[Move]) -> usize {
+        if thread_rng().gen::<f64>() < self.epsilon {
+            return random_index(moves.len());
+        }
+
+        let scores: Vec<i32> = moves.iter().map(|mv| board.see(mv)).collect();
+        let best_score = *scores.iter().max().unwrap();
+        let best: Vec<usize> = scores
+            .iter()
+            .enumerate()
+            .filter(|(_, score)| **score == best_score)
+            .map(|(index, _)| index)
+            .collect();
+        best[random_index(best.len())]
+    }
+}
+
+/// 1手詰めがあればそれを指し、なければ `fallback` に任せる
+pub struct MateInOnePolicy {
+    pub fallback: Box<dyn RolloutPolicy>,
+}
+
+impl RolloutPolicy for MateInOnePolicy {
+    fn select_move(&self, board: &Board, turn: ColorType, moves: &[Move]) -> usize {
+        match find_mate_in_one(board, turn, moves) {
+            Some(index) => index,
+            None => self.fallback.select_move(board, turn, moves),
+        }
+    }
+}
+
+#[pyclass(eq, eq_int)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum RolloutPolicyType {
+    Uniform = 0,
+    CaptureFirst,
+    Softmax,
+    EpsilonGreedy,
+}
+
+/// プレイアウトの方策と打ち切りの設定
+#[pyclass]
+#[derive(Clone, Debug)]
+pub struct RolloutConfig {
+    #[pyo3(get, set)]
+    pub policy: RolloutPolicyType,
+    #[pyo3(get, set)]
+    pub temperature: f64,
+    #[pyo3(get, set)]
+    pub epsilon: f64,
+    #[pyo3(get, set)]
+    pub mate_in_one: bool,
+    #[pyo3(get, set)]
+    pub cutoff: Option<u16>,
+    #[pyo3(get, set)]
+    pub cutoff_margin: i32,
+}
+
+impl Default for RolloutConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RolloutConfig {
+    pub fn new() -> Self {
+        Self {
+            policy: RolloutPolicyType::Uniform,
+            temperature: 100.0,
+            epsilon: 0.1,
+            mate_in_one: false,
+            cutoff: None,
+            cutoff_margin: 0,
+        }
+    }
+
+    pub fn build_policy(&self) -> Box<dyn RolloutPolicy> {
+        let policy: Box<dyn RolloutPolicy> = match self.policy {
+            RolloutPolicyType::Uniform => Box::new(UniformPolicy),
+            RolloutPolicyType::CaptureFirst => Box::new(CaptureFirstPolicy),
+            RolloutPolicyType::Softmax => Box::new(SoftmaxPolicy {
+                temperature: self.temperature,
+            }),
+            RolloutPolicyType::EpsilonGreedy => Box::new(EpsilonGreedyPolicy {
+                epsilon: self.epsilon,
+            }),
+        };
+        if self.mate_in_one {
+            Box::new(MateInOnePolicy { fallback: policy })
+        } else {
+            policy
+        }
+    }
+}
+
+#[pymethods]
+impl RolloutConfig {
+    #[new]
+    #[pyo3(signature = (policy = RolloutPolicyType::Uniform, temperature = 100.0, epsilon = 0.1, mate_in_one = false, cutoff = None, cutoff_margin = 0))]
+    pub fn new_for_python(
+        policy: RolloutPolicyType,
+        temperature: f64,
+        epsilon: f64,
+        mate_in_one: bool,
+        cutoff: Option<u16>,
+        cutoff_margin: i32,
+    ) -> Self {
+        Self {
+            policy,
+            temperature,
+            epsilon,
+            mate_in_one,
+            cutoff,
+            cutoff_margin,
+        }
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!(
+            "RolloutConfig(policy={:?}, temperature={}, epsilon={}, mate_in_one={}, cutoff={:?}, cutoff_margin={})",
+            self.policy, self.temperature, self.epsilon, self.mate_in_one, self.cutoff, self.cutoff_margin
+        )
+    }
+}
@@ -0,0 +1,320 @@
+use super::color::{get_reverse_color, ColorType};
+use super::evaluator::{Evaluator, PyEvaluator, UniformEvaluator};
+use super::game::Game;
+use super::moves::Move;
+use super::puct::{PuctConfig, PuctResult, PuctSearch};
+use super::variant::Variant;
+
+use pyo3::prelude::*;
+use rand::distributions::WeightedIndex;
+use rand::prelude::Distribution;
+use rand::thread_rng;
+use rayon::prelude::*;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::sync::Mutex;
+
+/// 自己対局の設定
+#[pyclass]
+#[derive(Clone, Debug)]
+pub struct SelfPlayConfig {
+    /// 1手ごとの探索の設定
+    #[pyo3(get, set)]
+    pub search: PuctConfig,
+    /// 訪問回数から手を選ぶときの温度
+    #[pyo3(get, set)]
+    pub temperature: f32,
+    /// 温度を使って手を選ぶ手数（以降は最も訪問回数の多い手を指す）
+    #[pyo3(get, set)]
+    pub temperature_moves: u16,
+    /// 手番側の評価値がこれを下回ったら投了する（`None` なら投了しない）
+    #[pyo3(get, set)]
+    pub resign_threshold: Option<f32>,
+    /// この手数に達したら引き分けにする
+    #[pyo3(get, set)]
+    pub max_moves: u16,
+    #[pyo3(get, set)]
+    pub variant: Variant,
+}
+
+impl Default for SelfPlayConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 自己対局の1局面の記録
+#[derive(Clone, Debug)]
+pub struct SelfPlayRecord {
+    /// 手番と手数を含むSFEN
+    pub sfen: String,
+    /// 根の合法手
+    pub moves: Vec<Move>,
+    /// `moves` と同じ順の訪問回数
+    pub visits: Vec<u32>,
+    /// 手番側から見た対局の結果（勝ち1、負け-1、引き分け0）
+    pub result: f32,
+}
+
+impl SelfPlayRecord {
+    /// JSON Linesの1行（改行を含まない）
+    pub fn to_json(&self) -> String {
+        let moves: Vec<String> = self
+            .moves
+            .iter()
+            .map(|mv| format!("\"{}\"", mv.to_string()))
+            .collect();
+        let visits: Vec<String> = self.visits.iter().map(|v| v.to_string()).collect();
+        format!(
+            "{{\"sfen\":\"{}\",\"moves\":[{}],\"visits\":[{}],\"result\":{}}}",
+            self.sfen,
+            moves.join(","),
+            visits.join(","),
+            self.result
+        )
+    }
+}
+
+/// 1局の結果
+#[derive(Clone, Debug)]
+pub struct SelfPlayGame {
+    pub records: Vec<SelfPlayRecord>,
+    /// 勝った側（引き分けなら `ColorType::None`）
+    pub winner: ColorType,
+}
+
+/// 自己対局をまとめて実行した結果
+#[pyclass]
+#[derive(Clone, Debug, Default)]
+pub struct SelfPlaySummary {
+    #[pyo3(get)]
+    pub games: usize,
+    #[pyo3(get)]
+    pub black_wins: usize,
+    #[pyo3(get)]
+    pub white_wins: usize,
+    #[pyo3(get)]
+    pub draws: usize,
+    /// 書き出した局面の数
+    #[pyo3(get)]
+    pub positions: usize,
+}
+
+impl SelfPlaySummary {
+    fn add(&mut self, game: &SelfPlayGame) {
+        self.games += 1;
+        self.positions += game.records.len();
+        match game.winner {
+            ColorType::Black => self.black_wins += 1,
+            ColorType::White => self.white_wins += 1,
+            _ => self.draws += 1,
+        }
+    }
+}
+
+#[pymethods]
+impl SelfPlaySummary {
+    pub fn __repr__(&self) -> String {
+        format!(
+            "SelfPlaySummary(games={}, black_wins={}, white_wins={}, draws={}, positions={})",
+            self.games, self.black_wins, self.white_wins, self.draws, self.positions
+        )
+    }
+}
+
+/// 訪問回数を `1 / temperature` 乗した重みで手を選ぶ
+fn sample_move(result: &PuctResult, temperature: f32) -> Option<Move> {
+    if temperature <= 0.0 {
+        return result.best_move();
+    }
+    let weights: Vec<f64> = result
+        .visits
+        .iter()
+        .map(|visits| (*visits as f64).powf(1.0 / temperature as f64))
+        .collect();
+    match WeightedIndex::new(&weights) {
+        Ok(distribution) => Some(result.moves[distribution.sample(&mut thread_rng())]),
+        // 重みがすべて0なら最善手を指す
+        Err(_) => result.best_move(),
+    }
+}
+
+impl SelfPlayConfig {
+    pub fn new() -> Self {
+        Self {
+            search: PuctConfig {
+                dirichlet_epsilon: 0.25,
+                ..PuctConfig::new()
+            },
+            temperature: 1.0,
+            temperature_moves: 30,
+            resign_threshold: None,
+            max_moves: 512,
+            variant: Variant::Standard,
+        }
+    }
+
+    /// 初期局面から1局指す
+    pub fn play_game(&self, evaluator: &dyn Evaluator) -> Result<SelfPlayGame, String> {
+        let mut game = Game::from_variant(self.variant);
+        let mut search = PuctSearch::new(evaluator, self.search.clone());
+        // 記録と、その局面の手番
+        let mut records: Vec<(SelfPlayRecord, ColorType)> = Vec::new();
+        let mut plies: u16 = 0;
+
+        let winner = loop {
+            if plies >= self.max_moves {
+                break ColorType::None;
+            }
+            let result = search.search(&game.board, game.turn)?;
+            if result.moves.is_empty() {
+                // 合法手がなければ手番側の負け
+                break get_reverse_color(game.turn);
+            }
+            if self
+                .resign_threshold
+                .is_some_and(|threshold| result.value < threshold)
+            {
+                break get_reverse_color(game.turn);
+            }
+
+            let temperature = if plies < self.temperature_moves {
+                self.temperature
+            } else {
+                0.0
+            };
+            let mv = sample_move(&result, temperature)
+                .ok_or_else(|| "search returned no move".to_string())?;
+            records.push((
+                SelfPlayRecord {
+                    sfen: game.to_sfen(),
+                    moves: result.moves,
+                    visits: result.visits,
+                    result: 0.0,
+                },
+                game.turn,
+            ));
+            game.execute_move(&mv);
+            plies += 1;
+        };
+
+        let records = records
+            .into_iter()
+            .map(|(mut record, turn)| {
+                record.result = if winner == ColorType::None {
+                    0.0
+                } else if winner == turn {
+                    1.0
+                } else {
+                    -1.0
+                };
+                record
+            })
+            .collect();
+        Ok(SelfPlayGame { records, winner })
+    }
+
+    /// `games` 局を並列に指し、終わった局から局面の記録をJSON Linesで `writer` に書き出す
+    pub fn run<W: Write + Send>(
+        &self,
+        evaluator: &dyn Evaluator,
+        games: usize,
+        num_threads: usize,
+        writer: W,
+    ) -> Result<SelfPlaySummary, String> {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(num_threads)
+            .build()
+            .map_err(|e| e.to_string())?;
+        let output = Mutex::new((writer, SelfPlaySummary::default()));
+
+        pool.install(|| {
+            (0..games).into_par_iter().try_for_each(|_| {
+                let game = self.play_game(evaluator)?;
+                let mut output = output.lock().map_err(|e| e.to_string())?;
+                let (writer, summary) = &mut *output;
+                for record in &game.records {
+                    writeln!(writer, "{}", record.to_json()).map_err(|e| e.to_string())?;
+                }
+                summary.add(&game);
+                Ok::<(), String>(())
+            })
+        })?;
+
+        let (mut writer, summary) = output.into_inner().map_err(|e| e.to_string())?;
+        writer.flush().map_err(|e| e.to_string())?;
+        Ok(summary)
+    }
+
+    /// `path` のファイルに書き出す
+    pub fn run_to_file(
+        &self,
+        evaluator: &dyn Evaluator,
+        path: &str,
+        games: usize,
+        num_threads: usize,
+    ) -> Result<SelfPlaySummary, String> {
+        let file = File::create(path).map_err(|e| format!("{}: {}", path, e))?;
+        self.run(evaluator, games, num_threads, BufWriter::new(file))
+    }
+}
+
+#[pymethods]
+impl SelfPlayConfig {
+    #[new]
+    #[pyo3(signature = (search = None, temperature = 1.0, temperature_moves = 30, resign_threshold = None, max_moves = 512, variant = Variant::Standard))]
+    pub fn new_for_python(
+        search: Option<PuctConfig>,
+        temperature: f32,
+        temperature_moves: u16,
+        resign_threshold: Option<f32>,
+        max_moves: u16,
+        variant: Variant,
+    ) -> Self {
+        Self {
+            search: search.unwrap_or_else(|| Self::new().search),
+            temperature,
+            temperature_moves,
+            resign_threshold,
+            max_moves,
+            variant,
+        }
+    }
+
+    /// `games` 局を指して `output` に書き出す（`evaluator` を省くと一様な評価器を使う）
+    ///
+    /// 対局中はGILを解放し、`evaluator` を呼ぶときだけGILを取る。
+    #[pyo3(name = "run")]
+    #[pyo3(signature = (output, games, threads = None, evaluator = None))]
+    pub fn python_run(
+        &self,
+        py: Python<'_>,
+        output: String,
+        games: usize,
+        threads: Option<usize>,
+        evaluator: Option<Py<PyAny>>,
+    ) -> PyResult<SelfPlaySummary> {
+        let num_threads = threads.unwrap_or_else(num_cpus::get);
+        let result = match evaluator {
+            Some(callback) => {
+                let evaluator = PyEvaluator::new(callback);
+                py.allow_threads(|| self.run_to_file(&evaluator, &output, games, num_threads))
+            }
+            None => py
+                .allow_threads(|| self.run_to_file(&UniformEvaluator, &output, games, num_threads)),
+        };
+        result.map_err(pyo3::exceptions::PyValueError::new_err)
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!(
+            "SelfPlayConfig(search={}, temperature={}, temperature_moves={}, resign_threshold={:?}, max_moves={}, variant={})",
+            self.search.__repr__(),
+            self.temperature,
+            self.temperature_moves,
+            self.resign_threshold,
+            self.max_moves,
+            self.variant.get_name()
+        )
+    }
+}
@@ -49,4 +49,14 @@ mod tests {
         let row = index_to_column(100);
         assert_eq!(row, 1);
     }
+
+    #[test]
+    fn test_address_square() {
+        assert_eq!(Address::from_numbers(1, 1).to_square(), Some(0));
+        assert_eq!(Address::from_square(80), Address::from_numbers(9, 9));
+        assert_eq!(Address::from_numbers(0, 3).to_square(), None);
+        for square in 0..81 {
+            assert_eq!(Address::from_square(square).to_square(), Some(square));
+        }
+    }
 }
@@ -0,0 +1,101 @@
+#[cfg(test)]
+
+mod tests {
+    use crate::{
+        address::Address,
+        attack::{
+            attacks_from, attacks_from_by_pattern, bishop_attacks, lance_attacks, rook_attacks,
+        },
+        bitboard::{BitBoard, BIT_OF_FRAME},
+        board::Board,
+        color::ColorType,
+        piece::PieceType,
+    };
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    use strum::IntoEnumIterator;
+
+    fn squares() -> Vec<u8> {
+        (1..=9)
+            .flat_map(|row| {
+                (1..=9).map(move |column| Address::from_numbers(column, row).to_index())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_attack_tables_match_patterns() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut occupancies = vec![BitBoard::new(), BitBoard::from_u128(u128::MAX)];
+        for _ in 0..20 {
+            let bits = rng.gen::<u128>() & rng.gen::<u128>() & !BIT_OF_FRAME;
+            occupancies.push(BitBoard::from_u128(bits));
+        }
+
+        for occupied in occupancies {
+            for index in squares() {
+                for color in [ColorType::Black, ColorType::White] {
+                    for piece_type in PieceType::iter().skip(1) {
+                        assert_eq!(
+                            attacks_from(piece_type, color, index, occupied),
+                            attacks_from_by_pattern(piece_type, color, index, occupied),
+                            "{:?} {:?} {}",
+                            piece_type,
+                            color,
+                            index
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_attack_sliding_pieces() {
+        let index = Address::from_numbers(5, 5).to_index();
+        assert_eq!(rook_attacks(index, BitBoard::new()).count_ones(), 16);
+        assert_eq!(bishop_attacks(index, BitBoard::new()).count_ones(), 16);
+        assert_eq!(
+            lance_attacks(ColorType::Black, index, BitBoard::new()).count_ones(),
+            4
+        );
+
+        // 駒に当たったマスで止まる
+        let blocker = Address::from_numbers(5, 7).to_index();
+        let occupied = BitBoard::from_u128(1u128 << (127 - blocker));
+        assert_eq!(
+            lance_attacks(ColorType::Black, index, occupied).count_ones(),
+            2
+        );
+        assert_eq!(
+            lance_attacks(ColorType::White, index, occupied).count_ones(),
+            4
+        );
+        assert_eq!(rook_attacks(index, occupied).count_ones(), 14);
+    }
+
+    #[test]
+    fn test_attack_board_move_squares() {
+        // 成駒や端の駒、飛び駒の利きが重なる局面を含める
+        let boards: Vec<Board> = [
+            "lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL b - 1",
+            "8l/1l+R2P3/p2pBG1pp/kps1p4/Nn1P2G2/P1P1P2PP/1PS6/1KSG3+r1/LN2+p3L w Sbgn3p 124",
+            "ln1g3nl/1r1sk1gs1/p1pppp1pp/1p4p2/9/2P3P2/PP1PPP1PP/1BG1K2R1/LNS2GSNL b Bb 1",
+            "l2Nk3l/1N5n1/2+B3+b2/9/4R4/9/2+r3G2/1n5N1/Ln2K3L b 2P 1",
+        ]
+        .iter()
+        .map(|sfen| Board::from_sfen(sfen.to_string()))
+        .collect();
+
+        // 最終段の桂馬などは `MovePattern` をたどるとインデックス121以降へはみ出すので除いて比べる
+        let playable = BitBoard::from_u128(!(BIT_OF_FRAME | 0x7F));
+        for board in boards {
+            for index in squares() {
+                assert_eq!(
+                    board.get_able_move_squares(index),
+                    board.get_able_move_squares_by_pattern(index) & playable
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,116 @@
+#[cfg(test)]
+
+mod tests {
+    use crate::{
+        address::Address,
+        bitboard::{BitBoard, BIT_OF_FRAME, PRO_ZONE},
+        bitboard81::{BitBoard81, SQUARE_NUMBER},
+        board::Board,
+        color::ColorType,
+        piece::PieceType,
+    };
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    #[test]
+    fn test_bitboard81_square_and_address() {
+        let address = Address::from_numbers(1, 1);
+        assert_eq!(address.to_square(), Some(0));
+        assert_eq!(Address::from_square(80), Address::from_numbers(9, 9));
+        assert_eq!(Address::from_numbers(0, 3).to_square(), None);
+
+        for square in 0..SQUARE_NUMBER {
+            let bitboard = BitBoard81::from_address(&Address::from_square(square));
+            assert!(bitboard.contains(square));
+            assert_eq!(bitboard.count_ones(), 1);
+        }
+        assert!(BitBoard81::from_index(0).is_empty());
+    }
+
+    #[test]
+    fn test_bitboard81_framed_round_trip() {
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..100 {
+            let framed = BitBoard::from_u128(rng.gen::<u128>() & !BIT_OF_FRAME & !0x7F);
+            let compact = BitBoard81::from_bitboard(framed);
+            assert_eq!(compact.count_ones(), framed.count_ones());
+            assert_eq!(compact.to_bitboard(), framed);
+            let indices: Vec<u8> = compact.indices().collect();
+            let mut expected = framed.get_trues();
+            expected.sort();
+            assert_eq!(indices, expected);
+        }
+
+        // 枠のビットは捨てられる
+        let frame = BitBoard::from_u128(BIT_OF_FRAME);
+        assert!(BitBoard81::from_bitboard(frame).is_empty());
+
+        let pro_zone = BitBoard81::from(PRO_ZONE[ColorType::Black as usize]);
+        assert_eq!(pro_zone.count_ones(), 27);
+        assert!(pro_zone.contains(Address::from_numbers(1, 7).to_square().unwrap()));
+        assert!(!pro_zone.contains(Address::from_numbers(1, 6).to_square().unwrap()));
+    }
+
+    #[test]
+    fn test_bitboard81_operations() {
+        let mut bitboard = BitBoard81::new();
+        bitboard.set(0);
+        bitboard.set(62);
+        bitboard.set(63);
+        bitboard.set(80);
+        assert_eq!(bitboard.count_ones(), 4);
+        assert_eq!(bitboard.squares().collect::<Vec<u8>>(), vec![0, 62, 63, 80]);
+
+        bitboard.clear(62);
+        assert!(!bitboard.contains(62));
+        assert_eq!((!bitboard).count_ones(), 78);
+        assert_eq!((!BitBoard81::new()).count_ones(), SQUARE_NUMBER as u32);
+        assert_eq!(
+            bitboard & BitBoard81::from_square(63),
+            BitBoard81::from_square(63)
+        );
+        assert_eq!((bitboard ^ bitboard), BitBoard81::new());
+        assert_eq!(bitboard.pop_lsb(), Some(0));
+        assert_eq!(bitboard.count_ones(), 2);
+    }
+
+    #[test]
+    fn test_bitboard81_board_pieces() {
+        let mut board = Board::new();
+        board.startpos();
+        let pieces = BitBoard81::from(board.has_piece);
+        assert_eq!(pieces.count_ones(), 40);
+        assert_eq!(BitBoard::from(pieces), board.has_piece);
+    }
+
+    #[test]
+    fn test_bitboard81_board_helpers() {
+        let board = Board::from_sfen(
+            "8l/1l+R2P3/p2pBG1pp/kps1p4/Nn1P2G2/P1P1P2PP/1PS6/1KSG3+r1/LN2+p3L w Sbgn3p 124"
+                .to_string(),
+        );
+        assert_eq!(board.occupied81(), BitBoard81::from(board.has_piece));
+        assert_eq!(
+            board.pieces81(ColorType::Black) | board.pieces81(ColorType::White),
+            board.occupied81()
+        );
+        for color in [ColorType::Black, ColorType::White] {
+            let mut piece_list = board.get_piece_list(color).to_vec();
+            piece_list.sort();
+            assert_eq!(
+                board.pieces81(color).indices().collect::<Vec<u8>>(),
+                piece_list
+            );
+        }
+
+        let dragons = board.specific_pieces81(PieceType::Dragon);
+        assert_eq!(dragons.count_ones(), 2);
+        for square in dragons.squares() {
+            let address = Address::from_square(square);
+            assert_eq!(
+                board.get_piece_type_from_index(address.to_index()),
+                PieceType::Dragon
+            );
+        }
+    }
+}
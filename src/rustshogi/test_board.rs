@@ -3,21 +3,28 @@
 mod tests {
     use crate::{
         address::Address,
-        bitboard::{BitBoard, BIT_OF_FRAME, BIT_OF_PRO_ZONE_BLACK},
-        board::Board,
-        color::ColorType,
+        bitboard::{BitBoard, BIT_OF_FRAME, BIT_OF_PRO_ZONE_BLACK, FRAME, PRO_ZONE},
+        board::{Board, BoardSource},
+        color::{get_reverse_color, ColorType},
         moves::Move,
-        piece::PieceType,
+        piece::{Piece, PieceType},
+        planes::{to_planes, PlaneOptions, POSITION_PLANES},
+        puct::legal_moves,
+        variant::Variant,
     };
 
     #[test]
     fn test_board_new() {
         let board = Board::new();
-        assert_eq!(board.is_frame, BitBoard::from_u128(BIT_OF_FRAME));
-        assert_eq!(
-            board.able_pro[0],
-            BitBoard::from_u128(BIT_OF_PRO_ZONE_BLACK)
-        );
+        assert_eq!(board.has_piece, BitBoard::new());
+        assert_eq!(FRAME, BitBoard::from_u128(BIT_OF_FRAME));
+        assert_eq!(PRO_ZONE[0], BitBoard::from_u128(BIT_OF_PRO_ZONE_BLACK));
+    }
+
+    #[test]
+    fn test_board_size() {
+        // ビットボード18枚（288）+ 持ち駒（272）+ mailbox・駒リスト・駒数（245）+ 将棋の種類（1）
+        assert_eq!(std::mem::size_of::<Board>(), 808);
     }
 
     #[test]
@@ -95,7 +102,7 @@ mod tests {
     fn test_board_to_vector() {
         let mut board = Board::new();
         board.startpos();
-        let vector = board.to_vector(None);
+        let vector = board.to_vector(None).unwrap();
         assert_eq!(vector.len(), 2320);
     }
 
@@ -103,7 +110,536 @@ mod tests {
     fn test_board_to_vector_with_options() {
         let mut board = Board::new();
         board.startpos();
-        let vector = board.to_vector(Some(300));
-        assert_eq!(vector.len(), 300);
+        // 300次元の主成分を持つグローバルなPCA変換がなければエラー
+        assert!(board.to_vector(Some(300)).is_err());
+        assert_eq!(board.to_vector(Some(3000)).unwrap().len(), 2320);
+    }
+
+    #[test]
+    fn test_board_to_vector_batch() {
+        let sfens = [
+            "lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL b - 1",
+            "4k4/9/4+p4/4R4/9/9/9/9/4K4 b 2Pg 1",
+            "4k4/9/9/9/9/9/9/9/4K4 w - 1",
+        ];
+        let mut sources: Vec<BoardSource> = sfens
+            .iter()
+            .map(|sfen| BoardSource::from(sfen.to_string()))
+            .collect();
+        sources.push(BoardSource::from(Board::from_sfen(sfens[1].to_string())));
+
+        for target_dimensions in [None, Some(3000)] {
+            let dimensions = Board::vector_dimensions(target_dimensions);
+            let batch = Board::to_vector_batch(&sources, target_dimensions, 2).unwrap();
+            assert_eq!(batch.len(), sources.len() * dimensions);
+            for (row, sfen) in batch
+                .chunks(dimensions)
+                .zip(sfens.iter().chain([&sfens[1]]))
+            {
+                let board = Board::from_sfen(sfen.to_string());
+                assert_eq!(row, board.to_vector(target_dimensions).unwrap().as_slice());
+            }
+        }
+        assert!(Board::to_vector_batch(&[], None, 2).unwrap().is_empty());
+        assert!(Board::to_vector_batch(&sources, Some(300), 2).is_err());
+    }
+
+    fn index(column: u8, row: u8) -> u8 {
+        Address::from_numbers(column, row).to_index()
+    }
+
+    #[test]
+    fn test_board_see_undefended() {
+        let mut board = Board::new();
+        board.deploy(index(5, 4), PieceType::Silver, ColorType::Black);
+        board.deploy(index(5, 5), PieceType::Pawn, ColorType::White);
+        let mv = Move::from_standart(
+            Address::from_numbers(5, 4),
+            Address::from_numbers(5, 5),
+            false,
+        );
+        assert_eq!(board.see(&mv), 90);
+
+        board.deploy(index(5, 6), PieceType::Gold, ColorType::White);
+        assert_eq!(board.see(&mv), 90 - 495);
+    }
+
+    #[test]
+    fn test_board_see_x_ray() {
+        let mut board = Board::new();
+        board.deploy(index(5, 3), PieceType::Rook, ColorType::Black);
+        board.deploy(index(5, 2), PieceType::Rook, ColorType::Black);
+        board.deploy(index(5, 5), PieceType::Pawn, ColorType::White);
+        board.deploy(index(5, 6), PieceType::Silver, ColorType::White);
+        let mv = Move::from_standart(
+            Address::from_numbers(5, 3),
+            Address::from_numbers(5, 5),
+            false,
+        );
+        assert_eq!(board.see(&mv), 90 - 990 + 495);
+    }
+
+    #[test]
+    fn test_board_see_promotion() {
+        let mut board = Board::new();
+        board.deploy(index(5, 6), PieceType::Pawn, ColorType::Black);
+        board.deploy(index(5, 7), PieceType::Pawn, ColorType::White);
+        let mv = Move::from_standart(
+            Address::from_numbers(5, 6),
+            Address::from_numbers(5, 7),
+            true,
+        );
+        assert_eq!(board.see(&mv), 540);
+    }
+
+    #[test]
+    fn test_board_see_drop() {
+        let mut board = Board::new();
+        board.deploy(index(5, 6), PieceType::Pawn, ColorType::White);
+        let mv = Move::from_drop(
+            Piece::from(ColorType::Black, PieceType::Gold),
+            Address::from_numbers(5, 5),
+        );
+        assert_eq!(board.see(&mv), -540);
+    }
+
+    #[test]
+    fn test_board_see_king_cannot_recapture() {
+        let mut board = Board::new();
+        board.deploy(index(6, 4), PieceType::Silver, ColorType::Black);
+        board.deploy(index(5, 4), PieceType::Gold, ColorType::Black);
+        board.deploy(index(5, 5), PieceType::Pawn, ColorType::White);
+        board.deploy(index(5, 6), PieceType::King, ColorType::White);
+        let mv = Move::from_standart(
+            Address::from_numbers(6, 4),
+            Address::from_numbers(5, 5),
+            false,
+        );
+        assert_eq!(board.see(&mv), 90);
+    }
+
+    #[test]
+    fn test_board_execute_move_promote_and_capture() {
+        let mut board = Board::new();
+        board.deploy(index(5, 6), PieceType::Rook, ColorType::Black);
+        board.deploy(index(5, 8), PieceType::Dragon, ColorType::White);
+        board.execute_move(&Move::from_standart(
+            Address::from_numbers(5, 6),
+            Address::from_numbers(5, 8),
+            true,
+        ));
+        assert_eq!(
+            board.get_piece_type_from_index(index(5, 8)),
+            PieceType::Dragon
+        );
+        assert_eq!(
+            board.get_color_type_from_index(index(5, 8)),
+            ColorType::Black
+        );
+        assert_eq!(board.hand.get_count(ColorType::Black, PieceType::Rook), 1);
+        assert_eq!(board.hand.get_count(ColorType::Black, PieceType::King), 0);
+    }
+
+    #[test]
+    fn test_board_execute_move_promote() {
+        let mut board = Board::new();
+        board.deploy(index(3, 6), PieceType::Pawn, ColorType::Black);
+        board.deploy(index(7, 6), PieceType::Silver, ColorType::Black);
+        board.execute_move(&Move::from_standart(
+            Address::from_numbers(3, 6),
+            Address::from_numbers(3, 7),
+            true,
+        ));
+        assert_eq!(
+            board.get_piece_type_from_index(index(3, 7)),
+            PieceType::ProPawn
+        );
+        assert_eq!(
+            board.get_color_type_from_index(index(3, 7)),
+            ColorType::Black
+        );
+
+        // 成らない指し手では駒の種類は変わらない
+        board.execute_move(&Move::from_standart(
+            Address::from_numbers(7, 6),
+            Address::from_numbers(7, 7),
+            false,
+        ));
+        assert_eq!(
+            board.get_piece_type_from_index(index(7, 7)),
+            PieceType::Silver
+        );
+    }
+
+    #[test]
+    fn test_board_evaluate() {
+        let mut board = Board::new();
+        board.startpos();
+        assert_eq!(board.evaluate(ColorType::Black), 0);
+        board.hand.add_piece(ColorType::Black, PieceType::Pawn);
+        assert_eq!(board.evaluate(ColorType::Black), 90);
+        assert_eq!(board.evaluate(ColorType::White), -90);
+    }
+
+    #[test]
+    fn test_board_is_in_check() {
+        let mut board = Board::new();
+        board.deploy(index(5, 1), PieceType::King, ColorType::Black);
+        board.deploy(index(5, 9), PieceType::King, ColorType::White);
+        assert!(!board.is_in_check(ColorType::White));
+        board.deploy(index(5, 5), PieceType::Lance, ColorType::Black);
+        assert!(board.is_in_check(ColorType::White));
+        assert!(!board.is_in_check(ColorType::Black));
+    }
+
+    #[test]
+    fn test_board_from_sfen_promoted() {
+        let sfen = "4k4/9/4+P4/9/9/9/9/9/+r3K4";
+        let board = Board::from_sfen(format!("{} b G 1", sfen));
+        assert_eq!(
+            board.get_piece_type_from_index(index(5, 7)),
+            PieceType::ProPawn
+        );
+        assert_eq!(
+            board.get_color_type_from_index(index(5, 7)),
+            ColorType::Black
+        );
+        assert_eq!(
+            board.get_piece_type_from_index(index(1, 1)),
+            PieceType::Dragon
+        );
+        assert_eq!(
+            board.get_color_type_from_index(index(1, 1)),
+            ColorType::White
+        );
+        assert_eq!(board.hand.get_count(ColorType::Black, PieceType::Gold), 1);
+        assert_eq!(board.to_string(), sfen);
+
+        // 手番と手数を含まない形式
+        let board = Board::from_sfen(format!("{} 2p", sfen));
+        assert_eq!(board.hand.get_count(ColorType::White, PieceType::Pawn), 2);
+    }
+
+    #[test]
+    fn test_board_hash_key() {
+        let mut board = Board::new();
+        board.startpos();
+        let mut same = Board::new();
+        same.startpos();
+        assert_eq!(board.hash_key(), same.hash_key());
+
+        board.execute_move(&Move::from_standart(
+            Address::from_numbers(3, 3),
+            Address::from_numbers(3, 4),
+            false,
+        ));
+        assert_ne!(board.hash_key(), same.hash_key());
+
+        same.hand.add_piece(ColorType::Black, PieceType::Pawn);
+        let mut other = Board::new();
+        other.startpos();
+        other.hand.add_piece(ColorType::White, PieceType::Pawn);
+        assert_ne!(same.hash_key(), other.hash_key());
+    }
+
+    #[test]
+    fn test_board_mailbox_and_piece_lists() {
+        let mut board = Board::new();
+        board.startpos();
+        assert!(board.is_consistent());
+        assert_eq!(board.get_piece_list(ColorType::Black).len(), 20);
+        assert_eq!(board.get_piece_list(ColorType::White).len(), 20);
+        assert_eq!(
+            board.get_piece_type_from_index(index(5, 1)),
+            PieceType::King
+        );
+        assert_eq!(board.get_piece_type_from_index(0), PieceType::None);
+
+        // 角交換で駒リストと持ち駒が更新される
+        board.execute_move(&Move::from_standart(
+            Address::from_numbers(3, 3),
+            Address::from_numbers(3, 4),
+            false,
+        ));
+        board.execute_move(&Move::from_standart(
+            Address::from_numbers(7, 7),
+            Address::from_numbers(7, 6),
+            false,
+        ));
+        board.execute_move(&Move::from_standart(
+            Address::from_numbers(2, 2),
+            Address::from_numbers(8, 8),
+            true,
+        ));
+        assert!(board.is_consistent());
+        assert_eq!(board.get_piece_list(ColorType::Black).len(), 20);
+        assert_eq!(board.get_piece_list(ColorType::White).len(), 19);
+        assert_eq!(
+            board.get_piece_type_from_index(index(8, 8)),
+            PieceType::Horse
+        );
+
+        board.execute_move(&Move::from_drop(
+            Piece::from(ColorType::Black, PieceType::Bichop),
+            Address::from_numbers(5, 5),
+        ));
+        assert!(board.is_consistent());
+        assert_eq!(board.get_piece_list(ColorType::Black).len(), 21);
+        assert!(board
+            .get_piece_list(ColorType::Black)
+            .contains(&index(5, 5)));
+    }
+
+    #[test]
+    fn test_board_piece_lists_over_full() {
+        // 実戦ではありえない枚数の駒を置いても駒リストがあふれない
+        let mut board = Board::from_sfen(["PPPPPPPPP"; 9].join("/"));
+        assert!(board.is_consistent());
+        assert_eq!(board.get_piece_list(ColorType::Black).len(), 81);
+
+        for _ in 0..3 {
+            board.deploy(index(5, 5), PieceType::Gold, ColorType::Black);
+        }
+        assert!(board.is_consistent());
+        assert_eq!(board.get_piece_list(ColorType::Black).len(), 81);
+        assert_eq!(
+            board.get_piece_type_from_index(index(5, 5)),
+            PieceType::Gold
+        );
+    }
+
+    #[test]
+    fn test_board_eq_ignores_piece_list_order() {
+        let mut first = Board::new();
+        first.deploy(index(1, 1), PieceType::Pawn, ColorType::Black);
+        first.deploy(index(2, 1), PieceType::Gold, ColorType::Black);
+        let mut second = Board::new();
+        second.deploy(index(2, 1), PieceType::Gold, ColorType::Black);
+        second.deploy(index(1, 1), PieceType::Pawn, ColorType::Black);
+        assert_ne!(first.piece_lists, second.piece_lists);
+        assert_eq!(first, second);
+    }
+
+    fn sorted_values(moves: &[Move]) -> Vec<u16> {
+        let mut values: Vec<u16> = moves.iter().map(|mv| mv.value).collect();
+        values.sort();
+        values
+    }
+
+    #[test]
+    fn test_board_generate_checks() {
+        let sfens = [
+            "lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL b - 1",
+            "4k4/9/3+Pp4/4L4/9/2B6/9/9/4K4 b GSNPrn2p 1",
+            "ln1g2snl/1r1s1kgb1/p1pp1p1pp/4p1p2/1p5P1/2P6/PPSPPPP1P/1BG4R1/LN2KGSNL w Pp 1",
+        ];
+        for sfen in sfens {
+            let board = Board::from_sfen(sfen.to_string());
+            for color in [ColorType::Black, ColorType::White] {
+                let enemy = crate::color::get_reverse_color(color);
+                let king_index = board.get_king_index(enemy).unwrap();
+                let expected: Vec<Move> = board
+                    .search_moves(color)
+                    .into_iter()
+                    .filter(|mv| mv.get_is_drop() || mv.get_to().to_index() != king_index)
+                    .filter(|mv| {
+                        let mut next = board.clone();
+                        next.execute_move(mv);
+                        next.is_in_check(enemy)
+                    })
+                    .collect();
+                assert_eq!(
+                    sorted_values(&board.generate_checks(color)),
+                    sorted_values(&expected),
+                    "{} {:?}",
+                    sfen,
+                    color
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_board_generate_evasions() {
+        let sfens = [
+            // 隣の飛車を取るか玉が逃げる
+            "4k4/9/9/9/9/9/9/4r4/4K4 b GP 1",
+            // 遠くからの王手には合駒もできる
+            "4k4/9/4r4/9/9/9/9/9/3GK4 b SP 1",
+            // 両王手は玉を動かすしかない
+            "4k4/9/9/9/9/9/3n5/9/4K3r b GS 1",
+            // ピンされた金は合駒に使えない
+            "4l3k/9/9/9/9/9/2b6/4G4/4K4 b - 1",
+        ];
+        for sfen in sfens {
+            let board = Board::from_sfen(sfen.to_string());
+            assert!(board.is_in_check(ColorType::Black), "{}", sfen);
+            let expected: Vec<Move> = board
+                .search_moves(ColorType::Black)
+                .into_iter()
+                .filter(|mv| {
+                    let mut next = board.clone();
+                    next.execute_move(mv);
+                    !next.is_in_check(ColorType::Black)
+                })
+                .collect();
+            assert!(!expected.is_empty());
+            assert_eq!(
+                sorted_values(&board.generate_evasions(ColorType::Black)),
+                sorted_values(&expected),
+                "{}",
+                sfen
+            );
+        }
+
+        let mut board = Board::new();
+        board.startpos();
+        assert!(board.generate_evasions(ColorType::Black).is_empty());
+    }
+
+    #[test]
+    fn test_board_is_legal() {
+        let board = Board::from_sfen("4l3k/9/9/9/9/9/9/4G4/4K4 b - 1".to_string());
+        let pinned = Move::from_standart(
+            Address::from_numbers(5, 2),
+            Address::from_numbers(4, 2),
+            false,
+        );
+        assert!(!board.is_legal(&pinned));
+        let along = Move::from_standart(
+            Address::from_numbers(5, 2),
+            Address::from_numbers(5, 3),
+            false,
+        );
+        assert!(board.is_legal(&along));
+    }
+
+    #[test]
+    fn test_board_make_and_undo_move() {
+        let sfens = [
+            "lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL b - 1",
+            "4k4/9/3+Pp4/4L4/9/2B6/9/9/4K4 b GSNPrn2p 1",
+        ];
+        for sfen in sfens {
+            let original = Board::from_sfen(sfen.to_string());
+            for color in [ColorType::Black, ColorType::White] {
+                let mut board = original.clone();
+                for mv in original.search_moves(color) {
+                    let extended = board.make_move(&mv);
+                    board.undo_move(&extended);
+                    assert_eq!(board, original, "{}", mv.to_string());
+                    assert!(board.is_consistent());
+                    assert_eq!(board.hash_key(), original.hash_key());
+                }
+            }
+        }
+
+        // 成り駒を取った手を戻すと、成り駒のまま盤上に戻る
+        let mut board = Board::from_sfen("4k4/9/4+p4/4R4/9/9/9/9/4K4 b - 1".to_string());
+        let original = board.clone();
+        let extended = board.make_move(&Move::from_standart(
+            Address::from_numbers(5, 6),
+            Address::from_numbers(5, 7),
+            false,
+        ));
+        assert_eq!(board.hand.get_count(ColorType::Black, PieceType::Pawn), 1);
+        board.undo_move(&extended);
+        assert_eq!(
+            board.get_piece_type_from_index(index(5, 7)),
+            PieceType::ProPawn
+        );
+        assert_eq!(board, original);
+    }
+
+    #[test]
+    fn test_board_mirrored_and_flipped_hash() {
+        // 初期局面は先後を入れ替えても同じ局面で、左右反転すると飛車と角が入れ替わる
+        let mut startpos = Board::new();
+        startpos.startpos();
+        assert_eq!(startpos.flipped(), startpos);
+        assert_eq!(startpos.flipped().hash_key(), startpos.hash_key());
+        let swapped = Board::from_sfen(
+            "lnsgkgsnl/1b5r1/ppppppppp/9/9/9/PPPPPPPPP/1R5B1/LNSGKGSNL b - 1".to_string(),
+        );
+        assert_eq!(startpos.mirrored(), swapped);
+        assert_eq!(startpos.mirrored().hash_key(), swapped.hash_key());
+
+        let board = Board::from_sfen("4k4/9/3+Pp4/4L4/9/2B6/9/9/4K4 b GSNPrn2p 1".to_string());
+        for transformed in [board.mirrored(), board.flipped()] {
+            assert!(transformed.is_consistent());
+            assert_ne!(transformed.hash_key(), board.hash_key());
+        }
+        assert_eq!(board.mirrored().mirrored(), board);
+        assert_eq!(board.flipped().flipped(), board);
+        assert_eq!(board.mirrored().mirrored().hash_key(), board.hash_key());
+        assert_eq!(board.flipped().flipped().hash_key(), board.hash_key());
+
+        let mirrored = board.mirrored();
+        assert_eq!(
+            mirrored.get_piece(index(6, 7)).piece_type,
+            PieceType::ProPawn
+        );
+        assert_eq!(
+            mirrored.get_piece(index(7, 4)).piece_type,
+            PieceType::Bichop
+        );
+        let flipped = board.flipped();
+        assert_eq!(
+            flipped.get_piece(index(6, 3)),
+            Piece::from(ColorType::White, PieceType::ProPawn)
+        );
+        assert_eq!(flipped.hand.get_count(ColorType::White, PieceType::Gold), 1);
+        assert_eq!(flipped.hand.get_count(ColorType::Black, PieceType::Pawn), 2);
+        assert_eq!(flipped.hand.get_count(ColorType::Black, PieceType::Rook), 1);
+
+        let mut minishogi = Board::with_variant(Variant::Minishogi);
+        minishogi.startpos();
+        assert_eq!(minishogi.flipped(), minishogi);
+        assert_ne!(minishogi.mirrored(), minishogi);
+        assert_eq!(
+            minishogi.mirrored().perft(ColorType::Black, 2),
+            minishogi.perft(ColorType::Black, 2)
+        );
+    }
+
+    #[test]
+    fn test_board_augmented_moves_and_features() {
+        let board = Board::from_sfen(
+            "8l/1l+R2P3/p2pBG1pp/kps1p4/Nn1P2G2/P1P1P2PP/1PS6/1KSG3+r1/LN2+p3L b Sbgn3p 1"
+                .to_string(),
+        );
+        let mirrored = board.mirrored();
+        let flipped = board.flipped();
+        for turn in [ColorType::Black, ColorType::White] {
+            let moves = legal_moves(&board, turn);
+            assert_eq!(legal_moves(&mirrored, turn).len(), moves.len());
+            let flipped_moves = legal_moves(&flipped, get_reverse_color(turn));
+            assert_eq!(flipped_moves.len(), moves.len());
+            for mv in &moves {
+                let mirrored_move = mv.mirrored(Variant::Standard);
+                let flipped_move = mv.flipped(Variant::Standard);
+                assert!(mirrored.is_legal(&mirrored_move), "{}", mv);
+                assert!(flipped.is_legal(&flipped_move), "{}", mv);
+                assert_eq!(
+                    flipped_move.to_label(get_reverse_color(turn)),
+                    mv.to_label(turn)
+                );
+                assert_eq!(
+                    flipped_move.flipped(Variant::Standard).to_string(),
+                    mv.to_string()
+                );
+            }
+        }
+
+        // 手番側から見た平面は、先後を入れ替えた盤の相手番と同じ
+        let options = PlaneOptions::default();
+        let (_, height, width) = options.shape(&board);
+        let area = height * width;
+        let original = to_planes(&board, &[], ColorType::Black, 0, &options).unwrap();
+        let augmented = to_planes(&flipped, &[], ColorType::White, 0, &options).unwrap();
+        assert_eq!(
+            original[..POSITION_PLANES * area],
+            augmented[..POSITION_PLANES * area]
+        );
     }
 }
@@ -1,5 +1,5 @@
 use super::board::Board;
-use super::pca::{learn_simple_pca, set_global_pca_transform};
+use super::pca::{learn_simple_pca, set_global_pca_transform, GLOBAL_PCA_TEST_LOCK};
 
 #[test]
 fn test_board_new() {
@@ -12,7 +12,7 @@ fn test_board_new() {
 #[test]
 fn test_board_to_vector_no_compression() {
     let board = Board::new();
-    let vector = board.to_vector(None);
+    let vector = board.to_vector(None).unwrap();
 
     // 2304 (盤面) + 16 (持ち駒) = 2320次元
     assert_eq!(vector.len(), 2320);
@@ -21,22 +21,23 @@ fn test_board_to_vector_no_compression() {
 #[test]
 fn test_board_to_vector_with_compression() {
     let board = Board::new();
-    let vector = board.to_vector(Some(100));
-
-    assert_eq!(vector.len(), 100);
+    // 100次元の主成分を持つPCA変換がないので、黙って間引かずにエラーにする
+    assert!(board.to_vector(Some(100)).is_err());
 }
 
 #[test]
 fn test_board_to_vector_with_pca() {
     let board = Board::new();
+    let _lock = GLOBAL_PCA_TEST_LOCK.lock().unwrap();
 
     // サンプルデータでPCAを学習
     let samples = vec![vec![1.0; 2320], vec![2.0; 2320], vec![3.0; 2320]];
     let pca_transform = learn_simple_pca(&samples, 50);
     set_global_pca_transform(pca_transform);
 
-    let vector = board.to_vector(Some(50));
+    let vector = board.to_vector(Some(50)).unwrap();
     assert_eq!(vector.len(), 50);
+    assert!(board.to_vector(Some(40)).is_err());
 }
 
 #[test]
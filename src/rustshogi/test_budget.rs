@@ -0,0 +1,195 @@
+#[cfg(test)]
+
+mod tests {
+    use crate::{
+        board::Board,
+        budget::{is_leader_decided, SearchBudget, StopFlag},
+        color::ColorType,
+        game::Game,
+        mctsresult::MctsResult,
+        moves::Move,
+        rollout::RolloutConfig,
+    };
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    fn startpos_game() -> Game {
+        let mut board = Board::new();
+        board.startpos();
+        Game::from(board, 1, ColorType::Black, ColorType::None)
+    }
+
+    fn total_games(results: &[MctsResult]) -> u64 {
+        results.iter().map(|result| result.total_games).sum()
+    }
+
+    #[test]
+    fn test_budget_allocate_time() {
+        assert_eq!(SearchBudget::new().allocate_time_ms(), None);
+        assert_eq!(SearchBudget::from_time(3000).allocate_time_ms(), Some(3000));
+
+        let mut budget = SearchBudget::new();
+        budget.remaining_ms = Some(60000);
+        budget.increment_ms = 1000;
+        budget.moves_to_go = 30;
+        assert_eq!(budget.allocate_time_ms(), Some(3000));
+
+        budget.max_time_ms = Some(1500);
+        assert_eq!(budget.allocate_time_ms(), Some(1500));
+    }
+
+    #[test]
+    fn test_budget_allocate_time_byoyomi() {
+        let mut budget = SearchBudget::new();
+        budget.remaining_ms = Some(0);
+        budget.byoyomi_ms = 10000;
+        budget.margin_ms = 100;
+        assert_eq!(budget.allocate_time_ms(), Some(9900));
+
+        // 残り時間が少ないときは余裕を残して使い切らない
+        budget.remaining_ms = Some(120);
+        budget.byoyomi_ms = 0;
+        budget.moves_to_go = 1;
+        assert_eq!(budget.allocate_time_ms(), Some(20));
+    }
+
+    #[test]
+    fn test_budget_stop_flag_shared() {
+        let flag = StopFlag::new();
+        let mut budget = SearchBudget::new();
+        budget.stop_flag = Some(flag.clone());
+        assert!(!budget.is_stopped());
+        flag.stop();
+        assert!(budget.is_stopped());
+        flag.reset();
+        assert!(!budget.is_stopped());
+    }
+
+    #[test]
+    fn test_budget_is_leader_decided() {
+        let mut results = vec![
+            MctsResult::from(Board::new(), Move::new()),
+            MctsResult::from(Board::new(), Move::new()),
+        ];
+        for _ in 0..10 {
+            results[0].plus_result(ColorType::Black);
+        }
+        results[1].plus_result(ColorType::White);
+
+        // 10/13 > 3/4 だが 10/14 < 4/5
+        assert!(is_leader_decided(&results, ColorType::Black, 3));
+        assert!(!is_leader_decided(&results, ColorType::Black, 4));
+        // 後手から見ると1局しかない2手目が1位（1/4 > 3/13 だが 1/5 < 4/14）
+        assert!(is_leader_decided(&results, ColorType::White, 3));
+        assert!(!is_leader_decided(&results, ColorType::White, 4));
+    }
+
+    #[test]
+    fn test_budget_is_leader_decided_by_win_rate() {
+        // 1手目は30局で18勝（勝率0.6）、2手目は5局で5勝（勝率1.0）
+        let mut results = vec![
+            MctsResult::from(Board::new(), Move::new()),
+            MctsResult::from(Board::new(), Move::new()),
+        ];
+        for game in 0..30 {
+            let winner = if game < 18 {
+                ColorType::Black
+            } else {
+                ColorType::White
+            };
+            results[0].plus_result(winner);
+        }
+        for _ in 0..5 {
+            results[1].plus_result(ColorType::Black);
+        }
+
+        // 勝ち数の差（13）は残り10回より大きいが、勝率では2手目が上で、まだ逆転しうる
+        assert!(!is_leader_decided(&results, ColorType::Black, 10));
+        assert!(is_leader_decided(&results, ColorType::Black, 0));
+
+        // 引き分けは0.5勝として数える（5/8 < 21.5/34 なので、0勝なら決まってしまう）
+        results[0].plus_result(ColorType::None);
+        assert!(is_leader_decided(&results, ColorType::Black, 1));
+        assert!(!is_leader_decided(&results, ColorType::Black, 3));
+    }
+
+    #[test]
+    fn test_budget_rollout_unbounded() {
+        let game = startpos_game();
+        assert!(game
+            .rollout_with_budget(&SearchBudget::new(), 1, &RolloutConfig::new())
+            .is_err());
+    }
+
+    #[test]
+    fn test_budget_rollout_simulation_limit() {
+        let game = startpos_game();
+        let mut budget = SearchBudget::from_simulations(10);
+        budget.batch_size = 4;
+        let mut config = RolloutConfig::new();
+        config.cutoff = Some(10);
+        let results = game.rollout_with_budget(&budget, 2, &config).unwrap();
+        assert_eq!(results.len(), 30);
+        assert_eq!(total_games(&results), 10);
+    }
+
+    #[test]
+    fn test_budget_rollout_time_limit() {
+        let game = startpos_game();
+        let mut budget = SearchBudget::from_time(200);
+        budget.batch_size = 4;
+        let mut config = RolloutConfig::new();
+        config.cutoff = Some(10);
+
+        let start = Instant::now();
+        let results = game.rollout_with_budget(&budget, 2, &config).unwrap();
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert!(total_games(&results) > 0);
+    }
+
+    #[test]
+    fn test_budget_rollout_stopped_before_start() {
+        let game = startpos_game();
+        let flag = StopFlag::new();
+        flag.stop();
+        let mut budget = SearchBudget::new();
+        budget.stop_flag = Some(flag);
+        let results = game
+            .rollout_with_budget(&budget, 1, &RolloutConfig::new())
+            .unwrap();
+        assert_eq!(total_games(&results), 0);
+    }
+
+    #[test]
+    fn test_budget_rollout_stop_from_other_thread() {
+        let game = startpos_game();
+        let flag = StopFlag::new();
+        let mut budget = SearchBudget::new();
+        budget.stop_flag = Some(flag.clone());
+        budget.batch_size = 4;
+        let mut config = RolloutConfig::new();
+        config.cutoff = Some(10);
+
+        let stopper = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(100));
+            flag.stop();
+        });
+        let start = Instant::now();
+        let results = game.rollout_with_budget(&budget, 2, &config).unwrap();
+        stopper.join().unwrap();
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert!(total_games(&results) > 0);
+    }
+
+    #[test]
+    fn test_budget_rollout_early_stop() {
+        let game = startpos_game();
+        let mut budget = SearchBudget::from_simulations(1000);
+        budget.batch_size = 50;
+        budget.early_stop = true;
+        let mut config = RolloutConfig::new();
+        config.cutoff = Some(4);
+        let results = game.rollout_with_budget(&budget, 2, &config).unwrap();
+        assert!(total_games(&results) <= 1000);
+    }
+}
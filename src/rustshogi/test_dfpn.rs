@@ -0,0 +1,163 @@
+#[cfg(test)]
+
+mod tests {
+    use crate::{
+        address::Address,
+        board::Board,
+        color::ColorType,
+        dfpn::{solve_mate, DfPnSolver, MateStatus},
+        moves::Move,
+        piece::{Piece, PieceType},
+    };
+
+    /// 手順がすべて王手で、最後の局面で受け方に応手がないことを確かめる
+    fn assert_mate_sequence(board: &Board, moves: &[Move]) {
+        let solver = DfPnSolver::new(ColorType::Black, 0);
+        let mut current = board.clone();
+        for (ply, mv) in moves.iter().enumerate() {
+            let candidates = if ply % 2 == 0 {
+                solver.attack_moves(&current)
+            } else {
+                solver.evasion_moves(&current)
+            };
+            assert!(candidates
+                .iter()
+                .any(|candidate| candidate.value == mv.value));
+            current.execute_move(mv);
+        }
+        assert_eq!(moves.len() % 2, 1);
+        assert!(current.is_in_check(ColorType::White));
+        assert!(solver.evasion_moves(&current).is_empty());
+    }
+
+    #[test]
+    fn test_dfpn_mate_in_one() {
+        // 頭金
+        let board = Board::from_sfen("4k4/9/4P4/9/9/9/9/9/9 b G 1".to_string());
+        let result = solve_mate(&board, ColorType::Black, 10000);
+        assert_eq!(result.status, MateStatus::Proven);
+        let mate = Move::from_drop(
+            Piece::from(ColorType::Black, PieceType::Gold),
+            Address::from_numbers(5, 8),
+        );
+        assert_eq!(result.moves.len(), 1);
+        assert_eq!(result.moves[0].value, mate.value);
+        assert_mate_sequence(&board, &result.moves);
+    }
+
+    #[test]
+    fn test_dfpn_mate_sequence() {
+        let board = Board::from_sfen("8k/9/9/9/9/9/9/9/9 b R2G 1".to_string());
+        let result = solve_mate(&board, ColorType::Black, 10000);
+        assert_eq!(result.status, MateStatus::Proven);
+        assert!(result.moves.len() >= 3);
+        assert_mate_sequence(&board, &result.moves);
+    }
+
+    #[test]
+    fn test_dfpn_uchifuzume() {
+        // 歩を打てば詰むが打ち歩詰めなので不詰
+        let board = Board::from_sfen("7lk/7p1/8L/9/9/9/9/9/9 b P 1".to_string());
+        let result = solve_mate(&board, ColorType::Black, 10000);
+        assert_eq!(result.status, MateStatus::Disproven);
+        assert!(result.moves.is_empty());
+
+        // 金なら詰む
+        let board = Board::from_sfen("7lk/7p1/8L/9/9/9/9/9/9 b G 1".to_string());
+        let result = solve_mate(&board, ColorType::Black, 10000);
+        assert_eq!(result.status, MateStatus::Proven);
+        assert_mate_sequence(&board, &result.moves);
+    }
+
+    #[test]
+    fn test_dfpn_disproven() {
+        let board = Board::from_sfen("4k4/9/9/9/9/9/9/9/9 b 2G 1".to_string());
+        assert_eq!(
+            solve_mate(&board, ColorType::Black, 10000).status,
+            MateStatus::Disproven
+        );
+
+        let mut startpos = Board::new();
+        startpos.startpos();
+        assert_eq!(
+            solve_mate(&startpos, ColorType::Black, 10000).status,
+            MateStatus::Disproven
+        );
+    }
+
+    #[test]
+    fn test_dfpn_node_limit() {
+        let board = Board::from_sfen("8k/9/9/9/9/9/9/9/9 b RG 1".to_string());
+        let mut solver = DfPnSolver::new(ColorType::Black, 50);
+        let result = solver.solve(&board);
+        assert_eq!(result.status, MateStatus::Unknown);
+        assert!(result.moves.is_empty());
+        assert!(result.nodes <= 60);
+    }
+
+    #[test]
+    fn test_dfpn_white_attacker() {
+        // 先後を入れ替えた頭金
+        let board = Board::from_sfen("9/9/9/9/9/9/4p4/9/4K4 w g 1".to_string());
+        let result = solve_mate(&board, ColorType::White, 10000);
+        assert_eq!(result.status, MateStatus::Proven);
+        assert_eq!(result.moves.len(), 1);
+    }
+
+    #[test]
+    fn test_dfpn_ikken_ryu() {
+        // 一間竜: 竜を1三に寄れば2一は歩、1二と2二は竜が押さえている
+        let board = Board::from_sfen("8k/7P1/9/9/8+R/9/9/9/9 b - 1".to_string());
+        let result = solve_mate(&board, ColorType::Black, 10000);
+        assert_eq!(result.status, MateStatus::Proven);
+        let mate = Move::from_standart(
+            Address::from_numbers(9, 5),
+            Address::from_numbers(9, 7),
+            false,
+        );
+        assert_eq!(result.moves.len(), 1);
+        assert_eq!(result.moves[0].value, mate.value);
+        assert_mate_sequence(&board, &result.moves);
+    }
+
+    #[test]
+    fn test_dfpn_sacrifice_mate_in_three() {
+        // 香を成り捨てて玉を2一に呼び、桂の利いた2二に金を打つ
+        let board = Board::from_sfen("7sk/8p/9/6N2/7L1/9/9/9/9 b 2G 1".to_string());
+        let result = solve_mate(&board, ColorType::Black, 10000);
+        assert_eq!(result.status, MateStatus::Proven);
+        assert_eq!(result.moves.len(), 3);
+        assert_eq!(
+            result.moves[0].get_to().to_index(),
+            result.moves[1].get_to().to_index()
+        );
+        assert_mate_sequence(&board, &result.moves);
+
+        // 金を1二に捨てて銀を2一から動かし、2二に銀を打つ
+        let board = Board::from_sfen("7sk/5GR2/9/9/9/9/9/9/9 b SG 1".to_string());
+        let result = solve_mate(&board, ColorType::Black, 10000);
+        assert_eq!(result.status, MateStatus::Proven);
+        assert_eq!(result.moves.len(), 3);
+        assert_mate_sequence(&board, &result.moves);
+    }
+
+    #[test]
+    fn test_dfpn_two_knights_mate_in_five() {
+        // 歩を2一に成り捨てたあと、2枚の桂で詰ます
+        let board = Board::from_sfen("5p2k/4R2P1/5s3/9/9/9/9/9/9 b 2N 1".to_string());
+        let result = solve_mate(&board, ColorType::Black, 10000);
+        assert_eq!(result.status, MateStatus::Proven);
+        assert_eq!(result.moves.len(), 5);
+        assert_mate_sequence(&board, &result.moves);
+    }
+
+    #[test]
+    fn test_dfpn_perpetual_check_disproven() {
+        // 飛車1枚の王手は千日手になるだけで詰まない
+        let board = Board::from_sfen("7lk/9/9/9/9/9/9/9/9 b R 1".to_string());
+        assert_eq!(
+            solve_mate(&board, ColorType::Black, 10000).status,
+            MateStatus::Disproven
+        );
+    }
+}
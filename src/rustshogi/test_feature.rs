@@ -0,0 +1,253 @@
+#[cfg(test)]
+
+mod tests {
+    use crate::{
+        address::Address,
+        board::{Board, BoardSource},
+        feature::{FeatureExtractor, FeatureNormalization, FeaturePlane},
+        pca::{learn_simple_pca, PCASolver},
+    };
+    use std::sync::Arc;
+
+    const SFENS: [&str; 4] = [
+        "lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL b - 1",
+        "4k4/9/4+p4/4R4/9/9/9/9/4K4 b 2Pg 1",
+        "8l/1l+R2P3/p2pBG1pp/kps1p4/Nn1P2G2/P1P1P2PP/1PS6/1KSG3+r1/LN2+p3L w Sbgn3p 124",
+        "4k4/9/9/9/9/9/9/9/4K4 w 9Pr 1",
+    ];
+
+    fn boards() -> Vec<Board> {
+        SFENS
+            .iter()
+            .map(|sfen| Board::from_sfen(sfen.to_string()))
+            .collect()
+    }
+
+    /// 既定の18枚の平面のうち、指定した平面だけが1のマスの特徴量
+    fn square_features(planes: &[FeaturePlane]) -> Vec<f32> {
+        let mut features = vec![0.0; 18];
+        for plane in planes {
+            features[*plane as usize] = 1.0;
+        }
+        features
+    }
+
+    #[test]
+    fn test_default_features() {
+        let extractor = FeatureExtractor::default();
+        assert_eq!(extractor.dimensions(), 2320);
+
+        let features = extractor.extract(&boards()[0]).unwrap();
+        assert_eq!(features.len(), 2320);
+        let square = |column: u8, row: u8| {
+            let index = Address::from_numbers(column, row).to_index() as usize;
+            &features[index * 18..(index + 1) * 18]
+        };
+        assert_eq!(
+            square(5, 1),
+            square_features(&[
+                FeaturePlane::Occupied,
+                FeaturePlane::Black,
+                FeaturePlane::King
+            ])
+        );
+        assert_eq!(
+            square(2, 8),
+            square_features(&[
+                FeaturePlane::Occupied,
+                FeaturePlane::White,
+                FeaturePlane::Rook
+            ])
+        );
+        assert_eq!(square(5, 5), square_features(&[FeaturePlane::Empty]));
+        // 盤の枠のマスはどの平面も0
+        assert_eq!(&features[..18], square_features(&[]).as_slice());
+
+        let plane_count = |plane: FeaturePlane| -> f32 {
+            (0..128)
+                .map(|index| features[index * 18 + plane as usize])
+                .sum()
+        };
+        assert_eq!(plane_count(FeaturePlane::Occupied), 40.0);
+        assert_eq!(plane_count(FeaturePlane::Black), 20.0);
+        assert_eq!(plane_count(FeaturePlane::White), 20.0);
+        assert_eq!(plane_count(FeaturePlane::Empty), 41.0);
+        assert_eq!(plane_count(FeaturePlane::Pawn), 18.0);
+        assert_eq!(plane_count(FeaturePlane::ProPawn), 0.0);
+        assert!(features[2304..].iter().all(|value| *value == 0.0));
+
+        // 持ち駒は先手、後手の順に玉・金・飛・角・銀・桂・香・歩の枚数
+        let features = extractor.extract(&boards()[3]).unwrap();
+        assert_eq!(
+            &features[2304..],
+            &[0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 9.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0]
+        );
+
+        // 成駒は成駒の平面に立つ
+        let features = extractor.extract(&boards()[1]).unwrap();
+        let index = Address::from_numbers(5, 7).to_index() as usize;
+        assert_eq!(
+            &features[index * 18..(index + 1) * 18],
+            square_features(&[
+                FeaturePlane::Occupied,
+                FeaturePlane::White,
+                FeaturePlane::ProPawn
+            ])
+        );
+    }
+
+    #[test]
+    fn test_plane_selection() {
+        let extractor = FeatureExtractor::new(
+            vec![FeaturePlane::Black, FeaturePlane::King],
+            false,
+            FeatureNormalization::Raw,
+            None,
+        )
+        .unwrap();
+        assert_eq!(extractor.dimensions(), 256);
+        for board in boards() {
+            let all = board.to_vector(None).unwrap();
+            let selected = extractor.extract(&board).unwrap();
+            for (square, values) in selected.chunks(2).enumerate() {
+                assert_eq!(values, &[all[square * 18 + 2], all[square * 18 + 4]]);
+            }
+        }
+
+        assert!(FeatureExtractor::new(Vec::new(), false, FeatureNormalization::Raw, None).is_err());
+    }
+
+    #[test]
+    fn test_hand_ratio() {
+        let extractor =
+            FeatureExtractor::new(Vec::new(), true, FeatureNormalization::HandRatio, None).unwrap();
+        let features = extractor.extract(&boards()[3]).unwrap();
+        assert_eq!(features.len(), 16);
+        // 先手の歩9枚と後手の飛車1枚
+        assert_eq!(features[7], 0.5);
+        assert_eq!(features[10], 0.5);
+        assert_eq!(features.iter().filter(|value| **value != 0.0).count(), 2);
+    }
+
+    #[test]
+    fn test_compression_without_global() {
+        let samples: Vec<Vec<f32>> = boards()
+            .iter()
+            .map(|board| board.to_vector(None).unwrap())
+            .collect();
+        let extractors: Vec<FeatureExtractor> = [2, 3]
+            .iter()
+            .map(|&n| {
+                FeatureExtractor::default()
+                    .with_transform(Arc::new(learn_simple_pca(&samples, n)))
+                    .unwrap()
+            })
+            .collect();
+
+        // 次元数の違う抽出器を別々のスレッドで同時に使う
+        std::thread::scope(|scope| {
+            for extractor in &extractors {
+                let samples = &samples;
+                scope.spawn(move || {
+                    let transform = extractor.transform.as_ref().unwrap();
+                    for (board, sample) in boards().iter().zip(samples) {
+                        let features = extractor.extract(board).unwrap();
+                        assert_eq!(features.len(), transform.n_components);
+                        assert_eq!(features, transform.transform(sample).unwrap());
+                    }
+                });
+            }
+        });
+
+        // グローバルなPCA変換には影響しない
+        assert!(boards()[0].to_vector(Some(3)).is_err());
+
+        let small = learn_simple_pca(&[vec![0.0; 16], vec![1.0; 16]], 2);
+        assert!(FeatureExtractor::default()
+            .with_transform(Arc::new(small.clone()))
+            .is_err());
+        assert!(FeatureExtractor::new(
+            Vec::new(),
+            true,
+            FeatureNormalization::Raw,
+            Some(Arc::new(small))
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_normalization() {
+        let l2 = FeatureExtractor::new(
+            vec![FeaturePlane::Occupied],
+            true,
+            FeatureNormalization::L2,
+            None,
+        )
+        .unwrap();
+        for board in boards() {
+            let features = l2.extract(&board).unwrap();
+            let norm: f32 = features.iter().map(|value| value * value).sum();
+            assert!((norm - 1.0).abs() < 1e-5);
+        }
+
+        assert!(FeatureExtractor::new(
+            vec![FeaturePlane::Occupied],
+            true,
+            FeatureNormalization::Whiten,
+            None
+        )
+        .is_err());
+
+        let raw = FeatureExtractor::new(Vec::new(), true, FeatureNormalization::Raw, None).unwrap();
+        let samples: Vec<Vec<f32>> = boards()
+            .iter()
+            .map(|board| raw.extract(board).unwrap())
+            .collect();
+        let transform = Arc::new(learn_simple_pca(&samples, 2));
+        let whiten = FeatureExtractor::new(
+            Vec::new(),
+            true,
+            FeatureNormalization::Whiten,
+            Some(transform.clone()),
+        )
+        .unwrap();
+        for (board, sample) in boards().iter().zip(&samples) {
+            let expected = transform.transform(sample).unwrap();
+            let features = whiten.extract(board).unwrap();
+            for i in 0..2 {
+                let variance = transform.explained_variance[i];
+                assert!((features[i] - expected[i] / variance.sqrt()).abs() < 1e-5);
+            }
+        }
+    }
+
+    #[test]
+    fn test_fit_and_extract_batch() {
+        let sources: Vec<BoardSource> = SFENS
+            .iter()
+            .map(|sfen| BoardSource::from(sfen.to_string()))
+            .collect();
+        let extractor = FeatureExtractor::new(
+            vec![FeaturePlane::Black, FeaturePlane::White, FeaturePlane::Pawn],
+            true,
+            FeatureNormalization::HandRatio,
+            None,
+        )
+        .unwrap();
+        assert_eq!(extractor.raw_dimensions(), 400);
+
+        let fitted = extractor
+            .clone()
+            .fit(&sources, 2, PCASolver::Full, 2, 2)
+            .unwrap();
+        assert_eq!(fitted.dimensions(), 2);
+        assert_eq!(fitted.transform.as_ref().unwrap().n_features(), 400);
+
+        let batch = fitted.extract_batch(&sources, 2).unwrap();
+        assert_eq!(batch.len(), 2 * SFENS.len());
+        for (row, board) in batch.chunks(2).zip(boards()) {
+            assert_eq!(row, fitted.extract(&board).unwrap().as_slice());
+        }
+        assert!(extractor.extract_batch(&[], 2).unwrap().is_empty());
+    }
+}
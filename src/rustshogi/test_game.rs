@@ -1,7 +1,14 @@
 #[cfg(test)]
 
 mod tests {
-    use crate::{board::Board, color::ColorType, game::Game};
+    use crate::{
+        address::Address,
+        board::Board,
+        color::ColorType,
+        game::Game,
+        moves::Move,
+        rollout::{RolloutConfig, RolloutPolicyType, UniformPolicy},
+    };
 
     #[test]
     fn test_game_startpos() {
@@ -59,6 +66,10 @@ mod tests {
         for result in &results {
             // 白と黒の勝利数の合計が総ゲーム数以下であることを確認
             assert!(result.white_wins + result.black_wins <= result.total_games);
+            assert_eq!(
+                result.white_wins + result.black_wins + result.draws,
+                result.total_games
+            );
         }
 
         // 結果の数が可能な手の数と一致することを確認
@@ -74,4 +85,67 @@ mod tests {
 
         assert!(board.is_finished().0 || !board.is_finished().0);
     }
+
+    #[test]
+    fn test_game_playout_cutoff() {
+        let mut game = Game::new();
+        game.input_board("startpos".to_string());
+        game.playout(&UniformPolicy, Some(10), 0);
+        assert!(game.move_number <= 11);
+    }
+
+    #[test]
+    fn test_game_rollout_parallel_with_config() {
+        let mut game = Game::new();
+        game.input_board("startpos".to_string());
+        let mut config = RolloutConfig::new();
+        config.policy = RolloutPolicyType::Softmax;
+        config.cutoff = Some(20);
+        let results = game.rollout_parallel(8, 2, &config);
+        let total_games: u64 = results.iter().map(|r| r.total_games).sum();
+        assert_eq!(total_games, 8);
+    }
+
+    #[test]
+    fn test_game_null_move() {
+        let mut game = Game::new();
+        game.board.startpos();
+        let original = game.clone();
+        let key = game.hash_key();
+
+        let extended = game.make_null_move();
+        assert!(extended.to_move().get_is_pass());
+        assert_eq!(game.turn, ColorType::White);
+        assert_eq!(game.move_number, 2);
+        assert_eq!(game.board, original.board);
+        assert_ne!(game.hash_key(), key);
+
+        game.undo_null_move();
+        assert_eq!(game.turn, ColorType::Black);
+        assert_eq!(game.move_number, 1);
+        assert_eq!(game.hash_key(), key);
+
+        // パスを含む手順を指して戻すと元の局面に戻る
+        let moves = [
+            Move::from_standart(
+                Address::from_numbers(7, 3),
+                Address::from_numbers(7, 4),
+                false,
+            ),
+            Move::pass(),
+            Move::from_standart(
+                Address::from_numbers(2, 3),
+                Address::from_numbers(2, 4),
+                false,
+            ),
+        ];
+        let history: Vec<_> = moves.iter().map(|mv| game.make_move(mv)).collect();
+        assert_eq!(game.turn, ColorType::White);
+        for extended in history.iter().rev() {
+            game.undo_move(extended);
+        }
+        assert_eq!(game.board, original.board);
+        assert_eq!(game.turn, original.turn);
+        assert_eq!(game.move_number, original.move_number);
+    }
 }
@@ -0,0 +1,90 @@
+#[cfg(test)]
+
+mod tests {
+    use crate::{
+        board::Board,
+        color::ColorType,
+        game::Game,
+        handicap::Handicap,
+        piece::{Piece, PieceType},
+    };
+
+    fn count_pieces(board: &Board, color: ColorType) -> usize {
+        board.get_piece_list(color).len()
+    }
+
+    #[test]
+    fn test_handicap_from_name() {
+        assert_eq!(Handicap::from_name("2-piece"), Some(Handicap::TwoPiece));
+        assert_eq!(Handicap::from_name("Rook_Lance"), Some(Handicap::RookLance));
+        assert_eq!(Handicap::from_name("二枚落ち"), Some(Handicap::TwoPiece));
+        assert_eq!(Handicap::from_name("飛香落ち"), Some(Handicap::RookLance));
+        assert_eq!(Handicap::from_name("3-piece"), None);
+        for handicap in Handicap::ALL {
+            assert_eq!(Handicap::from_name(handicap.get_name()), Some(handicap));
+            assert_eq!(Handicap::from_name(handicap.kif_name()), Some(handicap));
+        }
+    }
+
+    #[test]
+    fn test_handicap_positions() {
+        let removed = [0, 1, 1, 1, 2, 2, 4, 6, 8, 10];
+        for (handicap, removed) in Handicap::ALL.into_iter().zip(removed) {
+            let board = Board::from_handicap(handicap.get_name()).unwrap();
+            assert!(board.is_consistent());
+            assert_eq!(count_pieces(&board, ColorType::Black), 20);
+            assert_eq!(
+                count_pieces(&board, ColorType::White),
+                20 - removed,
+                "{}",
+                handicap.get_name()
+            );
+            assert!(board.get_king_index(ColorType::White).is_some());
+        }
+
+        let mut even = Board::new();
+        even.startpos();
+        assert_eq!(Board::from_handicap("even").unwrap(), even);
+
+        // 二枚落ちは飛車と角だけがない
+        let board = Board::from_handicap("2-piece").unwrap();
+        let white_pieces: Vec<Piece> = board
+            .get_piece_list(ColorType::White)
+            .iter()
+            .map(|index| board.get_piece(*index))
+            .collect();
+        assert!(!white_pieces
+            .iter()
+            .any(|piece| piece.piece_type == PieceType::Rook
+                || piece.piece_type == PieceType::Bichop));
+
+        assert!(Board::from_handicap("unknown").is_err());
+    }
+
+    #[test]
+    fn test_handicap_game_starts_with_white() {
+        let game = Game::from_handicap("bishop").unwrap();
+        assert_eq!(game.turn, ColorType::White);
+        assert_eq!(game.move_number, 1);
+        assert!(!game.board.search_moves(game.turn).is_empty());
+
+        let game = Game::from_handicap("even").unwrap();
+        assert_eq!(game.turn, ColorType::Black);
+
+        let game = Game::from_sfen(&Handicap::TenPiece.sfen());
+        assert_eq!(game.turn, ColorType::White);
+        assert_eq!(count_pieces(&game.board, ColorType::White), 10);
+    }
+
+    #[test]
+    fn test_handicap_from_kif_header() {
+        let kif = "# ---- Kifu for Windows ----\n開始日時：2024/01/01\n手合割：四枚落ち\n先手：下手\n後手：上手\n";
+        assert_eq!(Handicap::from_kif_header(kif), Ok(Handicap::FourPiece));
+        let game = Game::from_kif_header(kif).unwrap();
+        assert_eq!(game.turn, ColorType::White);
+        assert_eq!(count_pieces(&game.board, ColorType::White), 16);
+
+        assert_eq!(Handicap::from_kif_header("先手：A\n"), Ok(Handicap::Even));
+        assert!(Handicap::from_kif_header("手合割：その他").is_err());
+    }
+}
@@ -0,0 +1,77 @@
+#[cfg(test)]
+
+mod tests {
+    use crate::{color::ColorType, mctsresult::MctsResult};
+
+    fn sample_result() -> MctsResult {
+        let mut result = MctsResult::new();
+        for _ in 0..6 {
+            result.plus_simulation(ColorType::Black, 100, 1.0);
+        }
+        for _ in 0..2 {
+            result.plus_simulation(ColorType::White, 50, 0.0);
+        }
+        for _ in 0..2 {
+            result.plus_simulation(ColorType::None, 500, 0.5);
+        }
+        result
+    }
+
+    #[test]
+    fn test_mctsresult_plus_result_counts_draws() {
+        let mut result = MctsResult::new();
+        result.plus_result(ColorType::Black);
+        result.plus_result(ColorType::None);
+        assert_eq!(result.black_wins, 1);
+        assert_eq!(result.draws, 1);
+        assert_eq!(result.total_games, 2);
+        assert_eq!(result.value_sum, 1.5);
+    }
+
+    #[test]
+    fn test_mctsresult_average_game_length() {
+        let result = sample_result();
+        assert_eq!(result.average_game_length(), 1700.0 / 10.0);
+    }
+
+    #[test]
+    fn test_mctsresult_mean_value_and_variance() {
+        let result = sample_result();
+        assert!((result.mean_value(ColorType::Black) - 0.7).abs() < 1e-9);
+        assert!((result.mean_value(ColorType::White) - 0.3).abs() < 1e-9);
+        // (6 * 0.09 + 2 * 0.49 + 2 * 0.04) / 9
+        assert!((result.value_variance() - 1.6 / 9.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_mctsresult_wilson_interval() {
+        let result = sample_result();
+        let (low, high) = result.wilson_interval(ColorType::Black, 1.96);
+        assert!(low < 0.7 && 0.7 < high);
+        assert!(low > 0.3 && high < 1.0);
+
+        let mut larger = MctsResult::new();
+        for _ in 0..100 {
+            larger.merge(&result);
+        }
+        let (larger_low, larger_high) = larger.wilson_interval(ColorType::Black, 1.96);
+        assert!(larger_high - larger_low < high - low);
+    }
+
+    #[test]
+    fn test_mctsresult_ucb() {
+        let result = sample_result();
+        let empty = MctsResult::new();
+        assert_eq!(empty.ucb(ColorType::Black, 10, 1.0), f64::INFINITY);
+        assert!(result.ucb(ColorType::Black, 20, 1.0) > result.mean_value(ColorType::Black));
+    }
+
+    #[test]
+    fn test_mctsresult_merge() {
+        let mut result = sample_result();
+        result.merge(&sample_result());
+        assert_eq!(result.total_games, 20);
+        assert_eq!(result.draws, 4);
+        assert_eq!(result.total_moves, 3400);
+    }
+}
@@ -0,0 +1,95 @@
+#[cfg(test)]
+
+mod tests {
+    use crate::{
+        address::{index_to_square, Address},
+        board::Board,
+        color::ColorType,
+        move_label::{legal_move_mask, MOVE_LABEL_NUMBER},
+        moves::Move,
+        piece::{Piece, PieceType},
+    };
+    use std::collections::HashSet;
+
+    const SAMPLE_SFENS: [&str; 4] = [
+        "lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL b - 1",
+        "ln1g1g1nl/1ks2r3/1pppp1bpp/p3spp2/9/P1P1SP1PP/1PBPP1P2/2GS3R1/LN2KG1NL b Pp 1",
+        "4k4/9/3+Pp4/4L4/9/2B6/9/9/4K4 b GSPrn2p 1",
+        "l6nl/5+P1gk/2np1S3/p1p4Pp/3P2Sp1/1PPb2P1P/P5GS1/R8/LN4bKL w RGgsn5p 1",
+    ];
+
+    fn legal_moves(board: &Board, turn: ColorType) -> Vec<Move> {
+        board
+            .search_moves(turn)
+            .into_iter()
+            .filter(|mv| board.is_legal(mv))
+            .collect()
+    }
+
+    #[test]
+    fn test_move_label_round_trip() {
+        for sfen in SAMPLE_SFENS {
+            let board = Board::from_sfen(sfen.to_string());
+            for turn in [ColorType::Black, ColorType::White] {
+                let moves = legal_moves(&board, turn);
+                let mut labels = HashSet::new();
+                for mv in &moves {
+                    let label = mv.to_label(turn).unwrap();
+                    assert!(label < MOVE_LABEL_NUMBER);
+                    // 同じ局面の別の手が同じラベルになることはない
+                    assert!(labels.insert(label), "{} {}", sfen, mv);
+                    let decoded = Move::from_label(label, &board, turn).unwrap();
+                    assert_eq!(decoded.value, mv.value, "{} {}", sfen, mv);
+                }
+
+                let mask = legal_move_mask(&board, turn);
+                assert_eq!(mask.len(), MOVE_LABEL_NUMBER);
+                assert_eq!(mask.iter().filter(|legal| **legal).count(), moves.len());
+                assert!(labels.iter().all(|label| mask[*label]));
+            }
+        }
+    }
+
+    #[test]
+    fn test_move_label_orientation() {
+        let mut board = Board::new();
+        board.startpos();
+        // 先手の歩を1つ進める手と、それを180度回した後手の手は同じラベル
+        let black = Move::from_standart(
+            Address::from_numbers(7, 3),
+            Address::from_numbers(7, 4),
+            false,
+        );
+        let white = Move::from_standart(
+            Address::from_numbers(3, 7),
+            Address::from_numbers(3, 6),
+            false,
+        );
+        assert_eq!(
+            black.to_label(ColorType::Black),
+            white.to_label(ColorType::White)
+        );
+        // 前へ1マスは方向0の平面
+        assert!(black.to_label(ColorType::Black).unwrap() < 81);
+
+        let drop = Move::from_drop(
+            Piece::from(ColorType::Black, PieceType::Rook),
+            Address::from_numbers(5, 5),
+        );
+        assert_eq!(drop.to_label(ColorType::Black).unwrap() / 81, 26);
+        assert_eq!(Move::pass().to_label(ColorType::Black), None);
+    }
+
+    #[test]
+    fn test_move_label_invalid() {
+        let mut board = Board::new();
+        board.startpos();
+        assert!(Move::from_label(MOVE_LABEL_NUMBER, &board, ColorType::Black).is_err());
+        // 5筋の歩は2マス先の5五へは動けない
+        let square = index_to_square(Address::from_numbers(5, 5).to_index()).unwrap() as usize;
+        assert!(Move::from_label(square, &board, ColorType::Black).is_err());
+        // 5四へなら動ける
+        let square = index_to_square(Address::from_numbers(5, 4).to_index()).unwrap() as usize;
+        assert!(Move::from_label(square, &board, ColorType::Black).is_ok());
+    }
+}
@@ -0,0 +1,74 @@
+#[cfg(test)]
+
+mod tests {
+    use crate::{
+        address::Address,
+        board::Board,
+        color::ColorType,
+        move_list::{MoveList, MAX_MOVES},
+        moves::Move,
+    };
+
+    #[test]
+    fn test_move_list_push_and_slice() {
+        let mut moves = MoveList::new();
+        assert!(moves.is_empty());
+        let mv = Move::from_standart(
+            Address::from_numbers(7, 3),
+            Address::from_numbers(7, 4),
+            false,
+        );
+        moves.push(mv);
+        moves.push(Move::new());
+        assert_eq!(moves.len(), 2);
+        assert_eq!(moves[0].value, mv.value);
+        assert_eq!(moves.iter().filter(|mv| mv.value == 0).count(), 1);
+
+        moves.clear();
+        assert!(moves.is_empty());
+        moves.extend((0..MAX_MOVES).map(|_| mv));
+        assert_eq!(moves.len(), MAX_MOVES);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_move_list_overflow() {
+        let mut moves = MoveList::new();
+        moves.extend((0..=MAX_MOVES).map(|_| Move::new()));
+    }
+
+    #[test]
+    fn test_board_search_moves_into() {
+        let sfens = [
+            "lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL b - 1",
+            "4k4/9/3+Pp4/4L4/9/2B6/9/9/4K4 b GSNPrn2p 1",
+        ];
+        let mut moves = MoveList::new();
+        for sfen in sfens {
+            let board = Board::from_sfen(sfen.to_string());
+            for color in [ColorType::Black, ColorType::White] {
+                board.search_moves_into(color, &mut moves);
+                let expected: Vec<u16> = board
+                    .search_moves(color)
+                    .iter()
+                    .map(|mv| mv.value)
+                    .collect();
+                let values: Vec<u16> = moves.iter().map(|mv| mv.value).collect();
+                assert_eq!(values, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_board_search_moves_more_than_legal_maximum() {
+        // 持ち駒7種と飛車・角・銀の成り不成で、疑似合法手が合法手の最大593手を超える
+        // （`MAX_MOVES` を593にするとここで溢れる）
+        let board =
+            Board::from_sfen("R8/2K1S1SSk/4B4/9/9/9/9/9/1L1L1L3 b RBGSNLP3g3n17p3l 1".to_string());
+        let mut moves = MoveList::new();
+        board.search_moves_into(ColorType::Black, &mut moves);
+        assert_eq!(moves.len(), 596);
+        assert!(moves.len() > 593 && moves.len() <= MAX_MOVES);
+        assert_eq!(moves.len(), board.search_moves(ColorType::Black).len());
+    }
+}
@@ -0,0 +1,196 @@
+#[cfg(test)]
+
+mod tests {
+    use crate::{
+        address::Address,
+        board::Board,
+        color::{get_reverse_color, ColorType},
+        move_picker::{HistoryTable, KillerTable, MovePicker, Stage},
+        moves::Move,
+        piece::PieceType,
+    };
+
+    const POSITIONS: [&str; 3] = [
+        "lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL b - 1",
+        "lnsgk2nl/1r4gs1/p1pppp1pp/1p4p2/7P1/2P6/PP1PPPP1P/1SG4R1/LN2KGSNL b Bb 1",
+        "4k4/9/3+Pp4/4L4/9/2B6/9/9/4K4 b GSPrn2p 1",
+    ];
+
+    fn index(column: u8, row: u8) -> u8 {
+        Address::from_numbers(column, row).to_index()
+    }
+
+    fn sorted_values(moves: &[Move]) -> Vec<u16> {
+        let mut values: Vec<u16> = moves.iter().map(|mv| mv.value).collect();
+        values.sort();
+        values
+    }
+
+    fn is_capture(board: &Board, mv: &Move) -> bool {
+        !mv.get_is_drop()
+            && board.get_piece_type_from_index(mv.get_to().to_index()) != PieceType::None
+    }
+
+    #[test]
+    fn test_move_picker_same_moves_as_search_moves() {
+        for sfen in POSITIONS {
+            let board = Board::from_sfen(sfen.to_string());
+            for color in [ColorType::Black, ColorType::White] {
+                let picked: Vec<Move> = MovePicker::new(&board, color).collect();
+                assert_eq!(
+                    sorted_values(&picked),
+                    sorted_values(&board.search_moves(color)),
+                    "{} {:?}",
+                    sfen,
+                    color
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_move_picker_stage_order() {
+        for sfen in POSITIONS {
+            let board = Board::from_sfen(sfen.to_string());
+            let color = ColorType::Black;
+            let mut picker = MovePicker::new(&board, color);
+            let mut previous = Stage::Captures;
+            while let Some(mv) = picker.next() {
+                let stage = picker.stage();
+                assert!(stage as u8 >= previous as u8);
+                previous = stage;
+                match stage {
+                    Stage::Captures => assert!(is_capture(&board, &mv) || mv.get_is_promote()),
+                    Stage::Checks => {
+                        assert!(!is_capture(&board, &mv) && !mv.get_is_promote());
+                        let mut next = board.clone();
+                        next.execute_move(&mv);
+                        assert!(next.is_in_check(get_reverse_color(color)));
+                    }
+                    Stage::Quiets => {
+                        assert!(!mv.get_is_drop() && !is_capture(&board, &mv));
+                        assert!(!board.gives_check(&mv));
+                    }
+                    Stage::Drops => {
+                        assert!(mv.get_is_drop());
+                        assert!(!board.gives_check(&mv));
+                    }
+                    Stage::Done => unreachable!(),
+                }
+            }
+            assert_eq!(picker.stage(), Stage::Done);
+        }
+    }
+
+    #[test]
+    fn test_move_picker_mvv_lva() {
+        let mut board = Board::new();
+        board.deploy(index(5, 4), PieceType::Pawn, ColorType::Black);
+        board.deploy(index(6, 4), PieceType::Silver, ColorType::Black);
+        board.deploy(index(4, 4), PieceType::Pawn, ColorType::Black);
+        board.deploy(index(5, 5), PieceType::Rook, ColorType::White);
+        board.deploy(index(6, 5), PieceType::Gold, ColorType::White);
+        board.deploy(index(4, 5), PieceType::Pawn, ColorType::White);
+
+        let captures: Vec<Move> = MovePicker::new(&board, ColorType::Black)
+            .take_while(|mv| is_capture(&board, mv) || mv.get_is_promote())
+            .collect();
+        let targets: Vec<(u8, PieceType)> = captures
+            .iter()
+            .map(|mv| {
+                (
+                    mv.get_to().to_index(),
+                    board.get_piece_type_from_index(mv.get_from().to_index()),
+                )
+            })
+            .collect();
+        // 飛車を歩で取る手、飛車を銀で取る手、金を取る手、歩を取る手の順
+        assert_eq!(targets[0], (index(5, 5), PieceType::Pawn));
+        assert_eq!(targets[1], (index(5, 5), PieceType::Silver));
+        assert_eq!(targets[2], (index(6, 5), PieceType::Silver));
+        assert_eq!(targets.len(), 4);
+        assert_eq!(targets[3], (index(4, 5), PieceType::Pawn));
+    }
+
+    #[test]
+    fn test_move_picker_killer_and_history() {
+        let board = Board::from_sfen(POSITIONS[0].to_string());
+        let killer = Move::from_standart(
+            Address::from_numbers(1, 1),
+            Address::from_numbers(1, 2),
+            false,
+        );
+        let mut killers = KillerTable::new(8);
+        killers.store(3, &killer);
+        assert_eq!(killers.rank(3, &killer), Some(0));
+        assert_eq!(killers.rank(2, &killer), None);
+
+        let first = MovePicker::new(&board, ColorType::Black)
+            .with_killers(&killers, 3)
+            .next()
+            .unwrap();
+        assert_eq!(first.value, killer.value);
+
+        let favorite = Move::from_standart(
+            Address::from_numbers(2, 3),
+            Address::from_numbers(2, 4),
+            false,
+        );
+        let mut history = HistoryTable::new();
+        history.update(ColorType::Black, &favorite, 4);
+        assert_eq!(history.get(ColorType::Black, &favorite), 16);
+        assert_eq!(history.get(ColorType::White, &favorite), 0);
+
+        let first = MovePicker::new(&board, ColorType::Black)
+            .with_history(&history)
+            .next()
+            .unwrap();
+        assert_eq!(first.value, favorite.value);
+    }
+
+    #[test]
+    fn test_board_gives_check_matches_execute_move() {
+        for sfen in POSITIONS {
+            let board = Board::from_sfen(sfen.to_string());
+            for color in [ColorType::Black, ColorType::White] {
+                let enemy = get_reverse_color(color);
+                let king_index = board.get_king_index(enemy).unwrap();
+                for mv in board.search_moves(color) {
+                    if !mv.get_is_drop() && mv.get_to().to_index() == king_index {
+                        continue;
+                    }
+                    let mut next = board.clone();
+                    next.execute_move(&mv);
+                    assert_eq!(
+                        board.gives_check(&mv),
+                        next.is_in_check(enemy),
+                        "{} {}",
+                        sfen,
+                        mv.to_string()
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_board_gives_check_discovered() {
+        let mut board = Board::new();
+        board.deploy(index(5, 9), PieceType::King, ColorType::White);
+        board.deploy(index(5, 5), PieceType::Silver, ColorType::Black);
+        board.deploy(index(5, 1), PieceType::Lance, ColorType::Black);
+        // 銀が斜めに退くと香車の利きが通る
+        let mv = Move::from_standart(
+            Address::from_numbers(5, 5),
+            Address::from_numbers(4, 6),
+            false,
+        );
+        assert!(board.gives_check(&mv));
+        let mv = Move::from_standart(
+            Address::from_numbers(5, 5),
+            Address::from_numbers(5, 6),
+            false,
+        );
+        assert!(!board.gives_check(&mv));
+    }
+}
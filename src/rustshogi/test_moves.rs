@@ -5,8 +5,9 @@ mod tests {
 
     use crate::{
         address::Address,
+        board::Board,
         color::ColorType,
-        moves::Move,
+        moves::{ExtendedMove, Move},
         piece::{Piece, PieceType},
     };
 
@@ -94,4 +95,54 @@ mod tests {
         let str = mv.to_string();
         assert_eq!(str, csa);
     }
+
+    #[test]
+    fn test_extended_move_from_move() {
+        let board = Board::from_sfen("4k4/9/4p4/4R4/9/9/9/9/4K4 b G 1".to_string());
+        let capture = Move::from_standart(
+            Address::from_numbers(5, 6),
+            Address::from_numbers(5, 7),
+            true,
+        );
+        let extended = ExtendedMove::from_move(&capture, &board);
+        assert_eq!(extended.to_move().value, capture.value);
+        assert_eq!(
+            extended.get_moved_piece(),
+            Piece::from(ColorType::Black, PieceType::Rook)
+        );
+        assert_eq!(
+            extended.get_captured_piece(),
+            Piece::from(ColorType::White, PieceType::Pawn)
+        );
+        assert!(extended.get_is_capture());
+        assert!(extended.get_is_check());
+        assert!(extended.get_is_promote());
+        assert_eq!(extended.to_string(), capture.to_string());
+
+        let drop = Move::from_drop(
+            Piece::from(ColorType::Black, PieceType::Gold),
+            Address::from_numbers(1, 1),
+        );
+        let extended = ExtendedMove::from_move(&drop, &board);
+        assert!(extended.get_is_drop());
+        assert!(!extended.get_is_capture());
+        assert!(!extended.get_is_check());
+        assert_eq!(extended.get_captured_piece().piece_type, PieceType::None);
+        assert_eq!(
+            extended.get_moved_piece(),
+            Piece::from(ColorType::Black, PieceType::Gold)
+        );
+        assert_eq!(Move::from(extended).value, drop.value);
+    }
+
+    #[test]
+    fn test_moves_pass() {
+        let pass = Move::pass();
+        assert!(pass.get_is_pass());
+        assert!(!pass.get_is_drop());
+        assert_eq!(pass.to_string(), "0000");
+        assert!(Move::from_csa("0000").get_is_pass());
+        assert!(Move::from_csa("pass").get_is_pass());
+        assert!(!Move::from_csa("7g7f").get_is_pass());
+    }
 }
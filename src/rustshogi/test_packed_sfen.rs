@@ -0,0 +1,179 @@
+#[cfg(test)]
+
+mod tests {
+    use crate::{
+        address::Address,
+        board::Board,
+        color::ColorType,
+        moves::Move,
+        packed_sfen::{
+            move16_to_move, move_to_move16, pack_position, read_record_file, unpack_position,
+            write_record_file, write_records, Hcpe, PackedFormat, PackedSfenValue, RecordReader,
+            TrainingRecord, HCPE_SIZE, PACKED_SFEN_VALUE_SIZE,
+        },
+        puct::legal_moves,
+        variant::Variant,
+    };
+
+    const FORMATS: [PackedFormat; 2] = [PackedFormat::PackedSfen, PackedFormat::HuffmanCodedPos];
+
+    const SFENS: [(&str, ColorType); 3] = [
+        (
+            "lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL -",
+            ColorType::Black,
+        ),
+        (
+            "8l/1l+R2P3/p2pBG1pp/kps1p4/Nn1P2G2/P1P1P2PP/1PS6/1KSG3+r1/LN2+p3L Sbgn3p",
+            ColorType::White,
+        ),
+        (
+            "lnsgkg1nl/1r5s1/pppppp1pp/6p2/9/2P6/PP1PPPPPP/7R1/LNSGKGSNL b Bb",
+            ColorType::Black,
+        ),
+    ];
+
+    #[test]
+    fn test_round_trip() {
+        for format in FORMATS {
+            for (sfen, turn) in SFENS {
+                let board = Board::from_sfen(sfen.to_string());
+                let data = pack_position(&board, turn, format).unwrap();
+                let (decoded, decoded_turn) = unpack_position(&data, format).unwrap();
+                assert_eq!(decoded.to_string(), board.to_string(), "{}", sfen);
+                assert_eq!(decoded.hand.to_sfen(), board.hand.to_sfen(), "{}", sfen);
+                assert_eq!(decoded_turn, turn);
+            }
+        }
+    }
+
+    #[test]
+    fn test_startpos_header() {
+        let mut board = Board::new();
+        board.startpos();
+        for format in FORMATS {
+            let data = pack_position(&board, ColorType::White, format).unwrap();
+            // 手番の1ビットの後に先手玉（5九 = 44）、後手玉（5一 = 36）の順
+            assert_eq!(data[0], 1 | (44 << 1));
+            assert_eq!(data[1] & 0x7F, 36);
+        }
+        let black = board.to_packed_sfen(ColorType::Black).unwrap();
+        assert_eq!(black[0], 44 << 1);
+    }
+
+    #[test]
+    fn test_formats_differ() {
+        let mut board = Board::new();
+        board.startpos();
+        assert_ne!(
+            board.to_packed_sfen(ColorType::Black).unwrap(),
+            board.to_hcp(ColorType::Black).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_pack_errors() {
+        let board = Board::from_sfen("4k4/9/9/9/9/9/9/9/4K4 -".to_string());
+        assert!(board.to_packed_sfen(ColorType::Black).is_err());
+
+        let mut minishogi = Board::with_variant(Variant::Minishogi);
+        minishogi.startpos();
+        assert!(minishogi.to_hcp(ColorType::Black).is_err());
+
+        assert!(Board::from_packed_sfen(&[0; 31]).is_err());
+    }
+
+    #[test]
+    fn test_move16() {
+        // 7七の歩を7六へ
+        let mv = Move::from_standart(
+            Address::from_numbers(3, 3),
+            Address::from_numbers(3, 4),
+            false,
+        );
+        assert_eq!(move_to_move16(&mv, PackedFormat::PackedSfen), 59 | 60 << 7);
+        assert_eq!(
+            move_to_move16(&mv, PackedFormat::HuffmanCodedPos),
+            59 | 60 << 7
+        );
+        assert_eq!(move_to_move16(&Move::pass(), PackedFormat::PackedSfen), 0);
+
+        for format in FORMATS {
+            for (sfen, turn) in SFENS {
+                let board = Board::from_sfen(sfen.to_string());
+                for mv in legal_moves(&board, turn) {
+                    let value = move_to_move16(&mv, format);
+                    let decoded = move16_to_move(value, turn, format).unwrap().unwrap();
+                    assert_eq!(decoded.to_string(), mv.to_string());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_records_bytes() {
+        let (sfen, turn) = SFENS[1];
+        let board = Board::from_sfen(sfen.to_string());
+        let mv = legal_moves(&board, turn)[0];
+
+        let psv = PackedSfenValue::from_board(&board, turn, -120, Some(&mv), 124, -1).unwrap();
+        let bytes = psv.to_bytes();
+        assert_eq!(bytes.len(), PACKED_SFEN_VALUE_SIZE);
+        let decoded = PackedSfenValue::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded, psv);
+        assert_eq!(
+            decoded.get_move().unwrap().unwrap().to_string(),
+            mv.to_string()
+        );
+        assert_eq!(decoded.board().unwrap().1, turn);
+
+        let hcpe = Hcpe::from_board(&board, turn, 300, Some(&mv), ColorType::White).unwrap();
+        let bytes = hcpe.to_bytes();
+        assert_eq!(bytes.len(), HCPE_SIZE);
+        let decoded = Hcpe::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded, hcpe);
+        assert_eq!(decoded.game_result, 2);
+        assert_eq!(decoded.winner(), ColorType::White);
+        assert_eq!(
+            decoded.get_best_move().unwrap().unwrap().to_string(),
+            mv.to_string()
+        );
+    }
+
+    #[test]
+    fn test_stream_records() {
+        let records: Vec<Hcpe> = SFENS
+            .iter()
+            .enumerate()
+            .map(|(i, (sfen, turn))| {
+                let board = Board::from_sfen(sfen.to_string());
+                Hcpe::from_board(&board, *turn, i as i16, None, ColorType::Black).unwrap()
+            })
+            .collect();
+
+        let mut buffer = Vec::new();
+        write_records(&mut buffer, &records).unwrap();
+        let read: Vec<Hcpe> = RecordReader::new(buffer.as_slice())
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(read, records);
+
+        // 途中で切れたデータはエラーにする
+        let truncated = &buffer[..buffer.len() - 1];
+        let results: Vec<Result<Hcpe, String>> = RecordReader::new(truncated).collect();
+        assert_eq!(results.len(), records.len());
+        assert!(results.last().unwrap().is_err());
+
+        let path = std::env::temp_dir().join(format!("rustshogi_hcpe_{}.bin", std::process::id()));
+        let path = path.to_str().unwrap();
+        write_record_file(path, &records[..2], false).unwrap();
+        write_record_file(path, &records[2..], true).unwrap();
+        assert_eq!(read_record_file::<Hcpe>(path, 0, None).unwrap(), records);
+        assert_eq!(
+            read_record_file::<Hcpe>(path, 1, Some(1)).unwrap(),
+            records[1..2]
+        );
+        assert!(read_record_file::<Hcpe>(path, 3, None).unwrap().is_empty());
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(Hcpe::SIZE, HCPE_SIZE);
+    }
+}
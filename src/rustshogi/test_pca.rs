@@ -19,11 +19,14 @@ fn test_pca_transform_transform() {
     let pca_transform = PCATransform::new(components, mean, 2);
 
     let features = vec![1.0, 2.0, 3.0];
-    let transformed = pca_transform.transform(&features);
+    let transformed = pca_transform.transform(&features).unwrap();
 
     assert_eq!(transformed.len(), 2);
     assert_eq!(transformed[0], 1.0);
     assert_eq!(transformed[1], 2.0);
+
+    assert!(pca_transform.transform(&[1.0, 2.0]).is_err());
+    assert!(PCATransform::unfitted(2).transform(&features).is_err());
 }
 
 #[test]
@@ -76,15 +79,14 @@ fn test_learn_pca_too_many_components() {
 #[test]
 fn test_apply_pca_compression_no_transform() {
     let features = vec![1.0, 2.0, 3.0, 4.0, 5.0];
-    let compressed = apply_pca_compression(&features, 3);
-
-    assert_eq!(compressed.len(), 3);
+    // 3次元の主成分を持つグローバルなPCA変換はないので、間引かずにエラーにする
+    assert!(apply_pca_compression(&features, 3).is_err());
 }
 
 #[test]
 fn test_apply_pca_compression_target_dims_larger() {
     let features = vec![1.0, 2.0, 3.0];
-    let compressed = apply_pca_compression(&features, 5);
+    let compressed = apply_pca_compression(&features, 5).unwrap();
 
     assert_eq!(compressed.len(), 3);
     assert_eq!(compressed, features);
@@ -96,9 +98,378 @@ fn test_set_and_get_global_pca_transform() {
     let mean = Array1::from_vec(vec![0.0, 0.0, 0.0]);
     let pca_transform = PCATransform::new(components, mean, 2);
 
+    let _lock = GLOBAL_PCA_TEST_LOCK.lock().unwrap();
     set_global_pca_transform(pca_transform.clone());
     let retrieved = get_global_pca_transform();
 
     assert!(retrieved.is_some());
     assert_eq!(retrieved.unwrap(), pca_transform);
 }
+
+#[test]
+fn test_learn_pca_explained_variance() {
+    // 1つ目の軸に沿って大きく、2つ目の軸に沿って小さく散らばるデータ
+    let samples = vec![
+        vec![-2.0, 0.5, 0.0],
+        vec![-1.0, -0.5, 0.0],
+        vec![1.0, -0.5, 0.0],
+        vec![2.0, 0.5, 0.0],
+    ];
+    let pca_transform = learn_pca(&samples, 2).unwrap();
+
+    // 分散は (4 + 1 + 1 + 4) / 3 と (4 * 0.25) / 3
+    let variance = &pca_transform.explained_variance;
+    assert!((variance[0] - 10.0 / 3.0).abs() < 1e-4);
+    assert!((variance[1] - 1.0 / 3.0).abs() < 1e-4);
+    assert!((pca_transform.total_variance - 11.0 / 3.0).abs() < 1e-4);
+
+    let ratio = pca_transform.explained_variance_ratio();
+    assert!((ratio[0] - 10.0 / 11.0).abs() < 1e-4);
+    assert!((ratio.sum() - 1.0).abs() < 1e-4);
+
+    let simple = learn_simple_pca(&samples, 1);
+    assert_eq!(simple.explained_variance[0], 2.5);
+    assert!((simple.explained_variance_ratio()[0] - 2.5 / 2.75).abs() < 1e-6);
+}
+
+#[test]
+fn test_learn_pca_single_sample() {
+    assert!(learn_pca(&[vec![1.0, 2.0]], 1).is_err());
+    assert!(learn_pca(&[vec![1.0, 2.0], vec![2.0, 1.0]], 0).is_err());
+}
+
+#[test]
+fn test_transform_batch() {
+    let samples = vec![
+        vec![1.0, 2.0, 0.0],
+        vec![2.0, 3.0, 1.0],
+        vec![3.0, 5.0, 0.0],
+        vec![4.0, 5.0, 1.0],
+    ];
+    let pca_transform = learn_pca(&samples, 2).unwrap();
+    let features: Vec<f32> = samples.iter().flatten().copied().collect();
+    let batch = pca_transform.transform_batch(&features).unwrap();
+
+    assert_eq!(batch.len(), 8);
+    for (row, sample) in batch.chunks(2).zip(&samples) {
+        let single = pca_transform.transform(sample).unwrap();
+        for (a, b) in row.iter().zip(&single) {
+            assert!((a - b).abs() < 1e-5);
+        }
+    }
+    assert!(pca_transform.transform_batch(&features[..7]).is_err());
+}
+
+#[test]
+fn test_pca_transform_save_and_load() {
+    let samples = vec![
+        vec![1.0, 2.0, 0.0],
+        vec![2.0, 3.0, 1.0],
+        vec![3.0, 5.0, 0.0],
+    ];
+    let pca_transform = learn_pca(&samples, 2).unwrap();
+
+    let path = std::env::temp_dir().join(format!("rustshogi_pca_{}.bin", std::process::id()));
+    let path = path.to_str().unwrap();
+    pca_transform.save(path).unwrap();
+    assert_eq!(PCATransform::load(path).unwrap(), pca_transform);
+
+    // 途中で切れたファイルはエラーにする
+    let data = std::fs::read(path).unwrap();
+    std::fs::write(path, &data[..data.len() - 1]).unwrap();
+    assert!(PCATransform::load(path).is_err());
+    std::fs::write(path, b"not a pca file").unwrap();
+    assert!(PCATransform::load(path).is_err());
+    std::fs::remove_file(path).unwrap();
+
+    assert!(PCATransform::unfitted(2).save(path).is_err());
+    assert!(PCATransform::load(path).is_err());
+}
+
+#[test]
+fn test_learn_pca_board_vectors() {
+    // 盤面の特徴量は疎で低ランクなので、主成分がNaNにならないことを確かめる
+    let samples: Vec<Vec<f32>> = [
+        "lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL b - 1",
+        "4k4/9/4+p4/4R4/9/9/9/9/4K4 b 2Pg 1",
+        "8l/1l+R2P3/p2pBG1pp/kps1p4/Nn1P2G2/P1P1P2PP/1PS6/1KSG3+r1/LN2+p3L w Sbgn3p 124",
+        "4k4/9/9/9/9/9/9/9/4K4 w 9Pr 1",
+    ]
+    .iter()
+    .map(|sfen| {
+        super::board::Board::from_sfen(sfen.to_string())
+            .to_vector(None)
+            .unwrap()
+    })
+    .collect();
+    let pca_transform = learn_pca(&samples, 3).unwrap();
+
+    assert!(pca_transform
+        .components
+        .iter()
+        .all(|value| value.is_finite()));
+    let variance = &pca_transform.explained_variance;
+    assert!(variance[0] >= variance[1] && variance[1] >= variance[2] && variance[2] > 0.0);
+    let ratio: f32 = pca_transform.explained_variance_ratio().iter().sum();
+    assert!((ratio - 1.0).abs() < 1e-4);
+}
+
+#[test]
+fn test_learn_pca_sparse_covariance_regression() {
+    use nalgebra::DMatrix;
+
+    // 以前の learn_pca は共分散行列を対称固有値分解していたが、盤面の特徴量のように
+    // ほとんどの行と列が0の低ランクな共分散行列ではNaNが出ていた（計算を軽くするため2枚目のビットボードだけ使う）
+    let samples: Vec<Vec<f32>> = [
+        "lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL b - 1",
+        "4k4/9/4+p4/4R4/9/9/9/9/4K4 b 2Pg 1",
+        "8l/1l+R2P3/p2pBG1pp/kps1p4/Nn1P2G2/P1P1P2PP/1PS6/1KSG3+r1/LN2+p3L w Sbgn3p 124",
+        "4k4/9/9/9/9/9/9/9/4K4 w 9Pr 1",
+    ]
+    .iter()
+    .map(|sfen| {
+        super::board::Board::from_sfen(sfen.to_string())
+            .to_vector(None)
+            .unwrap()[128..256]
+            .to_vec()
+    })
+    .collect();
+    let (n_samples, n_features) = (samples.len(), samples[0].len());
+
+    let mut centered = DMatrix::from_fn(n_samples, n_features, |i, j| samples[i][j]);
+    for j in 0..n_features {
+        let mean = centered.column(j).mean();
+        centered.column_mut(j).add_scalar_mut(-mean);
+    }
+    let covariance = centered.transpose() * &centered / (n_samples - 1) as f32;
+    let eigen = covariance.symmetric_eigen();
+    assert!(eigen.eigenvalues.iter().any(|value| value.is_nan()));
+
+    // 中心化したデータの特異値分解ならNaNにならない
+    let pca_transform = learn_pca(&samples, 3).unwrap();
+    assert!(pca_transform
+        .components
+        .iter()
+        .chain(pca_transform.explained_variance.iter())
+        .all(|value| value.is_finite()));
+    let total: f32 = pca_transform.explained_variance_ratio().iter().sum();
+    assert!((total - 1.0).abs() < 1e-4);
+}
+
+/// 3方向に大きく散らばり、残りの方向に小さな雑音が乗ったデータ
+fn low_rank_samples(n_samples: usize, n_features: usize, noise: f32) -> Vec<Vec<f32>> {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    let mut rng = StdRng::seed_from_u64(11);
+    let directions: Vec<Vec<f32>> = (0..3)
+        .map(|_| (0..n_features).map(|_| rng.gen_range(-1.0..1.0)).collect())
+        .collect();
+    (0..n_samples)
+        .map(|_| {
+            let weights = [
+                rng.gen_range(-3.0..3.0),
+                rng.gen_range(-2.0..2.0),
+                rng.gen_range(-1.0..1.0),
+            ];
+            (0..n_features)
+                .map(|j| {
+                    let signal: f32 = (0..3).map(|k| weights[k] * directions[k][j]).sum();
+                    1.0 + signal + noise * rng.gen_range(-1.0..1.0)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// 主成分の分散と向き（符号は問わない）が厳密解に近いことを確かめる
+fn assert_close_to_exact(
+    approx: &PCATransform,
+    exact: &PCATransform,
+    tolerance: f32,
+    min_cosine: f32,
+) {
+    assert_eq!(approx.components.shape(), exact.components.shape());
+    for (a, b) in approx.mean.iter().zip(exact.mean.iter()) {
+        assert!((a - b).abs() < 1e-4);
+    }
+    assert!((approx.total_variance - exact.total_variance).abs() < 1e-3 * exact.total_variance);
+    for i in 0..exact.n_components {
+        let expected = exact.explained_variance[i];
+        assert!(
+            (approx.explained_variance[i] - expected).abs() < tolerance * expected,
+            "component {}: {} vs {}",
+            i,
+            approx.explained_variance[i],
+            expected
+        );
+        let cosine = approx.components.row(i).dot(&exact.components.row(i)).abs();
+        assert!(cosine > min_cosine, "component {}: cosine {}", i, cosine);
+    }
+}
+
+#[test]
+fn test_learn_randomized_pca_matches_exact() {
+    let samples = low_rank_samples(60, 12, 0.05);
+    let exact = learn_pca(&samples, 3).unwrap();
+
+    let features: Vec<f32> = samples.iter().flatten().copied().collect();
+    let batches = || features.chunks(12 * 7).map(|batch| Ok(batch.to_vec()));
+    let config = RandomizedPCAConfig {
+        oversamples: 4,
+        ..RandomizedPCAConfig::default()
+    };
+    let randomized = learn_randomized_pca(12, 3, &config, batches).unwrap();
+    assert_close_to_exact(&randomized, &exact, 1e-3, 0.9999);
+
+    // 部分空間の次元が特徴量の次元に達すれば厳密解と一致する
+    let full = RandomizedPCAConfig {
+        oversamples: 20,
+        power_iterations: 0,
+        seed: 3,
+    };
+    let randomized = learn_randomized_pca(12, 3, &full, batches).unwrap();
+    assert_close_to_exact(&randomized, &exact, 1e-4, 0.99999);
+}
+
+#[test]
+fn test_learn_randomized_pca_errors() {
+    let config = RandomizedPCAConfig::default();
+    let one = || std::iter::once(Ok(vec![1.0, 2.0, 3.0]));
+    assert!(learn_randomized_pca(3, 2, &config, one).is_err());
+    assert!(learn_randomized_pca(3, 4, &config, one).is_err());
+    assert!(learn_randomized_pca(3, 0, &config, one).is_err());
+
+    let ragged = || std::iter::once(Ok(vec![1.0, 2.0, 3.0, 4.0]));
+    assert!(learn_randomized_pca(3, 2, &config, ragged).is_err());
+
+    let failing = || std::iter::once(Err("read error".to_string()));
+    assert_eq!(
+        learn_randomized_pca(3, 2, &config, failing).unwrap_err(),
+        "read error"
+    );
+}
+
+#[test]
+fn test_incremental_pca_matches_exact() {
+    let samples = low_rank_samples(60, 12, 0.0);
+    let exact = learn_pca(&samples, 3).unwrap();
+
+    // 雑音がなければ3主成分で全体を表せるので、バッチに分けても厳密解と一致する
+    let mut incremental = IncrementalPCA::new(12, 3).unwrap();
+    for batch in samples.chunks(7) {
+        let batch: Vec<f32> = batch.iter().flatten().copied().collect();
+        incremental.partial_fit(&batch).unwrap();
+    }
+    assert_eq!(incremental.n_samples_seen, 60);
+    assert_close_to_exact(&incremental.to_transform().unwrap(), &exact, 1e-3, 0.9999);
+
+    // 雑音があっても上位の主成分はほぼ一致する
+    let samples = low_rank_samples(60, 12, 0.05);
+    let exact = learn_pca(&samples, 3).unwrap();
+    let mut incremental = IncrementalPCA::new(12, 3).unwrap();
+    for batch in samples.chunks(10) {
+        let batch: Vec<f32> = batch.iter().flatten().copied().collect();
+        incremental.partial_fit(&batch).unwrap();
+    }
+    assert_close_to_exact(&incremental.to_transform().unwrap(), &exact, 1e-2, 0.999);
+}
+
+#[test]
+fn test_incremental_pca_errors() {
+    assert!(IncrementalPCA::new(3, 0).is_err());
+    assert!(IncrementalPCA::new(3, 4).is_err());
+
+    let mut incremental = IncrementalPCA::new(3, 2).unwrap();
+    assert!(incremental.to_transform().is_err());
+    // 最初のバッチは主成分数以上のサンプルが必要
+    assert!(incremental.partial_fit(&[1.0, 2.0, 3.0]).is_err());
+    assert!(incremental.partial_fit(&[1.0, 2.0, 3.0, 4.0]).is_err());
+    incremental
+        .partial_fit(&[1.0, 2.0, 3.0, 2.0, 2.0, 1.0])
+        .unwrap();
+    // 2回目以降は1サンプルでもよい
+    incremental.partial_fit(&[0.0, 1.0, 0.0]).unwrap();
+    assert_eq!(incremental.n_samples_seen, 3);
+    assert!(incremental.to_transform().is_ok());
+}
+
+#[test]
+fn test_pca_transform_fit_boards() {
+    use super::board::BoardSource;
+
+    let sfens = [
+        "lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL b - 1",
+        "lnsgkgsnl/1r5b1/ppppppppp/9/9/2P6/PP1PPPPPP/1B5R1/LNSGKGSNL w - 2",
+        "lnsgkgsnl/1r5b1/pppppp1pp/6p2/9/2P6/PP1PPPPPP/1B5R1/LNSGKGSNL b - 3",
+        "lnsgkgsnl/1r5b1/pppppp1pp/6p2/9/2P4P1/PP1PPPP1P/1B5R1/LNSGKGSNL w - 4",
+        "4k4/9/4+p4/4R4/9/9/9/9/4K4 b 2Pg 1",
+        "8l/1l+R2P3/p2pBG1pp/kps1p4/Nn1P2G2/P1P1P2PP/1PS6/1KSG3+r1/LN2+p3L w Sbgn3p 124",
+    ];
+    let sources: Vec<BoardSource> = sfens
+        .iter()
+        .map(|sfen| BoardSource::from(sfen.to_string()))
+        .collect();
+
+    // 6局面の階数は5なので、5主成分ならどちらの解法も全分散を説明する
+    let randomized = PCATransform::fit(&sources, 5, PCASolver::Randomized, 4, 2).unwrap();
+    let incremental = PCATransform::fit(&sources, 5, PCASolver::Incremental, 6, 2).unwrap();
+    for transform in [&randomized, &incremental] {
+        assert_eq!(transform.n_features(), 2320);
+        assert!((transform.explained_variance_ratio().sum() - 1.0).abs() < 1e-3);
+    }
+    for i in 0..5 {
+        let (a, b) = (
+            randomized.explained_variance[i],
+            incremental.explained_variance[i],
+        );
+        assert!((a - b).abs() < 1e-3 * b, "{} vs {}", a, b);
+    }
+
+    assert!(PCATransform::fit(&sources, 5, PCASolver::Randomized, 0, 2).is_err());
+    // 最初のバッチが主成分数より小さい
+    assert!(PCATransform::fit(&sources, 5, PCASolver::Incremental, 4, 2).is_err());
+}
+
+#[test]
+fn test_sparse_board_vectors_match_exact() {
+    // 実際の盤面の特徴量は疎で、サンプル数より階数が小さい
+    let samples: Vec<Vec<f32>> = [
+        "lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL b - 1",
+        "lnsgkgsnl/1r5b1/ppppppppp/9/9/2P6/PP1PPPPPP/1B5R1/LNSGKGSNL w - 2",
+        "lnsgkgsnl/1r5b1/pppppp1pp/6p2/9/2P6/PP1PPPPPP/1B5R1/LNSGKGSNL b - 3",
+        "lnsgkgsnl/1r5b1/pppppp1pp/6p2/9/2P4P1/PP1PPPP1P/1B5R1/LNSGKGSNL w - 4",
+        "4k4/9/4+p4/4R4/9/9/9/9/4K4 b 2Pg 1",
+        "8l/1l+R2P3/p2pBG1pp/kps1p4/Nn1P2G2/P1P1P2PP/1PS6/1KSG3+r1/LN2+p3L w Sbgn3p 124",
+    ]
+    .iter()
+    .map(|sfen| {
+        super::board::Board::from_sfen(sfen.to_string())
+            .to_vector(None)
+            .unwrap()
+    })
+    .collect();
+    let n_features = samples[0].len();
+    assert!(samples[0].iter().filter(|value| **value != 0.0).count() < n_features / 4);
+
+    // 部分空間の次元（主成分数 + 10）が階数を超えても、射影した行列の分解がNaNにならない
+    let exact = learn_pca(&samples, 3).unwrap();
+    let features: Vec<f32> = samples.iter().flatten().copied().collect();
+    let batches = || {
+        features
+            .chunks(n_features * 4)
+            .map(|batch| Ok(batch.to_vec()))
+    };
+    let randomized =
+        learn_randomized_pca(n_features, 3, &RandomizedPCAConfig::default(), batches).unwrap();
+    assert!(randomized.components.iter().all(|value| value.is_finite()));
+    assert_close_to_exact(&randomized, &exact, 1e-3, 0.999);
+
+    // 主成分数が階数に等しければ、バッチに分けても厳密解と一致する
+    let exact = learn_pca(&samples, 5).unwrap();
+    let mut incremental = IncrementalPCA::new(n_features, 5).unwrap();
+    for batch in features.chunks(n_features * 5) {
+        incremental.partial_fit(batch).unwrap();
+    }
+    assert_close_to_exact(&incremental.to_transform().unwrap(), &exact, 1e-3, 0.999);
+}
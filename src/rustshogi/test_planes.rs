@@ -0,0 +1,105 @@
+#[cfg(test)]
+
+mod tests {
+    use crate::{
+        board::Board,
+        color::ColorType,
+        planes::{to_planes, PlaneOptions, CONSTANT_PLANES, POSITION_PLANES},
+        variant::Variant,
+    };
+
+    fn startpos() -> Board {
+        let mut board = Board::new();
+        board.startpos();
+        board
+    }
+
+    #[test]
+    fn test_planes_shape() {
+        let board = startpos();
+        let options = PlaneOptions::default();
+        let planes = to_planes(&board, &[], ColorType::Black, 0, &options).unwrap();
+        assert_eq!(
+            options.shape(&board),
+            (POSITION_PLANES + CONSTANT_PLANES, 9, 9)
+        );
+        assert_eq!(planes.len(), (POSITION_PLANES + CONSTANT_PLANES) * 81);
+        // 盤上の駒は両者20枚ずつ
+        assert_eq!(planes[..28 * 81].iter().sum::<f32>(), 40.0);
+        // 先手の玉（平面0）は9段目の5筋で、上の段から並べると最後の行になる
+        assert_eq!(planes[8 * 9 + 4], 1.0);
+
+        let mut mini = Board::with_variant(Variant::Minishogi);
+        mini.startpos();
+        let options = PlaneOptions {
+            history_length: 2,
+            orient: false,
+        };
+        let planes = to_planes(&mini, &[], ColorType::Black, 0, &options).unwrap();
+        assert_eq!(
+            options.shape(&mini),
+            (2 * POSITION_PLANES + CONSTANT_PLANES, 5, 5)
+        );
+        assert_eq!(planes.len(), (2 * POSITION_PLANES + CONSTANT_PLANES) * 25);
+        // 足りない履歴の平面は0
+        let history = &planes[POSITION_PLANES * 25..2 * POSITION_PLANES * 25];
+        assert!(history.iter().all(|value| *value == 0.0));
+        assert!(to_planes(
+            &startpos(),
+            &[],
+            ColorType::Black,
+            0,
+            &PlaneOptions {
+                history_length: 0,
+                orient: true,
+            }
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_planes_orientation() {
+        // 平手の初期局面は180度回して先後を入れ替えると同じになる
+        let board = startpos();
+        let options = PlaneOptions::default();
+        let black = to_planes(&board, &[], ColorType::Black, 0, &options).unwrap();
+        let white = to_planes(&board, &[], ColorType::White, 0, &options).unwrap();
+        let position = POSITION_PLANES * 81;
+        assert_eq!(black[..position], white[..position]);
+        assert!(black[position..position + 81].iter().all(|v| *v == 0.0));
+        assert!(white[position..position + 81].iter().all(|v| *v == 1.0));
+
+        let fixed = PlaneOptions {
+            history_length: 1,
+            orient: false,
+        };
+        let white = to_planes(&board, &[], ColorType::White, 0, &fixed).unwrap();
+        assert_eq!(black[..position], white[..position]);
+
+        let board = Board::from_sfen("4k4/9/9/9/9/9/9/9/4K3L b - 1".to_string());
+        let oriented = to_planes(&board, &[], ColorType::White, 0, &options).unwrap();
+        let fixed = to_planes(&board, &[], ColorType::White, 0, &fixed).unwrap();
+        // 先手の香（9段目の9筋）は、後手から見ると相手の駒の1段目の1筋になる
+        let lance = 14 + 7 - 1;
+        assert_eq!(oriented[lance * 81], 1.0);
+        assert_eq!(fixed[(lance - 14) * 81 + 8 * 9 + 8], 1.0);
+    }
+
+    #[test]
+    fn test_planes_hand_history_and_repetition() {
+        let board = Board::from_sfen("4k4/9/9/9/9/9/9/9/4K4 b 2R9P 1".to_string());
+        let previous = startpos();
+        let options = PlaneOptions {
+            history_length: 2,
+            orient: true,
+        };
+        let planes = to_planes(&board, &[previous], ColorType::Black, 3, &options).unwrap();
+        // 先手の持ち駒の飛車（平面29）は2枚で最大、歩（平面34）は18枚中9枚
+        assert!(planes[29 * 81..30 * 81].iter().all(|v| *v == 1.0));
+        assert!(planes[34 * 81..35 * 81].iter().all(|v| *v == 0.5));
+        // 1局面前は平手の初期局面
+        let previous_board = &planes[POSITION_PLANES * 81..(POSITION_PLANES + 28) * 81];
+        assert_eq!(previous_board.iter().sum::<f32>(), 40.0);
+        assert!(planes[planes.len() - 81..].iter().all(|v| *v == 3.0));
+    }
+}
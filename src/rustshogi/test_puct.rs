@@ -0,0 +1,125 @@
+#[cfg(test)]
+
+mod tests {
+    use crate::{
+        board::Board,
+        color::ColorType,
+        evaluator::{priors_from_policy, EvalPosition, Evaluation, Evaluator, UniformEvaluator},
+        game::Game,
+        move_label::MOVE_LABEL_NUMBER,
+        puct::{legal_moves, PuctConfig, PuctSearch},
+    };
+    use std::sync::Mutex;
+
+    fn config(simulations: usize, batch_size: usize) -> PuctConfig {
+        PuctConfig {
+            simulations,
+            batch_size,
+            ..PuctConfig::new()
+        }
+    }
+
+    /// 評価した局面の数を記録する評価器
+    struct RecordingEvaluator {
+        batches: Mutex<Vec<usize>>,
+    }
+
+    impl Evaluator for RecordingEvaluator {
+        fn evaluate(&self, positions: &[EvalPosition]) -> Result<Vec<Evaluation>, String> {
+            self.batches.lock().unwrap().push(positions.len());
+            UniformEvaluator.evaluate(positions)
+        }
+    }
+
+    /// 事前確率の要素数を間違える評価器
+    struct BrokenEvaluator;
+
+    impl Evaluator for BrokenEvaluator {
+        fn evaluate(&self, positions: &[EvalPosition]) -> Result<Vec<Evaluation>, String> {
+            Ok(positions
+                .iter()
+                .map(|_| Evaluation {
+                    value: 0.0,
+                    priors: vec![1.0],
+                })
+                .collect())
+        }
+    }
+
+    #[test]
+    fn test_puct_uniform_visits() {
+        let mut game = Game::new();
+        game.board.startpos();
+        let result = game
+            .puct_search(&UniformEvaluator, &config(200, 8))
+            .unwrap();
+        assert_eq!(result.moves.len(), 30);
+        assert_eq!(result.visits.iter().sum::<u32>(), 200);
+        assert!(result.priors.iter().all(|p| (*p - 1.0 / 30.0).abs() < 1e-6));
+        assert!((result.visit_distribution().iter().sum::<f32>() - 1.0).abs() < 1e-5);
+        assert!(result.best_move().is_some());
+        assert_eq!(result.value, 0.0);
+    }
+
+    #[test]
+    fn test_puct_batches_leaves() {
+        let mut game = Game::new();
+        game.board.startpos();
+        let evaluator = RecordingEvaluator {
+            batches: Mutex::new(Vec::new()),
+        };
+        game.puct_search(&evaluator, &config(64, 16)).unwrap();
+        let batches = evaluator.batches.lock().unwrap();
+        // 根の評価の後は、仮想損失で複数の葉をまとめて評価する
+        assert_eq!(batches[0], 1);
+        assert!(batches.iter().all(|size| *size <= 16));
+        assert!(batches.iter().skip(1).any(|size| *size > 1));
+        assert_eq!(batches.iter().skip(1).sum::<usize>(), 64);
+    }
+
+    #[test]
+    fn test_puct_finds_mate_in_one() {
+        // 5八金打で詰み
+        let game = Game::from_sfen("4k4/9/4P4/9/9/9/9/9/4K4 b G 1");
+        let result = game
+            .puct_search(&UniformEvaluator, &config(1000, 8))
+            .unwrap();
+        let best = result.best_move().unwrap();
+        let mut board = game.board.clone();
+        board.execute_move(&best);
+        assert!(legal_moves(&board, ColorType::White).is_empty());
+        assert!(result.value > 0.5);
+    }
+
+    #[test]
+    fn test_puct_no_legal_moves_and_errors() {
+        let board = Board::from_sfen("4k4/4G4/4P4/9/9/9/9/9/4K4 w - 1".to_string());
+        let result = PuctSearch::new(&UniformEvaluator, config(10, 4))
+            .search(&board, ColorType::White)
+            .unwrap();
+        assert!(result.moves.is_empty());
+        assert!(result.best_move().is_none());
+        assert_eq!(result.value, -1.0);
+
+        let mut game = Game::new();
+        game.board.startpos();
+        assert!(game.puct_search(&BrokenEvaluator, &config(10, 4)).is_err());
+    }
+
+    #[test]
+    fn test_puct_priors_from_policy() {
+        let mut board = Board::new();
+        board.startpos();
+        let moves = legal_moves(&board, ColorType::Black);
+        let mut policy = vec![0.0; MOVE_LABEL_NUMBER];
+        let label = moves[3].to_label(ColorType::Black).unwrap();
+        policy[label] = 2.0;
+        let priors = priors_from_policy(&policy, ColorType::Black, &moves);
+        assert_eq!(priors[3], 1.0);
+        assert_eq!(priors.iter().sum::<f32>(), 1.0);
+
+        // 合法手の値がすべて0なら一様にする
+        let priors = priors_from_policy(&[0.0; MOVE_LABEL_NUMBER], ColorType::Black, &moves);
+        assert!(priors.iter().all(|p| *p == 1.0 / moves.len() as f32));
+    }
+}
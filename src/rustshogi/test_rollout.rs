@@ -0,0 +1,130 @@
+#[cfg(test)]
+
+mod tests {
+    use crate::{
+        address::Address,
+        board::Board,
+        color::ColorType,
+        moves::Move,
+        piece::{Piece, PieceType},
+        rollout::{
+            find_mate_in_one, material_gain, CaptureFirstPolicy, EpsilonGreedyPolicy,
+            RolloutConfig, RolloutPolicy, RolloutPolicyType, SoftmaxPolicy, UniformPolicy,
+        },
+    };
+
+    fn index(column: u8, row: u8) -> u8 {
+        Address::from_numbers(column, row).to_index()
+    }
+
+    fn capture_board() -> Board {
+        let mut board = Board::new();
+        board.deploy(index(5, 1), PieceType::King, ColorType::Black);
+        board.deploy(index(5, 9), PieceType::King, ColorType::White);
+        board.deploy(index(3, 4), PieceType::Silver, ColorType::Black);
+        board.deploy(index(3, 5), PieceType::Rook, ColorType::White);
+        board.deploy(index(7, 4), PieceType::Pawn, ColorType::Black);
+        board.deploy(index(7, 5), PieceType::Pawn, ColorType::White);
+        board
+    }
+
+    fn rook_capture() -> Move {
+        Move::from_standart(
+            Address::from_numbers(3, 4),
+            Address::from_numbers(3, 5),
+            false,
+        )
+    }
+
+    #[test]
+    fn test_rollout_uniform_policy() {
+        let mut board = Board::new();
+        board.startpos();
+        let moves = board.search_moves(ColorType::Black);
+        for _ in 0..20 {
+            let index = UniformPolicy.select_move(&board, ColorType::Black, &moves);
+            assert!(index < moves.len());
+        }
+    }
+
+    #[test]
+    fn test_rollout_material_gain() {
+        let board = capture_board();
+        assert_eq!(material_gain(&board, &rook_capture()), 990);
+    }
+
+    #[test]
+    fn test_rollout_capture_first_policy() {
+        let board = capture_board();
+        let moves = board.search_moves(ColorType::Black);
+        for _ in 0..20 {
+            let selected = CaptureFirstPolicy.select_move(&board, ColorType::Black, &moves);
+            let to = moves[selected].get_to().to_index();
+            assert!(to == index(3, 5) || to == index(7, 5));
+        }
+    }
+
+    #[test]
+    fn test_rollout_capture_first_policy_takes_king() {
+        let mut board = capture_board();
+        board.deploy(index(5, 8), PieceType::Gold, ColorType::Black);
+        let moves = board.search_moves(ColorType::Black);
+        let selected = CaptureFirstPolicy.select_move(&board, ColorType::Black, &moves);
+        assert_eq!(moves[selected].get_to().to_index(), index(5, 9));
+    }
+
+    #[test]
+    fn test_rollout_softmax_policy_low_temperature() {
+        let board = capture_board();
+        let moves = board.search_moves(ColorType::Black);
+        let policy = SoftmaxPolicy { temperature: 1.0 };
+        for _ in 0..20 {
+            let selected = policy.select_move(&board, ColorType::Black, &moves);
+            assert_eq!(moves[selected].value, rook_capture().value);
+        }
+    }
+
+    #[test]
+    fn test_rollout_epsilon_greedy_policy() {
+        let board = capture_board();
+        let moves = board.search_moves(ColorType::Black);
+        let policy = EpsilonGreedyPolicy { epsilon: 0.0 };
+        let selected = policy.select_move(&board, ColorType::Black, &moves);
+        assert_eq!(moves[selected].value, rook_capture().value);
+    }
+
+    #[test]
+    fn test_rollout_find_mate_in_one() {
+        let mut board = Board::new();
+        board.deploy(index(5, 1), PieceType::King, ColorType::Black);
+        board.deploy(index(5, 9), PieceType::King, ColorType::White);
+        board.deploy(index(5, 7), PieceType::Pawn, ColorType::Black);
+        board.hand.add_piece(ColorType::Black, PieceType::Gold);
+        let moves = board.search_moves(ColorType::Black);
+
+        let selected = find_mate_in_one(&board, ColorType::Black, &moves).unwrap();
+        let mate = Move::from_drop(
+            Piece::from(ColorType::Black, PieceType::Gold),
+            Address::from_numbers(5, 8),
+        );
+        assert_eq!(moves[selected].value, mate.value);
+
+        let mut startpos = Board::new();
+        startpos.startpos();
+        let moves = startpos.search_moves(ColorType::Black);
+        assert!(find_mate_in_one(&startpos, ColorType::Black, &moves).is_none());
+    }
+
+    #[test]
+    fn test_rollout_config_build_policy() {
+        let mut config = RolloutConfig::new();
+        config.policy = RolloutPolicyType::CaptureFirst;
+        config.mate_in_one = true;
+        let policy = config.build_policy();
+        let board = capture_board();
+        let moves = board.search_moves(ColorType::Black);
+        let selected = policy.select_move(&board, ColorType::Black, &moves);
+        let to = moves[selected].get_to().to_index();
+        assert!(to == index(3, 5) || to == index(7, 5));
+    }
+}
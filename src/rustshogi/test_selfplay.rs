@@ -0,0 +1,120 @@
+#[cfg(test)]
+
+mod tests {
+    use crate::{
+        color::ColorType,
+        evaluator::UniformEvaluator,
+        game::Game,
+        puct::{PuctConfig, PuctSearch},
+        selfplay::SelfPlayConfig,
+        variant::Variant,
+    };
+
+    fn config(simulations: usize, max_moves: u16) -> SelfPlayConfig {
+        SelfPlayConfig {
+            search: PuctConfig {
+                simulations,
+                batch_size: 4,
+                ..SelfPlayConfig::new().search
+            },
+            max_moves,
+            variant: Variant::Minishogi,
+            ..SelfPlayConfig::new()
+        }
+    }
+
+    #[test]
+    fn test_game_to_sfen_round_trip() {
+        let sfens = [
+            "lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL b - 1",
+            "lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL w - 2",
+            "8l/1l+R2P3/p2pBG1pp/kps1p4/Nn1P2G2/P1P1P2PP/1PS6/1KSG3+r1/LN2+p3L w Sbgn3p 124",
+            "4k4/9/9/9/9/9/9/9/4K4 b 2R2B4G4S4N4L9P 1",
+        ];
+        for sfen in sfens {
+            assert_eq!(Game::from_sfen(sfen).to_sfen(), sfen);
+        }
+    }
+
+    #[test]
+    fn test_dirichlet_noise_changes_root_priors_only_in_search() {
+        let game = Game::from_variant(Variant::Standard);
+        let mut config = PuctConfig {
+            simulations: 64,
+            batch_size: 8,
+            dirichlet_epsilon: 0.5,
+            ..PuctConfig::new()
+        };
+        let noisy = PuctSearch::new(&UniformEvaluator, config.clone())
+            .search(&game.board, game.turn)
+            .unwrap();
+        // 結果の事前確率はノイズを混ぜる前の値
+        let uniform = 1.0 / noisy.moves.len() as f32;
+        assert!(noisy.priors.iter().all(|p| (p - uniform).abs() < 1e-6));
+        assert_eq!(noisy.visits.iter().sum::<u32>(), 64);
+
+        config.dirichlet_alpha = 0.0;
+        assert!(PuctSearch::new(&UniformEvaluator, config)
+            .search(&game.board, game.turn)
+            .is_err());
+    }
+
+    #[test]
+    fn test_play_game_records_results() {
+        let game = config(8, 40).play_game(&UniformEvaluator).unwrap();
+        assert!(!game.records.is_empty());
+        assert!(game.records.len() <= 40);
+        for (ply, record) in game.records.iter().enumerate() {
+            assert_eq!(record.moves.len(), record.visits.len());
+            let expected = match game.winner {
+                ColorType::None => 0.0,
+                // 勝った側の手番の局面は1
+                winner if (ply % 2 == 0) == (winner == ColorType::Black) => 1.0,
+                _ => -1.0,
+            };
+            assert_eq!(record.result, expected);
+        }
+        assert!(game.records[0]
+            .sfen
+            .starts_with(Variant::Minishogi.board_sfen()));
+    }
+
+    #[test]
+    fn test_max_moves_is_draw() {
+        let game = config(4, 3).play_game(&UniformEvaluator).unwrap();
+        assert_eq!(game.records.len(), 3);
+        assert_eq!(game.winner, ColorType::None);
+        assert!(game.records.iter().all(|r| r.result == 0.0));
+    }
+
+    #[test]
+    fn test_resign_threshold() {
+        // 一様な評価器の評価値は0なので、閾値が正ならすぐに投了する
+        let mut config = config(4, 40);
+        config.resign_threshold = Some(0.5);
+        let game = config.play_game(&UniformEvaluator).unwrap();
+        assert!(game.records.is_empty());
+        assert_eq!(game.winner, ColorType::White);
+    }
+
+    #[test]
+    fn test_run_writes_json_lines() {
+        let mut output = Vec::new();
+        let summary = config(4, 10)
+            .run(&UniformEvaluator, 3, 2, &mut output)
+            .unwrap();
+        let text = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(summary.games, 3);
+        assert_eq!(
+            summary.black_wins + summary.white_wins + summary.draws,
+            summary.games
+        );
+        assert_eq!(lines.len(), summary.positions);
+        for line in lines {
+            assert!(line.starts_with("{\"sfen\":\""));
+            assert!(line.contains("\"visits\":["));
+            assert!(line.ends_with('}'));
+        }
+    }
+}
@@ -0,0 +1,115 @@
+#[cfg(test)]
+
+mod tests {
+    use crate::{
+        address::Address,
+        bitboard::{BitBoard, FRAME, LAST1_ZONE, LAST2_ZONE, PRO_ZONE},
+        board::Board,
+        color::ColorType,
+        game::Game,
+        piece::PieceType,
+        variant::Variant,
+    };
+
+    #[test]
+    fn test_variant_standard_geometry() {
+        let standard = Variant::Standard;
+        let mut playable = FRAME;
+        playable.flip();
+        assert_eq!(standard.playable(), playable);
+        for color in [ColorType::Black, ColorType::White] {
+            assert_eq!(standard.pro_zone(color), PRO_ZONE[color as usize]);
+            assert_eq!(standard.last1_zone(color), LAST1_ZONE[color as usize]);
+            assert_eq!(standard.last2_zone(color), LAST2_ZONE[color as usize]);
+        }
+        assert_eq!(Board::new().variant, Variant::Standard);
+    }
+
+    #[test]
+    fn test_variant_from_name() {
+        for variant in Variant::ALL {
+            assert_eq!(Variant::from_name(variant.get_name()), Some(variant));
+        }
+        assert_eq!(Variant::from_name("Mini-Shogi"), Some(Variant::Minishogi));
+        assert_eq!(Variant::from_name("chess"), None);
+    }
+
+    #[test]
+    fn test_variant_minishogi_sfen() {
+        let mut board = Board::with_variant(Variant::Minishogi);
+        board.startpos();
+        assert!(board.is_consistent());
+        assert_eq!(board.to_string(), "rbsgk/4p/5/P4/KGSBR");
+        assert_eq!(
+            board.get_piece_type_from_index(Address::from_numbers(1, 1).to_index()),
+            PieceType::King
+        );
+        assert_eq!(
+            board.get_piece_type_from_index(Address::from_numbers(5, 5).to_index()),
+            PieceType::King
+        );
+
+        for variant in Variant::ALL {
+            let board = Board::from_sfen_with_variant(variant.sfen(), variant);
+            assert_eq!(board.to_string(), variant.board_sfen());
+        }
+        // 盤の大きさが違えば同じ駒の並びでも別の局面
+        assert_ne!(
+            Board::from_sfen_with_variant("4k/5/5/5/K4".to_string(), Variant::Minishogi),
+            Board::from_sfen_with_variant("4k/5/5/5/K4".to_string(), Variant::GoroGoro)
+        );
+    }
+
+    #[test]
+    fn test_variant_moves_stay_on_board() {
+        let board =
+            Board::from_sfen_with_variant("4k/5/5/5/R3K b BGp 1".to_string(), Variant::Minishogi);
+        let playable = Variant::Minishogi.playable();
+        for color in [ColorType::Black, ColorType::White] {
+            for mv in board.search_moves(color) {
+                let to_bit = BitBoard::from_u128(1u128 << (127 - mv.get_to().to_index()));
+                assert_ne!(to_bit & playable, BitBoard::new(), "{}", mv);
+                if mv.get_is_promote() {
+                    let from_bit = BitBoard::from_u128(1u128 << (127 - mv.get_from().to_index()));
+                    let zone = Variant::Minishogi.pro_zone(color);
+                    assert!(((to_bit | from_bit) & zone) != BitBoard::new());
+                }
+            }
+        }
+        // 後手の歩は1段目には打てない（空きマス22から1段目の3マスを除く）
+        let pawn_drops = board.get_able_drop_squares(ColorType::White, PieceType::Pawn);
+        assert_eq!(
+            pawn_drops & Variant::Minishogi.last1_zone(ColorType::White),
+            BitBoard::new()
+        );
+        assert_eq!(pawn_drops.count_ones(), 19);
+    }
+
+    #[test]
+    fn test_variant_perft_standard() {
+        let mut board = Board::new();
+        board.startpos();
+        assert_eq!(board.perft(ColorType::Black, 1), 30);
+        assert_eq!(board.perft(ColorType::Black, 2), 900);
+        assert_eq!(board.perft(ColorType::Black, 3), 25470);
+    }
+
+    #[test]
+    fn test_variant_perft_minishogi() {
+        let game = Game::from_variant(Variant::Minishogi);
+        assert_eq!(game.board.perft(game.turn, 1), 14);
+        assert_eq!(game.board.perft(game.turn, 2), 181);
+        assert_eq!(game.board.perft(game.turn, 3), 2512);
+    }
+
+    #[test]
+    fn test_variant_random_play() {
+        for variant in [Variant::Minishogi, Variant::Judkins, Variant::GoroGoro] {
+            let mut game = Game::from_variant(variant);
+            let result = game.random_play();
+            assert!(result.board.is_consistent());
+            assert_eq!(result.board.variant, variant);
+            assert!(result.move_number <= 500);
+        }
+    }
+}
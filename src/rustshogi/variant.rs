@@ -0,0 +1,196 @@
+use super::address::Address;
+use super::bitboard::BitBoard;
+use super::color::ColorType;
+
+use once_cell::sync::Lazy;
+use pyo3::prelude::*;
+
+/// 盤の大きさと初期配置が異なる将棋の種類
+///
+/// どの種類も11x11の枠付きビットボードの左下（1筋1段）から詰めて置く。
+#[pyclass(eq, eq_int)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash, Default)]
+pub enum Variant {
+    /// 本将棋（9x9）
+    #[default]
+    Standard = 0,
+    /// 5五将棋（5x5）
+    Minishogi,
+    /// ジャドケンズ将棋（6x6）
+    Judkins,
+    /// ごろごろ将棋（5筋6段）
+    GoroGoro,
+}
+
+/// 種類ごとに前もって計算しておくマスの集合
+#[derive(Clone, Copy, Debug)]
+pub struct Geometry {
+    /// 駒を置けるマス
+    pub playable: BitBoard,
+    /// 手番ごとの成れるマス
+    pub pro_zone: [BitBoard; 2],
+    /// 手番ごとの最も奥の1段
+    pub last1_zone: [BitBoard; 2],
+    /// 手番ごとの奥から2段
+    pub last2_zone: [BitBoard; 2],
+}
+
+static GEOMETRIES: Lazy<[Geometry; 4]> = Lazy::new(|| Variant::ALL.map(Geometry::new));
+
+/// `first` 段から `last` 段までの、盤上にあるマス
+fn rows(variant: Variant, first: u8, last: u8) -> BitBoard {
+    let mut bits = 0u128;
+    for row in first..=last {
+        for column in 1..=variant.width() {
+            bits |= 1u128 << (127 - Address::from_numbers(column, row).to_index());
+        }
+    }
+    BitBoard::from_u128(bits)
+}
+
+impl Geometry {
+    fn new(variant: Variant) -> Self {
+        let height = variant.height();
+        let ranks = variant.promotion_ranks();
+        // 先手は段の大きい側へ進む
+        Self {
+            playable: rows(variant, 1, height),
+            pro_zone: [
+                rows(variant, height - ranks + 1, height),
+                rows(variant, 1, ranks),
+            ],
+            last1_zone: [rows(variant, height, height), rows(variant, 1, 1)],
+            last2_zone: [rows(variant, height - 1, height), rows(variant, 1, 2)],
+        }
+    }
+}
+
+impl Variant {
+    pub const ALL: [Variant; 4] = [
+        Variant::Standard,
+        Variant::Minishogi,
+        Variant::Judkins,
+        Variant::GoroGoro,
+    ];
+
+    /// 筋の数
+    pub fn width(&self) -> u8 {
+        match self {
+            Variant::Standard => 9,
+            Variant::Minishogi => 5,
+            Variant::Judkins => 6,
+            Variant::GoroGoro => 5,
+        }
+    }
+
+    /// 段の数
+    pub fn height(&self) -> u8 {
+        match self {
+            Variant::Standard => 9,
+            Variant::Minishogi => 5,
+            Variant::Judkins => 6,
+            Variant::GoroGoro => 6,
+        }
+    }
+
+    /// 敵陣として成れる段の数
+    pub fn promotion_ranks(&self) -> u8 {
+        match self {
+            Variant::Standard => 3,
+            Variant::Minishogi => 1,
+            Variant::Judkins => 2,
+            Variant::GoroGoro => 2,
+        }
+    }
+
+    /// 初期局面の盤面部分のSFEN
+    pub fn board_sfen(&self) -> &'static str {
+        match self {
+            Variant::Standard => "lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL",
+            Variant::Minishogi => "rbsgk/4p/5/P4/KGSBR",
+            Variant::Judkins => "rbnsgk/5p/6/6/P5/KGSNBR",
+            Variant::GoroGoro => "sgkgs/5/1ppp1/1PPP1/5/SGKGS",
+        }
+    }
+
+    /// 手番と持ち駒を含めた初期局面のSFEN
+    pub fn sfen(&self) -> String {
+        format!("{} b - 1", self.board_sfen())
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Variant::Standard => "standard",
+            Variant::Minishogi => "minishogi",
+            Variant::Judkins => "judkins",
+            Variant::GoroGoro => "gorogoro",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Variant> {
+        let normalized = name
+            .trim()
+            .to_ascii_lowercase()
+            .replace(['_', ' ', '-'], "");
+        Self::ALL
+            .into_iter()
+            .find(|variant| variant.get_name() == normalized)
+    }
+
+    pub fn geometry(&self) -> &'static Geometry {
+        &GEOMETRIES[*self as usize]
+    }
+
+    pub fn playable(&self) -> BitBoard {
+        self.geometry().playable
+    }
+
+    pub fn pro_zone(&self, color: ColorType) -> BitBoard {
+        self.geometry().pro_zone[color as usize]
+    }
+
+    pub fn last1_zone(&self, color: ColorType) -> BitBoard {
+        self.geometry().last1_zone[color as usize]
+    }
+
+    pub fn last2_zone(&self, color: ColorType) -> BitBoard {
+        self.geometry().last2_zone[color as usize]
+    }
+}
+
+#[pymethods]
+impl Variant {
+    #[staticmethod]
+    #[pyo3(name = "from_name")]
+    pub fn python_from_name(name: &str) -> PyResult<Variant> {
+        Self::from_name(name).ok_or_else(|| {
+            pyo3::exceptions::PyValueError::new_err(format!("unknown variant: {}", name))
+        })
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!("<Variant.{}: {}>", self.get_name(), *self as u8)
+    }
+
+    #[getter]
+    pub fn name(&self) -> String {
+        self.get_name().to_string()
+    }
+
+    #[getter]
+    #[pyo3(name = "width")]
+    pub fn python_width(&self) -> u8 {
+        self.width()
+    }
+
+    #[getter]
+    #[pyo3(name = "height")]
+    pub fn python_height(&self) -> u8 {
+        self.height()
+    }
+
+    #[pyo3(name = "sfen")]
+    pub fn python_sfen(&self) -> String {
+        self.sfen()
+    }
+}
@@ -0,0 +1,58 @@
+use super::color::ColorType;
+use super::piece::{NOT_PRO_PIECE_TYPE_NUMBER, PIECE_TYPE_NUMBER};
+
+use once_cell::sync::Lazy;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// 持ち駒の枚数の上限（歩の18枚）
+const MAX_HAND_COUNT: usize = 18;
+
+/// 局面のハッシュ値を計算するための乱数表
+pub struct ZobristTable {
+    board: [[[u64; 128]; PIECE_TYPE_NUMBER as usize]; ColorType::ColorNumber as usize],
+    hand: [[[u64; MAX_HAND_COUNT + 1]; NOT_PRO_PIECE_TYPE_NUMBER as usize + 1];
+        ColorType::ColorNumber as usize],
+    /// 後手番の局面に加える値
+    pub turn: u64,
+}
+
+impl ZobristTable {
+    fn new() -> Self {
+        // 実行ごとに同じ値になるよう固定のシードを使う
+        let mut rng = StdRng::seed_from_u64(0x5348_4f47_4921);
+        let mut table = Self {
+            board: [[[0; 128]; PIECE_TYPE_NUMBER as usize]; ColorType::ColorNumber as usize],
+            hand: [[[0; MAX_HAND_COUNT + 1]; NOT_PRO_PIECE_TYPE_NUMBER as usize + 1];
+                ColorType::ColorNumber as usize],
+            turn: 0,
+        };
+        for color in table.board.iter_mut() {
+            for piece in color.iter_mut() {
+                for key in piece.iter_mut() {
+                    *key = rng.gen();
+                }
+            }
+        }
+        for color in table.hand.iter_mut() {
+            // 0枚は値を加えない
+            for piece in color.iter_mut() {
+                for key in piece.iter_mut().skip(1) {
+                    *key = rng.gen();
+                }
+            }
+        }
+        table.turn = rng.gen();
+        table
+    }
+
+    pub fn board_key(&self, color: ColorType, piece_type: usize, index: u8) -> u64 {
+        self.board[color as usize][piece_type][index as usize]
+    }
+
+    pub fn hand_key(&self, color: ColorType, piece_type: usize, count: u8) -> u64 {
+        self.hand[color as usize][piece_type][(count as usize).min(MAX_HAND_COUNT)]
+    }
+}
+
+pub static ZOBRIST: Lazy<ZobristTable> = Lazy::new(ZobristTable::new);